@@ -1,9 +1,9 @@
 use crate::ClippyConfiguration;
-use crate::msrvs::Msrv;
+use crate::msrvs::{self, Msrv};
 use crate::types::{
-    DisallowedPath, MacroMatcher, MatchLintBehaviour, PubUnderscoreFieldsBehaviour, Rename, SourceItemOrdering,
-    SourceItemOrderingCategory, SourceItemOrderingModuleItemGroupings, SourceItemOrderingModuleItemKind,
-    SourceItemOrderingTraitAssocItemKind, SourceItemOrderingTraitAssocItemKinds,
+    CustomTry, DisallowedPath, MacroMatcher, MatchLintBehaviour, PubUnderscoreFieldsBehaviour, QuestionMarkVisibility,
+    Rename, SourceItemOrdering, SourceItemOrderingCategory, SourceItemOrderingModuleItemGroupings,
+    SourceItemOrderingModuleItemKind, SourceItemOrderingTraitAssocItemKind, SourceItemOrderingTraitAssocItemKinds,
 };
 use rustc_errors::Applicability;
 use rustc_session::Session;
@@ -81,14 +81,22 @@ struct TryConf {
     conf: Conf,
     errors: Vec<ConfError>,
     warnings: Vec<ConfError>,
+    /// The checks of the options set in the file, along with the spans of their keys.
+    validations: Vec<(Span, Validation)>,
 }
 
+/// Checks an option against the whole configuration, returning a warning for each setting which has
+/// no effect or contradicts another one. It's registered on the option with `#[validate(..)]`, and
+/// only runs if the option is set in the configuration file.
+type Validation = fn(&Conf) -> Vec<String>;
+
 impl TryConf {
     fn from_toml_error(file: &SourceFile, error: &toml::de::Error) -> Self {
         Self {
             conf: Conf::default(),
             errors: vec![ConfError::from_toml(file, error)],
             warnings: vec![],
+            validations: vec![],
         }
     }
 }
@@ -115,16 +123,20 @@ fn spanned(
         Self {
             message: message.into(),
             suggestion,
-            span: Span::new(
-                file.start_pos + BytePos::from_usize(span.start),
-                file.start_pos + BytePos::from_usize(span.end),
-                SyntaxContext::root(),
-                None,
-            ),
+            span: file_span(file, span),
         }
     }
 }
 
+fn file_span(file: &SourceFile, span: Range<usize>) -> Span {
+    Span::new(
+        file.start_pos + BytePos::from_usize(span.start),
+        file.start_pos + BytePos::from_usize(span.end),
+        SyntaxContext::root(),
+        None,
+    )
+}
+
 // Remove code tags and code behind '# 's, as they are not needed for the lint docs and --explain
 pub fn sanitize_explanation(raw_docs: &str) -> String {
     // Remove tags and hidden code:
@@ -142,6 +154,14 @@ pub fn sanitize_explanation(raw_docs: &str) -> String {
                 explanation.push('\n');
             }
             in_code = !in_code;
+        } else if !in_code
+            && line.starts_with('#')
+            && line.ends_with('}')
+            && let Some((heading, _)) = line.rsplit_once(" {#")
+        {
+            // Remove the anchors of headings, which are only used by the lint list
+            explanation += heading;
+            explanation.push('\n');
         } else if !(in_code && line.starts_with("# ")) {
             explanation += line;
             explanation.push('\n');
@@ -176,6 +196,7 @@ macro_rules! define_Conf {
         $(#[doc = $doc:literal])+
         $(#[conf_deprecated($dep:literal, $new_conf:ident)])?
         $(#[default_text = $default_text:expr])?
+        $(#[validate($validate:path)])?
         $(#[lints($($for_lints:ident),* $(,)?)])?
         $name:ident: $ty:ty = $default:expr,
     )*) => {
@@ -212,7 +233,10 @@ fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
             fn visit_map<V>(self, mut map: V) -> Result<Self::Value, V::Error> where V: MapAccess<'de> {
                 let mut errors = Vec::new();
                 let mut warnings = Vec::new();
+                let mut validations = Vec::new();
                 $(let mut $name = None;)*
+                // the spans of the deprecated keys which were used, to report conflicts with their replacements
+                let mut deprecated_spans = Vec::new();
                 // could get `Field` here directly, but get `String` first for diagnostics
                 while let Some(name) = map.next_key::<toml::Spanned<String>>()? {
                     match Field::deserialize(name.get_ref().as_str().into_deserializer()) {
@@ -232,15 +256,8 @@ fn visit_map<V>(self, mut map: V) -> Result<Self::Value, V::Error> where V: MapA
                                     }
                                     None => {
                                         $name = Some(value);
-                                        // $new_conf is the same as one of the defined `$name`s, so
-                                        // this variable is defined in line 2 of this function.
-                                        $(match $new_conf {
-                                            Some(_) => errors.push(ConfError::spanned(self.0, concat!(
-                                                "duplicate field `", stringify!($new_conf),
-                                                "` (provided as `", stringify!($name), "`)"
-                                            ), None, name.span())),
-                                            None => $new_conf = $name.clone(),
-                                        })?
+                                        $(deprecated_spans.push((stringify!($new_conf), name.span()));)?
+                                        $(validations.push((file_span(self.0, name.span()), $validate as Validation));)?
                                     },
                                 }
                             }
@@ -249,8 +266,28 @@ fn visit_map<V>(self, mut map: V) -> Result<Self::Value, V::Error> where V: MapA
                         Ok(Field::third_party) => drop(map.next_value::<IgnoredAny>())
                     }
                 }
+                // Deprecated keys are aliases of their replacements, and may only be given along with
+                // them if both have the same value. $new_conf is the same as one of the defined
+                // `$name`s, so this variable is defined at the start of this function.
+                $($(if let Some(value) = &$name {
+                    match &$new_conf {
+                        Some(new_value) if new_value != value => {
+                            let (_, span) = deprecated_spans
+                                .iter()
+                                .find(|(new_conf, _)| *new_conf == stringify!($new_conf))
+                                .expect("deprecated keys record their span");
+                            errors.push(ConfError::spanned(self.0, format!(
+                                "conflicting values for `{}` and its deprecated name `{}`",
+                                stringify!($new_conf).replace('_', "-"),
+                                stringify!($name).replace('_', "-"),
+                            ), None, span.clone()));
+                        },
+                        Some(_) => {},
+                        None => $new_conf = Some(value.clone()),
+                    }
+                })?)*
                 let conf = Conf { $($name: $name.unwrap_or_else(defaults::$name),)* };
-                Ok(TryConf { conf, errors, warnings })
+                Ok(TryConf { conf, errors, warnings, validations })
             }
         }
 
@@ -535,7 +572,17 @@ pub fn get_configuration_metadata() -> Vec<ClippyConfiguration> {
     /// Whether the matches should be considered by the lint, and whether there should
     /// be filtering for common types.
     #[lints(manual_let_else)]
+    match_lint_behaviour_let_else: MatchLintBehaviour = MatchLintBehaviour::WellKnownTypes,
+    /// DEPRECATED CONFIGURATION: MATCHES_FOR_LET_ELSE.
+    ///
+    /// Use `match-lint-behaviour-let-else` instead.
+    #[conf_deprecated("Please use `match-lint-behaviour-let-else` instead", match_lint_behaviour_let_else)]
     matches_for_let_else: MatchLintBehaviour = MatchLintBehaviour::WellKnownTypes,
+    /// Whether `match` expressions should be rewritten to use the `?` operator, and whether a
+    /// wildcard pattern is accepted for the `None` arm. Possible values are `"Never"`,
+    /// `"WellKnownTypes"` and `"AllTypes"`.
+    #[lints(manual_let_else, question_mark_match)]
+    matches_for_question_mark: MatchLintBehaviour = MatchLintBehaviour::WellKnownTypes,
     /// The maximum number of bool parameters a function can have
     #[lints(fn_params_excessive_bools)]
     max_fn_params_bools: u64 = 3,
@@ -611,6 +658,8 @@ pub fn get_configuration_metadata() -> Vec<ClippyConfiguration> {
         option_as_ref_deref,
         option_map_unwrap_or,
         ptr_as_ptr,
+        question_mark,
+        question_mark_match,
         redundant_field_names,
         redundant_static_lifetimes,
         seek_from_current,
@@ -633,6 +682,68 @@ pub fn get_configuration_metadata() -> Vec<ClippyConfiguration> {
     /// exported visibility, or whether they are marked as "pub".
     #[lints(pub_underscore_fields)]
     pub_underscore_fields_behavior: PubUnderscoreFieldsBehaviour = PubUnderscoreFieldsBehaviour::PubliclyExported,
+    /// Types implementing `Try` which `question_mark` treats like `Result`, along with the method checking
+    /// for their failure case. Guards such as `if v.is_invalid() { return v; }` are then rewritten to `v?;`.
+    ///
+    /// #### Example
+    ///
+    /// ```toml
+    /// question-mark-custom-try = [ { type = "validated::Validated", is-failure = "is_invalid" } ]
+    /// ```
+    #[validate(validate_question_mark_custom_try)]
+    #[lints(question_mark)]
+    question_mark_custom_try: Vec<CustomTry> = Vec::new(),
+    /// Whether to emit a single diagnostic per function containing all of its suggested rewrites,
+    /// instead of one diagnostic per rewritten block.
+    #[lints(question_mark, question_mark_match)]
+    question_mark_group_per_fn: bool = false,
+    /// Whether `question_mark` should lint `let...else` statements returning `None`, suggesting
+    /// `let x = e?;` instead of `let Some(x) = e else { return None };`.
+    #[validate(validate_question_mark_lint_let_else)]
+    #[lints(question_mark)]
+    question_mark_lint_let_else: bool = true,
+    /// Whether `question_mark` should only emit diagnostics whose suggestion is machine applicable,
+    /// skipping the ones which `cargo clippy --fix` wouldn't apply.
+    #[lints(question_mark, question_mark_match)]
+    question_mark_machine_applicable_only: bool = false,
+    /// Whether `question_mark` should skip functions marked `#[no_mangle]`, which are usually called
+    /// through FFI. Functions with a non-Rust ABI, such as `extern "C" fn`, are always skipped.
+    #[lints(question_mark, question_mark_match)]
+    question_mark_skip_no_mangle: bool = false,
+    /// Whether `question_mark` should also lint guards such as `if flag && x.is_none() { return None; }`,
+    /// suggesting `if flag { x?; }` instead.
+    #[lints(question_mark)]
+    question_mark_split_conditions: bool = false,
+    /// Whether `question_mark` should also lint guards whose condition was hoisted into a local, such as
+    /// `let missing = x.is_none(); if missing { return None; }`, suggesting `x?;` and removing the local.
+    #[lints(question_mark)]
+    question_mark_track_condition_locals: bool = true,
+    /// Whether `question_mark_used` should allow the `?` operator inside of `try` blocks, which
+    /// capture the residual rather than propagating it out of the function.
+    #[lints(question_mark_used)]
+    question_mark_used_allow_in_try_blocks: bool = false,
+    /// List of trait paths whose implementations may use the `?` operator despite
+    /// `question_mark_used`, e.g. `core::fmt::Display`. `question_mark` keeps linting inside of them.
+    ///
+    /// #### Example
+    ///
+    /// ```toml
+    /// question-mark-used-allowed-traits = [ "core::fmt::Display", "core::fmt::Debug" ]
+    /// ```
+    #[validate(validate_question_mark_used_allowed_traits)]
+    #[lints(manual_let_else, question_mark, question_mark_match, question_mark_used)]
+    question_mark_used_allowed_traits: Vec<String> = Vec::new(),
+    /// Whether `question_mark_used` should suggest replacing the `?` operator with `.expect()` for
+    /// `Result`s and `.unwrap()` for `Option`s in test code, where panicking points at the failure
+    /// location. Tests returning a `Result` keep using `?`. The lint still has to be enabled for the
+    /// tests.
+    #[lints(question_mark_used)]
+    question_mark_used_in_tests_suggest_expect: bool = false,
+    /// Which functions `question_mark` lints, based on their effective visibility. Possible values are
+    /// `"all"`, `"public-only"` and `"private-only"`. Closures are linted according to the function
+    /// containing them.
+    #[lints(manual_let_else, question_mark, question_mark_match)]
+    question_mark_visibility: QuestionMarkVisibility = QuestionMarkVisibility::All,
     /// Whether to lint only if it's multiline.
     #[lints(semicolon_inside_block)]
     semicolon_inside_block_ignore_singleline: bool = false,
@@ -658,6 +769,10 @@ pub fn get_configuration_metadata() -> Vec<ClippyConfiguration> {
     /// The minimum number of struct fields for the lints about field names to trigger
     #[lints(struct_field_names)]
     struct_field_name_threshold: u64 = 3,
+    /// The maximum width of the lines suggested by `manual_let_else` and `question_mark`. Longer
+    /// `let` statements are split across lines, the way rustfmt formats them with the same `max_width`.
+    #[lints(manual_let_else, question_mark)]
+    suggestion_max_width: u64 = 100,
     /// Whether to suppress a restriction lint in constant code. In same
     /// cases the restructured operation might not be unavoidable, as the
     /// suggested counterparts are unavailable in constant code. This
@@ -794,6 +909,57 @@ fn extend_vec_if_indicator_present(vec: &mut Vec<String>, default: &[&str]) {
     }
 }
 
+fn is_ident(s: &str) -> bool {
+    let mut chars = s.chars();
+    chars.next().is_some_and(|c| c == '_' || c.is_alphabetic())
+        && chars.all(|c| c == '_' || c.is_alphanumeric())
+        && s != "_"
+}
+
+fn is_path(s: &str) -> bool {
+    s.split("::").all(is_ident)
+}
+
+fn validate_question_mark_custom_try(conf: &Conf) -> Vec<String> {
+    let mut warnings = Vec::new();
+    for custom_try in &conf.question_mark_custom_try {
+        if !is_path(&custom_try.path) {
+            warnings.push(format!(
+                "`{}` is not a type path, so this entry is ignored",
+                custom_try.path
+            ));
+        }
+        if !is_ident(&custom_try.is_failure) {
+            warnings.push(format!(
+                "`{}` is not a method name, so this entry is ignored",
+                custom_try.is_failure
+            ));
+        }
+    }
+    warnings
+}
+
+fn validate_question_mark_lint_let_else(conf: &Conf) -> Vec<String> {
+    if conf.question_mark_lint_let_else
+        && let Some(msrv) = conf.msrv.current()
+        && msrv < msrvs::LET_ELSE
+    {
+        vec![format!(
+            "`let...else` isn't available in Rust {msrv}, so `question-mark-lint-let-else` has no effect"
+        )]
+    } else {
+        Vec::new()
+    }
+}
+
+fn validate_question_mark_used_allowed_traits(conf: &Conf) -> Vec<String> {
+    conf.question_mark_used_allowed_traits
+        .iter()
+        .filter(|path| !is_path(path))
+        .map(|path| format!("`{path}` is not a trait path, so it is ignored"))
+        .collect()
+}
+
 impl Conf {
     pub fn read(sess: &Session, path: &io::Result<(Option<PathBuf>, Vec<String>)>) -> &'static Conf {
         static CONF: OnceLock<Conf> = OnceLock::new();
@@ -816,7 +982,8 @@ fn read_inner(sess: &Session, path: &io::Result<(Option<PathBuf>, Vec<String>)>)
         let TryConf {
             mut conf,
             errors,
-            warnings,
+            mut warnings,
+            validations,
         } = match path {
             Ok((Some(path), _)) => match sess.source_map().load_file(path) {
                 Ok(file) => deserialize(&file),
@@ -830,6 +997,15 @@ fn read_inner(sess: &Session, path: &io::Result<(Option<PathBuf>, Vec<String>)>)
 
         conf.msrv.read_cargo(sess);
 
+        // The options are validated once the MSRV from `Cargo.toml` is known
+        for (span, validate) in validations {
+            warnings.extend(validate(&conf).into_iter().map(|message| ConfError {
+                message,
+                suggestion: None,
+                span,
+            }));
+        }
+
         // all conf errors are non-fatal, we just use the default conf in case of error
         for error in errors {
             let mut diag = sess.dcx().struct_span_err(
@@ -987,4 +1163,13 @@ fn configs_are_tested() {
             "Configuration variable lacks test: {names:?}\nAdd a test to `tests/ui-toml`"
         );
     }
+
+    #[test]
+    fn heading_anchors_are_removed() {
+        let docs = " ### Example\n #### `if` guards {#question_mark-if}\n ```ignore\n let x = S {#[a] b: 1};\n ```\n";
+        assert_eq!(
+            crate::sanitize_explanation(docs),
+            "### Example\n#### `if` guards\n```rust\nlet x = S {#[a] b: 1};\n```\n"
+        );
+    }
 }
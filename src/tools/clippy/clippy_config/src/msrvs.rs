@@ -1,7 +1,7 @@
 use rustc_ast::Attribute;
 use rustc_attr::parse_version;
 use rustc_session::{RustcVersion, Session};
-use rustc_span::{Symbol, sym};
+use rustc_span::{Span, Symbol, sym};
 use serde::Deserialize;
 use smallvec::{SmallVec, smallvec};
 use std::fmt;
@@ -52,7 +52,7 @@ macro_rules! msrv_aliases {
     1,36,0 { ITERATOR_COPIED }
     1,35,0 { OPTION_COPIED, RANGE_CONTAINS }
     1,34,0 { TRY_FROM }
-    1,33,0 { UNDERSCORE_IMPORTS }
+    1,33,0 { UNDERSCORE_IMPORTS, OPTION_TRANSPOSE }
     1,30,0 { ITERATOR_FIND_MAP, TOOL_ATTRIBUTES }
     1,29,0 { ITER_FLATTEN }
     1,28,0 { FROM_BOOL, REPEAT_WITH }
@@ -69,6 +69,14 @@ macro_rules! msrv_aliases {
 #[derive(Debug, Clone)]
 pub struct Msrv {
     stack: SmallVec<[RustcVersion; 2]>,
+    /// How many of the versions on `stack` were set via `#[clippy::msrv]`.
+    attr_depth: usize,
+    /// The spans of the nodes which set the MSRV via `#[clippy::msrv]`, together with their depth,
+    /// in the order they were visited, see [`Msrv::meets_at`]. Only the ancestors of the current
+    /// node and their children are kept.
+    attr_spans: Vec<(usize, Span, RustcVersion)>,
+    /// How many of the nodes passed to [`Msrv::check_attributes_at`] are currently entered.
+    node_depth: usize,
 }
 
 impl fmt::Display for Msrv {
@@ -88,14 +96,24 @@ fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     {
         let v = String::deserialize(deserializer)?;
         parse_version(Symbol::intern(&v))
-            .map(|v| Msrv { stack: smallvec![v] })
+            .map(|v| Msrv {
+                stack: smallvec![v],
+                attr_depth: 0,
+                attr_spans: Vec::new(),
+                node_depth: 0,
+            })
             .ok_or_else(|| serde::de::Error::custom("not a valid Rust version"))
     }
 }
 
 impl Msrv {
     pub fn empty() -> Msrv {
-        Msrv { stack: SmallVec::new() }
+        Msrv {
+            stack: SmallVec::new(),
+            attr_depth: 0,
+            attr_spans: Vec::new(),
+            node_depth: 0,
+        }
     }
 
     pub fn read_cargo(&mut self, sess: &Session) {
@@ -124,6 +142,60 @@ pub fn meets(&self, required: RustcVersion) -> bool {
         self.current().map_or(true, |msrv| msrv >= required)
     }
 
+    /// Checks if the MSRV in effect at `span` meets `required`. Unlike [`Msrv::meets`], this can be
+    /// asked about the children of the current node and its ancestors after they were left, as long
+    /// as the attributes were passed to [`Msrv::check_attributes_at`].
+    pub fn meets_at(&self, span: Span, required: RustcVersion) -> bool {
+        // Nodes are visited before the nodes inside of them, so the last match is the innermost one
+        self.attr_spans
+            .iter()
+            .rev()
+            .find(|(_, attr_span, _)| attr_span.contains(span))
+            .map(|&(_, _, msrv)| msrv)
+            .or_else(|| self.configured())
+            .map_or(true, |msrv| msrv >= required)
+    }
+
+    /// The MSRV from `clippy.toml` or `Cargo.toml`, ignoring any `#[clippy::msrv]` attributes.
+    fn configured(&self) -> Option<RustcVersion> {
+        if self.stack.len() > self.attr_depth {
+            self.stack.first().copied()
+        } else {
+            None
+        }
+    }
+
+    fn push_attr(&mut self, version: RustcVersion, span: Option<Span>) {
+        self.stack.push(version);
+        self.attr_depth += 1;
+        if let Some(span) = span {
+            self.attr_spans.push((self.node_depth, span, version));
+        }
+    }
+
+    fn enter_node(&mut self, version: Option<RustcVersion>, span: Span) {
+        self.node_depth += 1;
+        if let Some(version) = version {
+            self.push_attr(version, Some(span));
+        }
+    }
+
+    fn leave_node(&mut self, had_attr: bool) {
+        if had_attr {
+            self.stack.pop();
+            self.attr_depth -= 1;
+        }
+        // The nodes inside of the one being left can't be asked about anymore
+        while self
+            .attr_spans
+            .last()
+            .is_some_and(|&(depth, ..)| depth > self.node_depth)
+        {
+            self.attr_spans.pop();
+        }
+        self.node_depth -= 1;
+    }
+
     fn parse_attr(sess: &Session, attrs: &[Attribute]) -> Option<RustcVersion> {
         let sym_msrv = Symbol::intern("msrv");
         let mut msrv_attrs = attrs.iter().filter(|attr| attr.path_matches(&[sym::clippy, sym_msrv]));
@@ -153,13 +225,119 @@ fn parse_attr(sess: &Session, attrs: &[Attribute]) -> Option<RustcVersion> {
 
     pub fn check_attributes(&mut self, sess: &Session, attrs: &[Attribute]) {
         if let Some(version) = Self::parse_attr(sess, attrs) {
-            self.stack.push(version);
+            self.push_attr(version, None);
         }
     }
 
+    /// Like [`Msrv::check_attributes`], but also records `span`, the span of the node `attrs`
+    /// belong to, for [`Msrv::meets_at`].
+    pub fn check_attributes_at(&mut self, sess: &Session, attrs: &[Attribute], span: Span) {
+        self.enter_node(Self::parse_attr(sess, attrs), span);
+    }
+
+    /// The counterpart of [`Msrv::check_attributes_at`], which has to be used instead of
+    /// [`Msrv::check_attributes_post`] for the nodes passed to it.
+    pub fn check_attributes_post_at(&mut self, sess: &Session, attrs: &[Attribute]) {
+        self.leave_node(Self::parse_attr(sess, attrs).is_some());
+    }
+
     pub fn check_attributes_post(&mut self, sess: &Session, attrs: &[Attribute]) {
         if Self::parse_attr(sess, attrs).is_some() {
             self.stack.pop();
+            self.attr_depth -= 1;
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustc_span::{BytePos, create_default_session_globals_then};
+
+    const V1_50: RustcVersion = RustcVersion {
+        major: 1,
+        minor: 50,
+        patch: 0,
+    };
+    const V1_60: RustcVersion = RustcVersion {
+        major: 1,
+        minor: 60,
+        patch: 0,
+    };
+    const V1_70: RustcVersion = RustcVersion {
+        major: 1,
+        minor: 70,
+        patch: 0,
+    };
+
+    fn span(lo: u32, hi: u32) -> Span {
+        Span::with_root_ctxt(BytePos(lo), BytePos(hi))
+    }
+
+    fn configured(version: RustcVersion) -> Msrv {
+        let mut msrv = Msrv::empty();
+        msrv.stack.push(version);
+        msrv
+    }
+
+    #[test]
+    fn meets_at_uses_innermost_attribute() {
+        create_default_session_globals_then(|| {
+            let mut msrv = configured(V1_70);
+            msrv.enter_node(Some(V1_50), span(0, 100));
+            msrv.enter_node(Some(V1_60), span(10, 20));
+
+            assert!(msrv.meets_at(span(12, 15), V1_60));
+            assert!(!msrv.meets_at(span(12, 15), V1_70));
+            assert!(!msrv.meets_at(span(30, 40), V1_60));
+            assert!(msrv.meets_at(span(200, 210), V1_70));
+        });
+    }
+
+    #[test]
+    fn meets_at_after_leaving_node() {
+        create_default_session_globals_then(|| {
+            let mut msrv = configured(V1_70);
+            msrv.enter_node(Some(V1_50), span(10, 20));
+            msrv.leave_node(true);
+
+            assert!(msrv.meets(V1_70));
+            assert!(!msrv.meets_at(span(12, 15), V1_60));
+            assert!(msrv.meets_at(span(30, 40), V1_70));
+        });
+    }
+
+    #[test]
+    fn meets_at_without_configured_msrv() {
+        create_default_session_globals_then(|| {
+            let mut msrv = Msrv::empty();
+            msrv.enter_node(Some(V1_50), span(10, 20));
+
+            assert!(!msrv.meets_at(span(12, 15), V1_60));
+            assert!(msrv.meets_at(span(30, 40), V1_70));
+        });
+    }
+
+    #[test]
+    fn meets_at_forgets_nested_nodes() {
+        create_default_session_globals_then(|| {
+            let mut msrv = configured(V1_70);
+            msrv.enter_node(None, span(0, 100));
+            msrv.enter_node(Some(V1_50), span(10, 50));
+            msrv.enter_node(Some(V1_60), span(20, 30));
+            msrv.leave_node(true);
+
+            // The children of the current node are still known after they were left
+            assert!(!msrv.meets_at(span(22, 25), V1_70));
+            assert_eq!(msrv.attr_spans.len(), 2);
+
+            msrv.leave_node(true);
+            assert!(!msrv.meets_at(span(22, 25), V1_60));
+            assert_eq!(msrv.attr_spans.len(), 1);
+
+            msrv.leave_node(false);
+            assert!(msrv.attr_spans.is_empty());
+            assert!(msrv.meets_at(span(22, 25), V1_70));
+        });
+    }
+}
@@ -9,6 +9,18 @@ pub struct Rename {
     pub rename: String,
 }
 
+/// A type implementing `Try` which `question_mark` treats like `Result`, see
+/// `question-mark-custom-try`.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct CustomTry {
+    /// The path of the type, e.g. `validated::Validated`.
+    #[serde(rename = "type")]
+    pub path: String,
+    /// The method returning whether a value is the failure case, e.g. `is_invalid`.
+    pub is_failure: String,
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(untagged)]
 pub enum DisallowedPath {
@@ -421,6 +433,7 @@ fn serialize<S>(&self, _serializer: S) -> Result<S::Ok, S::Error>
 }
 
 unimplemented_serialize! {
+    CustomTry,
     DisallowedPath,
     Rename,
     MacroMatcher,
@@ -431,3 +444,12 @@ pub enum PubUnderscoreFieldsBehaviour {
     PubliclyExported,
     AllPubFields,
 }
+
+/// Which functions `question_mark` lints, based on their effective visibility.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum QuestionMarkVisibility {
+    All,
+    PublicOnly,
+    PrivateOnly,
+}
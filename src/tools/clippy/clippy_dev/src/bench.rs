@@ -0,0 +1,16 @@
+use crate::{clippy_project_root, exit_if_err};
+use std::process::Command;
+
+/// # Panics
+///
+/// Panics if unable to run the `question_mark` benchmark
+pub fn question_mark(copies: usize) {
+    let mut cmd = Command::new("cargo");
+
+    cmd.current_dir(clippy_project_root())
+        .args(["test", "--release", "--test", "question-mark-bench"])
+        .args(["--features", "internal"])
+        .env("QUESTION_MARK_BENCH_COPIES", copies.to_string());
+
+    exit_if_err(cmd.status());
+}
@@ -18,6 +18,7 @@
 use std::path::PathBuf;
 use std::process::{self, ExitStatus};
 
+pub mod bench;
 pub mod dogfood;
 pub mod fmt;
 pub mod lint;
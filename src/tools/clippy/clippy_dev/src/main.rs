@@ -3,7 +3,7 @@
 #![warn(rust_2018_idioms, unused_lifetimes)]
 
 use clap::{Args, Parser, Subcommand};
-use clippy_dev::{dogfood, fmt, lint, new_lint, serve, setup, update_lints};
+use clippy_dev::{bench, dogfood, fmt, lint, new_lint, serve, setup, update_lints};
 use std::convert::Infallible;
 
 fn main() {
@@ -19,6 +19,7 @@ fn main() {
             allow_staged,
         } => dogfood::dogfood(fix, allow_dirty, allow_staged),
         DevCommand::Fmt { check, verbose } => fmt::run(check, verbose),
+        DevCommand::BenchQuestionMark { copies } => bench::question_mark(copies),
         DevCommand::UpdateLints { print_only, check } => {
             if print_only {
                 update_lints::print_lints();
@@ -101,6 +102,13 @@ enum DevCommand {
         /// Fix code even if the working directory has staged changes
         allow_staged: bool,
     },
+    #[command(name = "bench-question-mark")]
+    /// Measures the cost of the `question_mark` pass on the fixtures in `tests/question_mark_bench`
+    BenchQuestionMark {
+        #[arg(long, default_value = "200")]
+        /// The number of times each fixture is included into the benchmarked crate
+        copies: usize,
+    },
     /// Run rustfmt on all projects and tests
     Fmt {
         #[arg(long)]
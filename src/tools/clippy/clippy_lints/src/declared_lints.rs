@@ -28,6 +28,8 @@
     #[cfg(feature = "internal")]
     crate::utils::internal_lints::produce_ice::PRODUCE_ICE_INFO,
     #[cfg(feature = "internal")]
+    crate::utils::internal_lints::question_mark_dump::QUESTION_MARK_DUMP_INFO,
+    #[cfg(feature = "internal")]
     crate::utils::internal_lints::slow_symbol_comparisons::SLOW_SYMBOL_COMPARISONS_INFO,
     #[cfg(feature = "internal")]
     crate::utils::internal_lints::unnecessary_def_path::UNNECESSARY_DEF_PATH_INFO,
@@ -622,6 +624,9 @@
     crate::pub_underscore_fields::PUB_UNDERSCORE_FIELDS_INFO,
     crate::pub_use::PUB_USE_INFO,
     crate::question_mark::QUESTION_MARK_INFO,
+    crate::question_mark::QUESTION_MARK_CANDIDATES_SUMMARY_INFO,
+    crate::question_mark::QUESTION_MARK_MATCH_INFO,
+    crate::question_mark::QUESTION_MARK_RECHECKED_INFO,
     crate::question_mark_used::QUESTION_MARK_USED_INFO,
     crate::ranges::MANUAL_RANGE_CONTAINS_INFO,
     crate::ranges::RANGE_MINUS_ONE_INFO,
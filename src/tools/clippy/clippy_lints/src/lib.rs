@@ -68,6 +68,9 @@
 pub mod ctfe; // Very important lint, do not remove (rust#125116)
 pub mod declared_lints;
 pub mod deprecated_lints;
+#[cfg(feature = "internal")]
+pub use question_mark::bench as question_mark_bench;
+pub use question_mark::CONF_OPTIONS as QUESTION_MARK_CONF_OPTIONS;
 
 // begin lints modules, do not remove this comment, it’s used in `update_lints`
 mod absolute_paths;
@@ -399,6 +402,7 @@
 
 use clippy_config::{Conf, get_configuration_metadata, sanitize_explanation};
 use clippy_utils::macros::FormatArgsStorage;
+use question_mark::QuestionMarkClaims;
 use rustc_data_structures::fx::FxHashSet;
 use rustc_lint::{Lint, LintId};
 use utils::attr_collector::{AttrCollector, AttrStorage};
@@ -418,6 +422,22 @@ pub fn register_pre_expansion_lints(store: &mut rustc_lint::LintStore, conf: &'s
     store.register_early_pass(move || Box::new(attrs::PostExpansionEarlyAttributes::new(conf)));
 }
 
+/// Register only the `question_mark` lints and their pass, to measure the cost of the pass on its
+/// own.
+///
+/// Used in `./tests/question-mark-bench.rs`.
+#[cfg(feature = "internal")]
+pub fn register_question_mark_lints(store: &mut rustc_lint::LintStore, conf: &'static Conf) {
+    store.register_lints(&question_mark::QuestionMark::get_lints());
+    store.register_late_pass(move |tcx| {
+        Box::new(question_mark::QuestionMark::new(
+            tcx,
+            conf,
+            QuestionMarkClaims::default(),
+        ))
+    });
+}
+
 #[derive(Default)]
 struct RegistrationGroups {
     all: Vec<LintId>,
@@ -525,8 +545,23 @@ pub fn category_str(&self) -> &'static str {
             Internal => "internal",
         }
     }
+
+    /// Returns the identifiers of the syntactic shapes recognized by the lint, if it tells them
+    /// apart (e.g. `if-let` and `match` for `question_mark`)
+    pub fn shapes(&self) -> &'static [&'static str] {
+        LINT_SHAPES
+            .iter()
+            .find(|(lint, _)| std::ptr::eq(*lint, self.lint))
+            .map_or(&[], |&(_, shapes)| shapes)
+    }
 }
 
+/// Lints listing the syntactic shapes they recognize in their metadata, see [`LintInfo::shapes`].
+static LINT_SHAPES: &[(&&Lint, &[&str])] = &[(
+    &question_mark::QUESTION_MARK,
+    &question_mark::QuestionMarkKind::SHAPES,
+)];
+
 pub fn explain(name: &str) -> i32 {
     let target = format!("clippy::{}", name.to_ascii_uppercase());
 
@@ -592,6 +627,8 @@ pub fn register_lints(store: &mut rustc_lint::LintStore, conf: &'static Conf) {
     let attrs = attr_storage.clone();
     store.register_early_pass(move || Box::new(AttrCollector::new(attrs.clone())));
 
+    let question_mark_claims = QuestionMarkClaims::default();
+
     // all the internal lints
     #[cfg(feature = "internal")]
     {
@@ -614,6 +651,9 @@ pub fn register_lints(store: &mut rustc_lint::LintStore, conf: &'static Conf) {
             Box::new(utils::internal_lints::almost_standard_lint_formulation::AlmostStandardFormulation::new())
         });
         store.register_late_pass(|_| Box::new(utils::internal_lints::slow_symbol_comparisons::SlowSymbolComparisons));
+        store.register_late_pass(move |tcx| {
+            Box::new(utils::internal_lints::question_mark_dump::QuestionMarkDump::new(tcx, conf))
+        });
     }
 
     store.register_late_pass(|_| Box::new(ctfe::ClippyCtfe));
@@ -629,7 +669,8 @@ pub fn register_lints(store: &mut rustc_lint::LintStore, conf: &'static Conf) {
     store.register_late_pass(|_| Box::new(float_literal::FloatLiteral));
     store.register_late_pass(|_| Box::new(ptr::Ptr));
     store.register_late_pass(|_| Box::new(needless_bool::NeedlessBool));
-    store.register_late_pass(|_| Box::new(needless_bool::BoolComparison));
+    let claims = question_mark_claims.clone();
+    store.register_late_pass(move |_| Box::new(needless_bool::BoolComparison::new(claims.clone())));
     store.register_late_pass(|_| Box::new(needless_for_each::NeedlessForEach));
     store.register_late_pass(|_| Box::new(misc::LintPass));
     store.register_late_pass(|_| Box::new(eta_reduction::EtaReduction));
@@ -652,7 +693,8 @@ pub fn register_lints(store: &mut rustc_lint::LintStore, conf: &'static Conf) {
     store.register_late_pass(move |_| Box::new(approx_const::ApproxConstant::new(conf)));
     let format_args = format_args_storage.clone();
     store.register_late_pass(move |_| Box::new(methods::Methods::new(conf, format_args.clone())));
-    store.register_late_pass(move |_| Box::new(matches::Matches::new(conf)));
+    let claims = question_mark_claims.clone();
+    store.register_late_pass(move |_| Box::new(matches::Matches::new(conf, claims.clone())));
     store.register_late_pass(move |_| Box::new(manual_non_exhaustive::ManualNonExhaustive::new(conf)));
     store.register_late_pass(move |_| Box::new(manual_strip::ManualStrip::new(conf)));
     store.register_early_pass(move || Box::new(redundant_static_lifetimes::RedundantStaticLifetimes::new(conf)));
@@ -726,8 +768,9 @@ pub fn register_lints(store: &mut rustc_lint::LintStore, conf: &'static Conf) {
     store.register_late_pass(|_| Box::<useless_conversion::UselessConversion>::default());
     store.register_late_pass(|_| Box::new(implicit_hasher::ImplicitHasher));
     store.register_late_pass(|_| Box::new(fallible_impl_from::FallibleImplFrom));
-    store.register_late_pass(move |_| Box::new(question_mark::QuestionMark::new(conf)));
-    store.register_late_pass(|_| Box::new(question_mark_used::QuestionMarkUsed));
+    let claims = question_mark_claims.clone();
+    store.register_late_pass(move |tcx| Box::new(question_mark::QuestionMark::new(tcx, conf, claims.clone())));
+    store.register_late_pass(move |tcx| Box::new(question_mark_used::QuestionMarkUsed::new(tcx, conf)));
     store.register_early_pass(|| Box::new(suspicious_operation_groupings::SuspiciousOperationGroupings));
     store.register_late_pass(|_| Box::new(suspicious_trait_impl::SuspiciousImpl));
     store.register_late_pass(|_| Box::new(map_unit_fn::MapUnit));
@@ -864,7 +907,7 @@ pub fn register_lints(store: &mut rustc_lint::LintStore, conf: &'static Conf) {
     store.register_late_pass(move |_| Box::new(operators::Operators::new(conf)));
     store.register_late_pass(move |_| Box::new(std_instead_of_core::StdReexports::new(conf)));
     store.register_late_pass(move |_| Box::new(instant_subtraction::InstantSubtraction::new(conf)));
-    store.register_late_pass(|_| Box::new(partialeq_to_none::PartialeqToNone));
+    store.register_late_pass(move |_| Box::new(partialeq_to_none::PartialeqToNone::new(question_mark_claims.clone())));
     store.register_late_pass(move |_| Box::new(manual_clamp::ManualClamp::new(conf)));
     store.register_late_pass(|_| Box::new(manual_string_new::ManualStringNew));
     store.register_late_pass(|_| Box::new(unused_peekable::UnusedPeekable));
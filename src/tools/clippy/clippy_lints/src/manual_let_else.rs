@@ -1,19 +1,28 @@
-use crate::question_mark::{QUESTION_MARK, QuestionMark};
+use crate::question_mark::{QuestionMark, prelude_path};
 use clippy_config::msrvs;
 use clippy_config::types::MatchLintBehaviour;
-use clippy_utils::diagnostics::span_lint_and_then;
-use clippy_utils::higher::IfLetOrMatch;
-use clippy_utils::source::snippet_with_context;
+use clippy_utils::diagnostics::span_lint_hir_and_then;
+use clippy_utils::higher::{self, IfLetOrMatch};
+use clippy_utils::source::{indent_of, reindent_multiline, snippet_with_context};
 use clippy_utils::ty::is_type_diagnostic_item;
-use clippy_utils::{is_lint_allowed, is_never_expr, pat_and_expr_can_be_question_mark, peel_blocks};
+use clippy_utils::visitors::{for_each_expr_without_closures, is_local_used};
+use clippy_utils::{
+    SpanlessEq, is_expr_untyped_identity_function, is_never_expr, is_res_lang_ctor, path_to_local, path_to_local_id,
+    peel_blocks, tokenize_with_text,
+};
 use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use rustc_errors::Applicability;
-use rustc_hir::{Expr, ExprKind, MatchSource, Pat, PatKind, QPath, Stmt, StmtKind};
+use rustc_hir::LangItem::{OptionSome, ResultOk};
+use rustc_hir::{
+    BindingMode, Block, ByRef, Expr, ExprKind, HirId, LetStmt, MatchSource, Node, Pat, PatKind, QPath, Stmt, StmtKind,
+};
+use rustc_lexer::TokenKind;
 use rustc_lint::{LateContext, LintContext};
 use rustc_middle::lint::in_external_macro;
 
 use rustc_span::Span;
-use rustc_span::symbol::{Symbol, sym};
+use rustc_span::symbol::{Ident, Symbol, sym};
+use std::ops::ControlFlow;
 use std::slice;
 
 declare_clippy_lint! {
@@ -49,6 +58,90 @@
 
 impl<'tcx> QuestionMark {
     pub(crate) fn check_manual_let_else(&mut self, cx: &LateContext<'tcx>, stmt: &'tcx Stmt<'tcx>) {
+        if let Some(rewrite) = self.let_else_rewrite(cx, stmt)
+            // Leave the ones that `question_mark` rewrites to that lint
+            && self.question_mark_candidate(cx, Node::Expr(rewrite.init)).is_none()
+        {
+            emit_manual_let_else(
+                cx,
+                stmt.span,
+                stmt.hir_id,
+                rewrite.scrutinee,
+                &rewrite.ident_map,
+                rewrite.pat,
+                None,
+                rewrite.else_body,
+                self.suggestion_max_width,
+            );
+        } else {
+            self.check_diverging_fallback(cx, stmt);
+        }
+    }
+
+    /// Checks for a `let` statement unwrapping an `Option` or `Result` with a fallback closure
+    /// which diverges, like `let x = opt.unwrap_or_else(|| panic!("missing"));`. The body of the
+    /// closure becomes the `else` block, so the error of a `Result` must not be used by it.
+    fn check_diverging_fallback(&self, cx: &LateContext<'tcx>, stmt: &'tcx Stmt<'tcx>) {
+        if let StmtKind::Let(local) = stmt.kind
+            && let Some(init) = local.init
+            && local.els.is_none()
+            && local.ty.is_none()
+            && init.span.eq_ctxt(stmt.span)
+            && let ExprKind::MethodCall(segment, receiver, args, _) = init.kind
+            && let Some(fallback) = match (segment.ident.as_str(), args) {
+                ("unwrap_or_else", [fallback]) => Some(fallback),
+                ("map_or_else", [fallback, map]) if is_expr_untyped_identity_function(cx, map) => Some(fallback),
+                _ => None,
+            }
+            && let ExprKind::Closure(closure) = fallback.kind
+            && let receiver_ty = cx.typeck_results().expr_ty(receiver)
+            && cx.typeck_results().expr_adjustments(receiver).is_empty()
+            && let Some(ctor) = if is_type_diagnostic_item(cx, receiver_ty, sym::Option) {
+                prelude_path(cx, stmt.hir_id, "option::Option::Some")
+            } else if is_type_diagnostic_item(cx, receiver_ty, sym::Result) {
+                prelude_path(cx, stmt.hir_id, "result::Result::Ok")
+            } else {
+                None
+            }
+            && let body = cx.tcx.hir().body(closure.body)
+            && body.params.iter().all(|param| match param.pat.kind {
+                PatKind::Wild => true,
+                PatKind::Binding(_, id, _, None) => !is_local_used(cx, body.value, id),
+                _ => false,
+            })
+            && is_never_expr(cx, body.value).is_some()
+            && !diverges_from_closure_only(body.value)
+            && self.msrv.meets(msrvs::LET_ELSE)
+            && !in_external_macro(cx.sess(), stmt.span)
+        {
+            span_lint_hir_and_then(
+                cx,
+                MANUAL_LET_ELSE,
+                stmt.hir_id,
+                stmt.span,
+                "this could be rewritten as `let...else`",
+                |diag| {
+                    let mut app = Applicability::MachineApplicable;
+                    let (sn_pat, _) = snippet_with_context(cx, local.pat.span, stmt.span.ctxt(), "..", &mut app);
+                    let sugg = let_else_stmt(
+                        cx,
+                        stmt.span,
+                        receiver,
+                        &format!("{ctor}({sn_pat})"),
+                        None,
+                        body.value,
+                        self.suggestion_max_width,
+                        &mut app,
+                    );
+                    diag.span_suggestion(stmt.span, "consider writing", sugg, app);
+                },
+            );
+        }
+    }
+
+    /// Checks if `stmt` is a `let` statement initialized by an `if let` or `match` expression,
+    /// which can be rewritten to a `let...else` statement.
+    pub(crate) fn let_else_rewrite(&self, cx: &LateContext<'tcx>, stmt: &Stmt<'tcx>) -> Option<LetElseRewrite<'tcx>> {
         if let StmtKind::Let(local) = stmt.kind
             && let Some(init) = local.init
             && local.els.is_none()
@@ -63,65 +156,222 @@ pub(crate) fn check_manual_let_else(&mut self, cx: &LateContext<'tcx>, stmt: &'t
                     if let Some(ident_map) = expr_simple_identity_map(local.pat, let_pat, if_then)
                         && let Some(if_else) = if_else
                         && is_never_expr(cx, if_else).is_some()
-                        && let qm_allowed = is_lint_allowed(cx, QUESTION_MARK, stmt.hir_id)
-                        && (qm_allowed || pat_and_expr_can_be_question_mark(cx, let_pat, if_else).is_none())
+                        && !else_reevaluates_scrutinee(cx, if_let_expr, if_else)
                     {
-                        emit_manual_let_else(cx, stmt.span, if_let_expr, &ident_map, let_pat, if_else);
+                        return Some(LetElseRewrite {
+                            init,
+                            scrutinee: if_let_expr,
+                            ident_map,
+                            pat: let_pat,
+                            else_body: if_else,
+                        });
                     }
+                    None
                 },
                 IfLetOrMatch::Match(match_expr, arms, source) => {
                     if self.matches_behaviour == MatchLintBehaviour::Never {
-                        return;
+                        return None;
                     }
                     if source != MatchSource::Normal {
-                        return;
+                        return None;
                     }
                     // Any other number than two arms doesn't (necessarily)
                     // have a trivial mapping to let else.
                     if arms.len() != 2 {
-                        return;
+                        return None;
                     }
                     // Guards don't give us an easy mapping either
                     if arms.iter().any(|arm| arm.guard.is_some()) {
-                        return;
+                        return None;
                     }
                     let check_types = self.matches_behaviour == MatchLintBehaviour::WellKnownTypes;
-                    let diverging_arm_opt = arms.iter().enumerate().find(|(_, arm)| {
+                    let (idx, diverging_arm) = arms.iter().enumerate().find(|(_, arm)| {
                         is_never_expr(cx, arm.body).is_some() && pat_allowed_for_else(cx, arm.pat, check_types)
-                    });
-                    let Some((idx, diverging_arm)) = diverging_arm_opt else {
-                        return;
-                    };
+                    })?;
                     // If the non-diverging arm is the first one, its pattern can be reused in a let/else statement.
                     // However, if it arrives in second position, its pattern may cover some cases already covered
                     // by the diverging one.
                     // TODO: accept the non-diverging arm as a second position if patterns are disjointed.
                     if idx == 0 {
-                        return;
+                        return None;
+                    }
+                    if else_reevaluates_scrutinee(cx, match_expr, diverging_arm.body) {
+                        return None;
                     }
                     let pat_arm = &arms[1 - idx];
-                    let Some(ident_map) = expr_simple_identity_map(local.pat, pat_arm.pat, pat_arm.body) else {
-                        return;
-                    };
+                    let ident_map = expr_simple_identity_map(local.pat, pat_arm.pat, pat_arm.body)?;
+                    Some(LetElseRewrite {
+                        init,
+                        scrutinee: match_expr,
+                        ident_map,
+                        pat: pat_arm.pat,
+                        else_body: diverging_arm.body,
+                    })
+                },
+            }
+        } else {
+            None
+        }
+    }
+
+    /// Checks the statements of `block` for an uninitialized `let x;` which is initialized by the
+    /// `if let` following it, see [`DeferredInit`].
+    pub(crate) fn check_deferred_init(&mut self, cx: &LateContext<'tcx>, block: &'tcx Block<'tcx>) {
+        for [decl, next] in block.stmts.array_windows() {
+            let Some(init) = DeferredInit::parse(cx, decl, next) else {
+                continue;
+            };
+            // Leave the ones that `question_mark` rewrites to that lint
+            if self.check_deferred_init_question_mark(cx, &init) {
+                continue;
+            }
+            if self.msrv.meets_at(init.local.span, msrvs::LET_ELSE)
+                && is_never_expr(cx, init.if_else).is_some()
+                && !else_reevaluates_scrutinee(cx, init.if_let.let_expr, init.if_else)
+                && let Some(ty) = init.let_else_ty(cx)
+            {
+                let ident_map = FxHashMap::from_iter([(init.binding.name, init.local.pat)]);
+                emit_manual_let_else(
+                    cx,
+                    init.span,
+                    init.hir_id,
+                    init.if_let.let_expr,
+                    &ident_map,
+                    init.if_let.let_pat,
+                    ty.as_deref(),
+                    init.if_else,
+                    self.suggestion_max_width,
+                );
+            }
+        }
+    }
+}
 
-                    emit_manual_let_else(cx, stmt.span, match_expr, &ident_map, pat_arm.pat, diverging_arm.body);
+/// A `let` statement which can be rewritten to a `let...else` statement, see
+/// [`QuestionMark::let_else_rewrite`].
+pub(crate) struct LetElseRewrite<'tcx> {
+    /// The `if let` or `match` expression initializing the local.
+    pub init: &'tcx Expr<'tcx>,
+    pub scrutinee: &'tcx Expr<'tcx>,
+    /// The bindings of `pat` which are bound by the pattern of the local, see
+    /// `expr_simple_identity_map`.
+    pub ident_map: FxHashMap<Symbol, &'tcx Pat<'tcx>>,
+    /// The pattern of the `let...else` statement.
+    pub pat: &'tcx Pat<'tcx>,
+    /// The diverging branch, which becomes the `else` block.
+    pub else_body: &'tcx Expr<'tcx>,
+}
+
+/// An uninitialized `let x;`, which is directly followed by an `if let` assigning one of its
+/// bindings to `x`:
+///
+/// ```ignore
+/// let x;
+/// if let Some(v) = option {
+///     x = v;
+/// } else {
+///     return;
+/// }
+/// ```
+pub(crate) struct DeferredInit<'tcx> {
+    /// The span of both statements.
+    pub span: Span,
+    /// The `HirId` of the declaration.
+    pub hir_id: HirId,
+    pub local: &'tcx LetStmt<'tcx>,
+    pub if_let: higher::IfLet<'tcx>,
+    pub if_else: &'tcx Expr<'tcx>,
+    /// The binding of the `if let` pattern which is assigned to `x`, i.e. `v`.
+    pub binding: Ident,
+}
+
+impl<'tcx> DeferredInit<'tcx> {
+    fn parse(cx: &LateContext<'tcx>, decl: &'tcx Stmt<'tcx>, next: &'tcx Stmt<'tcx>) -> Option<Self> {
+        if let StmtKind::Let(local) = decl.kind
+            && local.init.is_none()
+            && local.els.is_none()
+            && let PatKind::Binding(BindingMode(ByRef::No, _), local_id, _, None) = local.pat.kind
+            && let StmtKind::Expr(if_expr) | StmtKind::Semi(if_expr) = next.kind
+            && !decl.span.from_expansion()
+            && if_expr.span.eq_ctxt(decl.span)
+            && !in_external_macro(cx.sess(), decl.span)
+            && let Some(if_let) = higher::IfLet::hir(cx, if_expr)
+            && let Some(if_else) = if_let.if_else
+            && let ExprKind::Block(then_block, None) = if_let.if_then.kind
+            && let Some(assign) = match (then_block.stmts, then_block.expr) {
+                ([stmt], None) => match stmt.kind {
+                    StmtKind::Semi(e) => Some(e),
+                    _ => None,
                 },
+                ([], Some(e)) => Some(e),
+                _ => None,
             }
+            && let ExprKind::Assign(lhs, rhs, _) = assign.kind
+            && path_to_local_id(lhs, local_id)
+            && let Some(rhs_id) = path_to_local(rhs)
+        {
+            // The assigned binding has to be moved out of the pattern, so that `x` keeps its type
+            let mut binding = None;
+            if_let.let_pat.each_binding_or_first(&mut |mode, id, _, ident| {
+                if id == rhs_id && mode.0 == ByRef::No {
+                    binding = Some(ident);
+                }
+            });
+            Some(Self {
+                span: decl.span.to(next.span),
+                hir_id: decl.hir_id,
+                local,
+                if_let,
+                if_else,
+                binding: binding?,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Returns the type annotation of the `let...else` statement, which has to keep the one of the
+    /// declaration, or `None` if it can't be spelled out. This is only possible for `Some(v)` and
+    /// `Ok(v)` patterns.
+    fn let_else_ty(&self, cx: &LateContext<'tcx>) -> Option<Option<String>> {
+        let Some(ty) = self.local.ty else {
+            return Some(None);
         };
+        let PatKind::TupleStruct(ref qpath, [field], _) = self.if_let.let_pat.kind else {
+            return None;
+        };
+        if !matches!(field.kind, PatKind::Binding(.., ident, None) if ident == self.binding) {
+            return None;
+        }
+        let (sn_ty, _) = snippet_with_context(cx, ty.span, self.span.ctxt(), "..", &mut Applicability::Unspecified);
+        let res = cx.qpath_res(qpath, self.if_let.let_pat.hir_id);
+        let scrutinee_ty = cx.typeck_results().expr_ty(self.if_let.let_expr);
+        if is_res_lang_ctor(cx, res, OptionSome) && is_type_diagnostic_item(cx, scrutinee_ty, sym::Option) {
+            Some(Some(format!("Option<{sn_ty}>")))
+        } else if is_res_lang_ctor(cx, res, ResultOk) && is_type_diagnostic_item(cx, scrutinee_ty, sym::Result) {
+            Some(Some(format!("Result<{sn_ty}, _>")))
+        } else {
+            None
+        }
     }
 }
 
+#[expect(clippy::too_many_arguments)]
 fn emit_manual_let_else(
     cx: &LateContext<'_>,
     span: Span,
+    hir_id: HirId,
     expr: &Expr<'_>,
     ident_map: &FxHashMap<Symbol, &Pat<'_>>,
     pat: &Pat<'_>,
+    ty: Option<&str>,
     else_body: &Expr<'_>,
+    max_width: u64,
 ) {
-    span_lint_and_then(
+    span_lint_hir_and_then(
         cx,
         MANUAL_LET_ELSE,
+        hir_id,
         span,
         "this could be rewritten as `let...else`",
         |diag| {
@@ -131,21 +381,128 @@ fn emit_manual_let_else(
             // * unused binding collision detection with existing ones
             // for this to be machine applicable.
             let mut app = Applicability::HasPlaceholders;
-            let (sn_expr, _) = snippet_with_context(cx, expr.span, span.ctxt(), "", &mut app);
-            let (sn_else, else_is_mac_call) = snippet_with_context(cx, else_body.span, span.ctxt(), "", &mut app);
-
-            let else_bl = if matches!(else_body.kind, ExprKind::Block(..)) && !else_is_mac_call {
-                sn_else.into_owned()
-            } else {
-                format!("{{ {sn_else} }}")
-            };
-            let sn_bl = replace_in_pattern(cx, span, ident_map, pat, &mut app, true);
-            let sugg = format!("let {sn_bl} = {sn_expr} else {else_bl};");
+            let sugg = let_else_sugg(cx, span, expr, ident_map, pat, ty, else_body, max_width, &mut app);
             diag.span_suggestion(span, "consider writing", sugg, app);
         },
     );
 }
 
+/// Builds the `let...else` statement replacing the statement at `span`, which binds `pat` to
+/// `expr` and diverges with `else_body` otherwise.
+#[expect(clippy::too_many_arguments)]
+pub(crate) fn let_else_sugg(
+    cx: &LateContext<'_>,
+    span: Span,
+    expr: &Expr<'_>,
+    ident_map: &FxHashMap<Symbol, &Pat<'_>>,
+    pat: &Pat<'_>,
+    ty: Option<&str>,
+    else_body: &Expr<'_>,
+    max_width: u64,
+    app: &mut Applicability,
+) -> String {
+    let sn_bl = replace_in_pattern(cx, span, ident_map, pat, app, true);
+    let_else_stmt(cx, span, expr, &sn_bl, ty, else_body, max_width, app)
+}
+
+/// Builds the `let...else` statement replacing the statement at `span`, which matches `expr`
+/// against the pattern `sn_bl`.
+#[expect(clippy::too_many_arguments)]
+fn let_else_stmt(
+    cx: &LateContext<'_>,
+    span: Span,
+    expr: &Expr<'_>,
+    sn_bl: &str,
+    ty: Option<&str>,
+    else_body: &Expr<'_>,
+    max_width: u64,
+    app: &mut Applicability,
+) -> String {
+    let (sn_expr, _) = snippet_with_context(cx, expr.span, span.ctxt(), "", app);
+    let (sn_else, else_is_mac_call) = snippet_with_context(cx, else_body.span, span.ctxt(), "", app);
+
+    let indent = indent_of(cx, span).unwrap_or(0);
+
+    // The diverging expression is always spliced in verbatim, so that e.g. the format
+    // arguments of a `panic!` (including implicit captures) are kept as written
+    let else_bl = if matches!(else_body.kind, ExprKind::Block(..)) && !else_is_mac_call {
+        sn_else.into_owned()
+    } else if sn_else.contains('\n') && !has_multiline_literal_or_comment(&sn_else) {
+        let sn_else = reindent_multiline(sn_else, true, Some(indent + 4));
+        format!("{{\n{}{sn_else}\n{}}}", " ".repeat(indent + 4), " ".repeat(indent))
+    } else {
+        format!("{{ {sn_else} }}")
+    };
+    let sn_ty = ty.map(|ty| format!(": {ty}")).unwrap_or_default();
+    let sugg = format!("let {sn_bl}{sn_ty} = {sn_expr} else {else_bl};");
+    // Put the `else` block on its own lines once the statement gets too wide, like rustfmt
+    if !sugg.contains('\n')
+        && (indent + sugg.len()) as u64 > max_width
+        && let Some(inner) = else_bl.strip_prefix("{ ").and_then(|bl| bl.strip_suffix(" }"))
+    {
+        let semi = if !inner.ends_with(';')
+            && matches!(
+                peel_blocks(else_body).kind,
+                ExprKind::Ret(_) | ExprKind::Break(..) | ExprKind::Continue(_)
+            ) {
+            ";"
+        } else {
+            ""
+        };
+        return format!(
+            "let {sn_bl}{sn_ty} = {sn_expr} else {{\n{}{inner}{semi}\n{}}};",
+            " ".repeat(indent + 4),
+            " ".repeat(indent)
+        );
+    }
+    sugg
+}
+
+/// Checks whether the body of a closure only diverges by returning from the closure, or by
+/// propagating an error out of it with `?`. Moved into an `else` block, it would return from the
+/// enclosing function instead.
+fn diverges_from_closure_only(body: &Expr<'_>) -> bool {
+    for_each_expr_without_closures(body, |e| match e.kind {
+        ExprKind::Ret(_) | ExprKind::Match(_, _, MatchSource::TryDesugar(_)) => ControlFlow::Break(()),
+        _ => ControlFlow::Continue(()),
+    })
+    .is_some()
+}
+
+/// Checks whether the diverging branch `else_body` evaluates the scrutinee again, which has side
+/// effects as it contains a call, `?` or `.await`. The suggested `let...else` would still evaluate
+/// both, but having them spelled out next to each other suggests otherwise.
+fn else_reevaluates_scrutinee<'tcx>(
+    cx: &LateContext<'tcx>,
+    scrutinee: &'tcx Expr<'tcx>,
+    else_body: &'tcx Expr<'tcx>,
+) -> bool {
+    let has_side_effects = for_each_expr_without_closures(scrutinee, |e| match e.kind {
+        ExprKind::Call(..)
+        | ExprKind::MethodCall(..)
+        | ExprKind::Match(_, _, MatchSource::TryDesugar(_) | MatchSource::AwaitDesugar) => ControlFlow::Break(()),
+        _ => ControlFlow::Continue(()),
+    })
+    .is_some();
+    has_side_effects
+        && for_each_expr_without_closures(else_body, |e| {
+            if SpanlessEq::new(cx).eq_expr(e, scrutinee) {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            }
+        })
+        .is_some()
+}
+
+/// Checks whether `snippet` contains a literal or a block comment spanning multiple lines, whose
+/// content would be changed by reindenting the snippet.
+fn has_multiline_literal_or_comment(snippet: &str) -> bool {
+    tokenize_with_text(snippet).any(|(kind, text, _)| {
+        matches!(kind, TokenKind::Literal { .. } | TokenKind::BlockComment { .. }) && text.contains('\n')
+    })
+}
+
 /// Replaces the locals in the pattern
 ///
 /// For this example:
@@ -24,6 +24,7 @@
 mod try_err;
 mod wild_in_or_pats;
 
+use crate::question_mark::{QuestionMarkClaims, is_question_mark_wild_guard};
 use clippy_config::Conf;
 use clippy_config::msrvs::{self, Msrv};
 use clippy_utils::source::walk_span_to_context;
@@ -978,13 +979,15 @@
 pub struct Matches {
     msrv: Msrv,
     infallible_destructuring_match_linted: bool,
+    question_mark_claims: QuestionMarkClaims,
 }
 
 impl Matches {
-    pub fn new(conf: &'static Conf) -> Self {
+    pub fn new(conf: &'static Conf, question_mark_claims: QuestionMarkClaims) -> Self {
         Self {
             msrv: conf.msrv.clone(),
             infallible_destructuring_match_linted: false,
+            question_mark_claims,
         }
     }
 }
@@ -1118,14 +1121,17 @@ fn check_expr(&mut self, cx: &LateContext<'tcx>, expr: &'tcx Expr<'_>) {
                         );
                     }
                 }
-                redundant_pattern_match::check_if_let(
-                    cx,
-                    expr,
-                    if_let.let_pat,
-                    if_let.let_expr,
-                    if_let.if_else.is_some(),
-                    if_let.let_span,
-                );
+                // `question_mark` already rewrites some of these to use the `?` operator
+                if !is_question_mark_wild_guard(cx, expr, &self.question_mark_claims) {
+                    redundant_pattern_match::check_if_let(
+                        cx,
+                        expr,
+                        if_let.let_pat,
+                        if_let.let_expr,
+                        if_let.if_else.is_some(),
+                        if_let.let_span,
+                    );
+                }
                 needless_match::check_if_let(cx, expr, &if_let);
             }
         } else {
@@ -1,3 +1,4 @@
+use crate::question_mark::{QuestionMarkClaims, is_question_mark_guard_cond};
 use clippy_utils::diagnostics::{span_lint, span_lint_and_sugg};
 use clippy_utils::source::snippet_with_applicability;
 use clippy_utils::sugg::Sugg;
@@ -9,7 +10,7 @@
 use rustc_errors::Applicability;
 use rustc_hir::{BinOpKind, Expr, ExprKind, UnOp};
 use rustc_lint::{LateContext, LateLintPass};
-use rustc_session::declare_lint_pass;
+use rustc_session::{declare_lint_pass, impl_lint_pass};
 use rustc_span::Span;
 use rustc_span::source_map::Spanned;
 
@@ -218,7 +219,17 @@ fn check_expr(&mut self, cx: &LateContext<'tcx>, e: &'tcx Expr<'_>) {
     }
 }
 
-declare_lint_pass!(BoolComparison => [BOOL_COMPARISON]);
+pub struct BoolComparison {
+    question_mark_claims: QuestionMarkClaims,
+}
+
+impl BoolComparison {
+    pub fn new(question_mark_claims: QuestionMarkClaims) -> Self {
+        Self { question_mark_claims }
+    }
+}
+
+impl_lint_pass!(BoolComparison => [BOOL_COMPARISON]);
 
 impl<'tcx> LateLintPass<'tcx> for BoolComparison {
     fn check_expr(&mut self, cx: &LateContext<'tcx>, e: &'tcx Expr<'_>) {
@@ -231,6 +242,10 @@ fn check_expr(&mut self, cx: &LateContext<'tcx>, e: &'tcx Expr<'_>) {
             let ignore_no_literal = None::<(fn(_, _) -> _, &str)>;
             match node {
                 BinOpKind::Eq => {
+                    // `if x.is_none() == true { return None; }` is rewritten to `x?;` by `question_mark`
+                    if is_question_mark_guard_cond(cx, e, &self.question_mark_claims) {
+                        return;
+                    }
                     let true_case = Some((|h| h, "equality checks against true are unnecessary"));
                     let false_case = Some((
                         |h: Sugg<'tcx>| !h,
@@ -1,10 +1,11 @@
+use crate::question_mark::{QuestionMarkClaims, is_question_mark_guard_cond};
 use clippy_utils::diagnostics::span_lint_and_sugg;
 use clippy_utils::ty::is_type_diagnostic_item;
 use clippy_utils::{is_res_lang_ctor, path_res, peel_hir_expr_refs, peel_ref_operators, sugg};
 use rustc_errors::Applicability;
 use rustc_hir::{BinOpKind, Expr, ExprKind, LangItem};
 use rustc_lint::{LateContext, LateLintPass};
-use rustc_session::declare_lint_pass;
+use rustc_session::impl_lint_pass;
 use rustc_span::sym;
 
 declare_clippy_lint! {
@@ -37,7 +38,18 @@
     style,
     "Binary comparison to `Option<T>::None` relies on `T: PartialEq`, which is unneeded"
 }
-declare_lint_pass!(PartialeqToNone => [PARTIALEQ_TO_NONE]);
+
+pub struct PartialeqToNone {
+    question_mark_claims: QuestionMarkClaims,
+}
+
+impl PartialeqToNone {
+    pub fn new(question_mark_claims: QuestionMarkClaims) -> Self {
+        Self { question_mark_claims }
+    }
+}
+
+impl_lint_pass!(PartialeqToNone => [PARTIALEQ_TO_NONE]);
 
 impl<'tcx> LateLintPass<'tcx> for PartialeqToNone {
     fn check_expr(&mut self, cx: &LateContext<'tcx>, e: &'tcx Expr<'_>) {
@@ -66,6 +78,11 @@ fn check_expr(&mut self, cx: &LateContext<'tcx>, e: &'tcx Expr<'_>) {
                 _ => return,
             };
 
+            // `if x == None { return None; }` is rewritten to `x?;` by `question_mark`
+            if is_eq && is_question_mark_guard_cond(cx, e, &self.question_mark_claims) {
+                return;
+            }
+
             // We are only interested in comparisons between `Option` and a literal `Option::None`
             let scrutinee = match (
                 is_none_ctor(left_side) && is_ty_option(right_side),
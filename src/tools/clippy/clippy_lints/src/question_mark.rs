@@ -1,28 +1,50 @@
-use crate::manual_let_else::MANUAL_LET_ELSE;
-use crate::question_mark_used::QUESTION_MARK_USED;
+use crate::manual_let_else::{self, DeferredInit, MANUAL_LET_ELSE};
+use crate::matches::REDUNDANT_PATTERN_MATCHING;
+use crate::question_mark_used::{QUESTION_MARK_USED, allowed_traits, is_in_allowed_trait_impl};
 use clippy_config::Conf;
-use clippy_config::msrvs::Msrv;
-use clippy_config::types::MatchLintBehaviour;
-use clippy_utils::diagnostics::span_lint_and_sugg;
-use clippy_utils::source::snippet_with_applicability;
-use clippy_utils::ty::{implements_trait, is_type_diagnostic_item};
+use clippy_config::msrvs::{self, Msrv};
+use clippy_config::types::{MatchLintBehaviour, QuestionMarkVisibility};
+use clippy_utils::diagnostics::{docs_section_link, span_lint_hir_and_then};
+use clippy_utils::macros::{is_panic, root_macro_call_first_node};
+use clippy_utils::source::{IntoSpan, SpanRangeExt, indent_of, snippet_with_applicability, walk_span_to_context};
+use clippy_utils::ty::{
+    get_type_diagnostic_name, implements_trait, is_type_diagnostic_item, match_type, needs_ordered_drop,
+};
+use clippy_utils::usage::{UsageKind, is_potentially_mutated, local_used_after_expr, used_after_stmt};
+use clippy_utils::visitors::{Descend, for_each_expr, for_each_expr_without_closures, is_local_used};
 use clippy_utils::{
-    eq_expr_value, higher, is_else_clause, is_in_const_context, is_lint_allowed, is_path_lang_item, is_res_lang_ctor,
-    pat_and_expr_can_be_question_mark, path_to_local, path_to_local_id, peel_blocks, peel_blocks_with_stmt,
-    span_contains_comment,
+    SpanlessEq, TryBlockTracker, def_path_def_ids, eq_expr_value, get_enclosing_loop_or_multi_call_closure,
+    get_parent_expr, higher, in_automatically_derived, is_const_body, is_else_clause, is_lint_allowed,
+    is_res_lang_ctor, pat_and_expr_can_be_question_mark, path_res, path_to_local, path_to_local_id, paths, peel_blocks,
+    peel_blocks_with_stmt, peel_identity_into, span_contains_cfg, span_contains_cfg_or_cfg_attr, span_contains_comment,
+    std_or_core, tokenize_with_text,
 };
-use rustc_errors::Applicability;
-use rustc_hir::LangItem::{self, OptionNone, OptionSome, ResultErr, ResultOk};
-use rustc_hir::def::Res;
+use rustc_ast::{Attribute, LitKind};
+use rustc_data_structures::sync::{Lock, Lrc};
+use rustc_errors::{Applicability, Diag};
+use rustc_hir::LangItem::{self, OptionNone, OptionSome, PollReady, ResultErr, ResultOk};
+use rustc_hir::def::{DefKind, Res};
+use rustc_hir::def_id::{CRATE_DEF_ID, DefIdMap, DefIdSet};
+use rustc_hir::intravisit::{Visitor, walk_path};
 use rustc_hir::{
-    BindingMode, Block, Body, ByRef, Expr, ExprKind, LetStmt, Mutability, Node, PatKind, PathSegment, QPath, Stmt,
-    StmtKind,
+    Arm, BinOpKind, BindingMode, Block, BlockCheckMode, Body, ByRef, CRATE_HIR_ID, Expr, ExprKind, FnRetTy, GenericArg,
+    HirId, HirIdSet, Item, ItemKind, ItemLocalId, LetStmt, LoopSource, MatchSource, Mutability, Node, OwnerNode, Pat,
+    PatKind, PathSegment, QPath, Stmt, StmtKind, TyKind, UnOp, UnsafeSource, UseKind,
 };
-use rustc_lint::{LateContext, LateLintPass};
-use rustc_middle::ty::Ty;
-use rustc_session::impl_lint_pass;
-use rustc_span::sym;
+use rustc_hir_typeck::expr_use_visitor::{Delegate, ExprUseVisitor, PlaceBase, PlaceWithHirId};
+use rustc_lexer::TokenKind;
+use rustc_lint::{LateContext, LateLintPass, Lint, LintContext};
+use rustc_middle::hir::nested_filter;
+use rustc_middle::lint::in_external_macro;
+use rustc_middle::mir::FakeReadCause;
+use rustc_middle::ty::adjustment::Adjust;
+use rustc_middle::ty::{self, BorrowKind, Ty, TyCtxt, TypeckResults};
+use rustc_session::{RustcVersion, impl_lint_pass};
 use rustc_span::symbol::Symbol;
+use rustc_span::{BytePos, Span, sym};
+use rustc_target::spec::abi::Abi;
+use std::iter;
+use std::ops::ControlFlow;
 
 declare_clippy_lint! {
     /// ### What it does
@@ -31,7 +53,12 @@
     /// ### Why is this bad?
     /// Question mark usage is more idiomatic.
     ///
+    /// ### Known problems
+    /// In functions returning a nested type such as `Result<Option<T>, E>`, guards like
+    /// `if option.is_none() { return Ok(None); }` aren't linted, as `?` can't produce `Ok(None)`.
+    ///
     /// ### Example
+    /// #### `if` guards {#question_mark-if}
     /// ```ignore
     /// if option.is_none() {
     ///     return None;
@@ -43,31 +70,284 @@
     /// ```ignore
     /// option?;
     /// ```
+    ///
+    /// #### `if let` expressions {#question_mark-if-let}
+    /// ```ignore
+    /// let value = if let Some(value) = option { value } else { return None };
+    /// ```
+    ///
+    /// Could be written:
+    ///
+    /// ```ignore
+    /// let value = option?;
+    /// ```
+    ///
+    /// #### `let...else` statements {#question_mark-let-else}
+    /// ```ignore
+    /// let Some(value) = option else {
+    ///     return None;
+    /// };
+    /// ```
+    ///
+    /// Could be written:
+    ///
+    /// ```ignore
+    /// let value = option?;
+    /// ```
+    ///
+    /// #### `match` expressions {#question_mark-match}
+    /// These are linted by `question_mark_match` instead, so that they can be allowed on their own.
+    ///
+    /// ```ignore
+    /// let value = match result {
+    ///     Ok(value) => value,
+    ///     Err(err) => return Err(err),
+    /// };
+    /// ```
+    ///
+    /// Could be written:
+    ///
+    /// ```ignore
+    /// let value = result?;
+    /// ```
     #[clippy::version = "pre 1.29.0"]
     pub QUESTION_MARK,
     style,
     "checks for expressions that could be replaced by the question mark operator"
 }
 
+declare_clippy_lint! {
+    /// ### What it does
+    /// Checks for `match` expressions that could be replaced by the question mark operator.
+    ///
+    /// ### Why is this bad?
+    /// Question mark usage is more idiomatic.
+    ///
+    /// This is split from `question_mark`, which checks the other shapes, so that either of them
+    /// can be allowed without a configuration file.
+    ///
+    /// ### Example
+    /// ```ignore
+    /// let value = match result {
+    ///     Ok(value) => value,
+    ///     Err(err) => return Err(err),
+    /// };
+    /// ```
+    ///
+    /// Could be written:
+    ///
+    /// ```ignore
+    /// let value = result?;
+    /// ```
+    #[clippy::version = "1.85.0"]
+    pub QUESTION_MARK_MATCH,
+    style,
+    "checks for `match` expressions that could be replaced by the question mark operator"
+}
+
+declare_clippy_lint! {
+    /// ### What it does
+    /// Emits a single summary per crate, counting the expressions which `question_mark` lints,
+    /// grouped by their shape (`if` guards, `if let` expressions, `let...else` statements and
+    /// `match` expressions).
+    ///
+    /// ### Why restrict this?
+    /// This is not about the code itself, but meant to measure how many sites would have to be
+    /// rewritten before `question_mark` can be enabled in a crate. Only sites at which
+    /// `question_mark` isn't allowed are counted, and nothing is emitted for crates without any.
+    ///
+    /// ### Example
+    /// ```ignore
+    /// #![warn(clippy::question_mark_candidates_summary)]
+    ///
+    /// fn f(a: Option<u32>) -> Option<u32> {
+    ///     if a.is_none() {
+    ///         return None;
+    ///     }
+    ///     a
+    /// }
+    /// ```
+    ///
+    /// Emits a summary stating that the crate contains one `if` guard which may be rewritten.
+    #[clippy::version = "1.85.0"]
+    pub QUESTION_MARK_CANDIDATES_SUMMARY,
+    restriction,
+    "summarizes the expressions in a crate which could be replaced by the question mark operator"
+}
+
+declare_clippy_lint! {
+    /// ### What it does
+    /// Checks for values unwrapped with the `?` operator which are checked for `None` or `Err`
+    /// right away in the next statement.
+    ///
+    /// ### Why is this bad?
+    /// This usually happens when a nested `Option<Option<T>>` (or `Result`) was flattened by a
+    /// rewrite to use `?`, e.g. after applying a `question_mark` suggestion. The check then tests
+    /// the inner value, while the original code tested the outer one.
+    ///
+    /// ### Example
+    /// ```no_run
+    /// fn f(a: Option<Option<u32>>) -> Option<u32> {
+    ///     let x = a?;
+    ///     if x.is_none() {
+    ///         return None;
+    ///     }
+    ///     x
+    /// }
+    /// ```
+    ///
+    /// The check has to be reviewed, as it may have been meant to check `a` rather than `x`.
+    #[clippy::version = "1.85.0"]
+    pub QUESTION_MARK_RECHECKED,
+    suspicious,
+    "checks for values unwrapped with `?` which are checked for `None` or `Err` again right away"
+}
+
 pub struct QuestionMark {
     pub(crate) msrv: Msrv,
     pub(crate) matches_behaviour: MatchLintBehaviour,
-    /// Keeps track of how many try blocks we are in at any point during linting.
-    /// This allows us to answer the question "are we inside of a try block"
-    /// very quickly, without having to walk up the parent chain, by simply checking
-    /// if it is greater than zero.
-    /// As for why we need this in the first place: <https://github.com/rust-lang/rust-clippy/issues/8628>
-    try_block_depth_stack: Vec<u32>,
+    /// Whether `match` expressions should be rewritten to use the `?` operator. Kept separate from
+    /// `matches_behaviour`, which is about rewriting them to `let...else`.
+    pub(crate) matches_for_question_mark: MatchLintBehaviour,
+    /// Keeps track of the try blocks we are in, as `?` would propagate to them.
+    /// See <https://github.com/rust-lang/rust-clippy/issues/8628>
+    try_blocks: TryBlockTracker,
+    /// Whether each body we are in is generated code, which isn't linted, so that `check_stmt`
+    /// and `check_expr` can return early.
+    generated_body_stack: Vec<bool>,
+    /// Which functions to lint, see `question-mark-visibility`.
+    visibility: QuestionMarkVisibility,
+    /// Whether each body we are in is excluded from `QUESTION_MARK` by `visibility`.
+    visibility_skip_stack: Vec<bool>,
+    /// Whether the lints of this pass are allowed throughout each body we are in, in which case it
+    /// isn't checked at all.
+    allowed_body_stack: Vec<bool>,
+    /// Whether to skip `#[no_mangle]` functions, see `question-mark-skip-no-mangle`.
+    skip_no_mangle: bool,
+    /// Whether each body we are in belongs to a function with a non-Rust ABI, or to a skipped
+    /// `#[no_mangle]` function.
+    ffi_skip_stack: Vec<bool>,
+    /// Whether to emit a single diagnostic per body, see `question-mark-group-per-fn`.
+    group_per_fn: bool,
+    /// Whether to lint `let...else` statements, see `question-mark-lint-let-else`.
+    lint_let_else: bool,
+    /// Whether to skip the diagnostics whose suggestion isn't machine applicable, see
+    /// `question-mark-machine-applicable-only`.
+    machine_applicable_only: bool,
+    /// Whether to lint guards with an additional condition, see `question-mark-split-conditions`.
+    split_conditions: bool,
+    /// The `Try` types treated like `Result`, with the method checking for their failure case, see
+    /// `question-mark-custom-try`.
+    custom_try: DefIdMap<Symbol>,
+    /// Whether to lint guards on a local holding the condition, see
+    /// `question-mark-track-condition-locals`.
+    track_condition_locals: bool,
+    /// The traits whose implementations `QUESTION_MARK_USED` doesn't lint, see
+    /// `question-mark-used-allowed-traits`.
+    question_mark_used_allowed_traits: DefIdSet,
+    /// The width after which suggested `let` statements are split, see `suggestion-max-width`.
+    pub(crate) suggestion_max_width: u64,
+    /// The suggestions collected for each body we are in, used when `group_per_fn` is set.
+    grouped_suggs_stack: Vec<Vec<QuestionMarkSugg>>,
+    /// The number of linted sites of each kind, for `QUESTION_MARK_CANDIDATES_SUMMARY`.
+    candidate_counts: [usize; 4],
+    /// The spans edited by the suggestions emitted in the outermost body we are in, which the
+    /// suggestions emitted after them mustn't overlap.
+    claims: QuestionMarkClaims,
+}
+
+/// The spans edited by the suggestions `QUESTION_MARK` emitted in the outermost body being
+/// checked. Shared with the lints which leave the guards it rewrites to it, so that they only step
+/// aside where it actually emitted a suggestion.
+#[derive(Clone, Default)]
+pub struct QuestionMarkClaims(Lrc<Lock<Vec<Span>>>);
+
+impl QuestionMarkClaims {
+    fn clear(&self) {
+        self.0.lock().clear();
+    }
+
+    /// Claims `spans`, unless one of them overlaps a span claimed before.
+    fn claim(&self, spans: Vec<Span>) -> bool {
+        if self.overlaps(&spans) {
+            false
+        } else {
+            self.0.lock().extend(spans);
+            true
+        }
+    }
+
+    /// Checks if one of `spans` overlaps a claimed span.
+    fn overlaps(&self, spans: &[Span]) -> bool {
+        let claimed = self.0.lock();
+        spans
+            .iter()
+            .any(|span| claimed.iter().any(|claimed| claimed.overlaps(*span)))
+    }
+
+    /// Checks if `span` is rewritten by a suggestion `QUESTION_MARK` emitted.
+    fn is_claimed(&self, span: Span) -> bool {
+        self.0.lock().iter().any(|claimed| claimed.contains(span))
+    }
 }
 
-impl_lint_pass!(QuestionMark => [QUESTION_MARK, MANUAL_LET_ELSE]);
+impl_lint_pass!(QuestionMark => [
+    QUESTION_MARK,
+    QUESTION_MARK_MATCH,
+    MANUAL_LET_ELSE,
+    QUESTION_MARK_CANDIDATES_SUMMARY,
+    QUESTION_MARK_RECHECKED,
+]);
+
+/// The configuration options read by `QuestionMark::new`, which `tests/config-metadata.rs` checks
+/// are registered as affecting the lints of the pass. Update it along with `new`.
+pub const CONF_OPTIONS: &[&str] = &[
+    "msrv",
+    "match-lint-behaviour-let-else",
+    "matches-for-question-mark",
+    "question-mark-visibility",
+    "question-mark-skip-no-mangle",
+    "question-mark-group-per-fn",
+    "question-mark-lint-let-else",
+    "question-mark-machine-applicable-only",
+    "question-mark-split-conditions",
+    "question-mark-custom-try",
+    "question-mark-track-condition-locals",
+    "question-mark-used-allowed-traits",
+    "suggestion-max-width",
+];
 
 impl QuestionMark {
-    pub fn new(conf: &'static Conf) -> Self {
+    pub fn new(tcx: TyCtxt<'_>, conf: &'static Conf, claims: QuestionMarkClaims) -> Self {
         Self {
             msrv: conf.msrv.clone(),
-            matches_behaviour: conf.matches_for_let_else,
-            try_block_depth_stack: Vec::new(),
+            matches_behaviour: conf.match_lint_behaviour_let_else,
+            matches_for_question_mark: conf.matches_for_question_mark,
+            try_blocks: TryBlockTracker::default(),
+            generated_body_stack: Vec::new(),
+            visibility: conf.question_mark_visibility,
+            visibility_skip_stack: Vec::new(),
+            allowed_body_stack: Vec::new(),
+            skip_no_mangle: conf.question_mark_skip_no_mangle,
+            ffi_skip_stack: Vec::new(),
+            group_per_fn: conf.question_mark_group_per_fn,
+            lint_let_else: conf.question_mark_lint_let_else,
+            machine_applicable_only: conf.question_mark_machine_applicable_only,
+            split_conditions: conf.question_mark_split_conditions,
+            custom_try: conf
+                .question_mark_custom_try
+                .iter()
+                .flat_map(|custom| {
+                    let is_failure = Symbol::intern(&custom.is_failure);
+                    def_path_def_ids(tcx, &custom.path.split("::").collect::<Vec<_>>()).map(move |id| (id, is_failure))
+                })
+                .collect(),
+            track_condition_locals: conf.question_mark_track_condition_locals,
+            question_mark_used_allowed_traits: allowed_traits(tcx, conf),
+            suggestion_max_width: conf.suggestion_max_width,
+            grouped_suggs_stack: Vec::new(),
+            candidate_counts: [0; 4],
+            claims,
         }
     }
 }
@@ -109,7 +389,7 @@ fn find_let_else_ret_expression<'hir>(block: &'hir Block<'hir>) -> Option<&'hir
     }
 }
 
-fn check_let_some_else_return_none(cx: &LateContext<'_>, stmt: &Stmt<'_>) {
+fn check_let_some_else_return_none(cx: &LateContext<'_>, stmt: &Stmt<'_>) -> Option<QuestionMarkSugg> {
     /// Make sure the init expr implements try trait so a valid suggestion could be given.
     ///
     /// Because the init expr could have the type of `&Option<T>` which does not implements `Try`.
@@ -121,7 +401,7 @@ fn check_let_some_else_return_none(cx: &LateContext<'_>, stmt: &Stmt<'_>) {
     /// or `(&some_struct.opt)?` since the first one has different semantics and the later does
     /// not implements `Try`.
     fn init_expr_can_use_question_mark(cx: &LateContext<'_>, init_expr: &Expr<'_>) -> bool {
-        let init_ty = cx.typeck_results().expr_ty_adjusted(init_expr);
+        let init_ty = typeck_results(cx).expr_ty_adjusted(init_expr);
         cx.tcx
             .lang_items()
             .try_trait()
@@ -136,25 +416,435 @@ fn init_expr_can_use_question_mark(cx: &LateContext<'_>, init_expr: &Expr<'_>) -
     }) = stmt.kind
         && init_expr_can_use_question_mark(cx, init_expr)
         && let Some(ret) = find_let_else_ret_expression(els)
+        // A local macro expanding to the `return` is fine, since it's replaced as a whole
+        && !in_external_macro(cx.sess(), ret.span)
         && let Some(inner_pat) = pat_and_expr_can_be_question_mark(cx, pat, ret)
+        && !has_at_binding(inner_pat)
         && !span_contains_comment(cx.tcx.sess.source_map(), els.span)
+        && !span_contains_cfg_or_cfg_attr(cx, els.span)
     {
         let mut applicability = Applicability::MaybeIncorrect;
         let init_expr_str = snippet_with_applicability(cx, init_expr.span, "..", &mut applicability);
-        let receiver_str = snippet_with_applicability(cx, inner_pat.span, "..", &mut applicability);
+        let receiver_str = match inner_pat.kind {
+            // `Ident` is printed as a raw identifier if it's a keyword in the edition it was written in
+            PatKind::Binding(mode, _, ident, None) => format!("{}{ident}", mode.prefix_str()).into(),
+            _ => snippet_with_applicability(cx, inner_pat.span, "..", &mut applicability),
+        };
         let sugg = format!("let {receiver_str} = {init_expr_str}?;",);
-        span_lint_and_sugg(
-            cx,
-            QUESTION_MARK,
-            stmt.span,
-            "this `let...else` may be rewritten with the `?` operator",
-            "replace it with",
+        Some(QuestionMarkSugg {
+            kind: QuestionMarkKind::LetElse,
+            hir_id: stmt.hir_id,
+            span: stmt.span,
+            msg: "this `let...else` may be rewritten with the `?` operator",
             sugg,
             applicability,
+            msrv_blocked: None,
+            extra: None,
+        })
+    } else {
+        None
+    }
+}
+
+/// Checks for `x @ ..` bindings anywhere in the pattern. These are left alone since the
+/// subpattern is usually what made the `let...else` necessary in the first place.
+fn has_at_binding(pat: &Pat<'_>) -> bool {
+    let mut found = false;
+    pat.walk_short(|p| {
+        found = matches!(p.kind, PatKind::Binding(.., Some(_)));
+        !found
+    });
+    found
+}
+
+/// The message of the suggestions rewriting guards to `let...else` statements rather than using
+/// the `?` operator, which are still made where `QUESTION_MARK_USED` bans the operator.
+const LET_ELSE_MSG: &str = "this block may be rewritten with `let...else`";
+
+/// The kinds of expressions `QUESTION_MARK` rewrites.
+#[derive(Clone, Copy)]
+pub(crate) enum QuestionMarkKind {
+    /// `if x.is_none() { return None; }` and similar guards.
+    IfIs,
+    /// `if let Some(y) = x { y } else { return None }`
+    IfLet,
+    /// `let Some(y) = x else { return None };`
+    LetElse,
+    /// `match x { Some(y) => y, None => return None }`
+    Match,
+}
+
+impl QuestionMarkKind {
+    const ALL: [Self; 4] = [Self::IfIs, Self::IfLet, Self::LetElse, Self::Match];
+
+    /// The identifiers of all shapes, listed in the lint metadata.
+    pub(crate) const SHAPES: [&'static str; QuestionMarkKind::ALL.len()] = {
+        let mut shapes = [""; QuestionMarkKind::ALL.len()];
+        let mut i = 0;
+        while i < shapes.len() {
+            shapes[i] = Self::ALL[i].docs_section();
+            i += 1;
+        }
+        shapes
+    };
+
+    fn description(self) -> &'static str {
+        match self {
+            Self::IfIs => "`if` guards",
+            Self::IfLet => "`if let` expressions",
+            Self::LetElse => "`let...else` statements",
+            Self::Match => "`match` expressions",
+        }
+    }
+
+    /// The anchor of the section on this shape in the documentation of `QUESTION_MARK`, which is
+    /// also its identifier in the lint metadata.
+    const fn docs_section(self) -> &'static str {
+        match self {
+            Self::IfIs => "if",
+            Self::IfLet => "if-let",
+            Self::LetElse => "let-else",
+            Self::Match => "match",
+        }
+    }
+
+    /// The lint emitted for this shape.
+    fn lint(self) -> &'static Lint {
+        match self {
+            Self::IfIs | Self::IfLet | Self::LetElse => QUESTION_MARK,
+            Self::Match => QUESTION_MARK_MATCH,
+        }
+    }
+
+    /// Links to the section on this shape in the documentation of `QUESTION_MARK`.
+    fn docs_link(self, diag: &mut Diag<'_, ()>) {
+        docs_section_link(diag, QUESTION_MARK, self.docs_section(), self.description());
+    }
+}
+
+/// A rewrite of a single site suggested by `QUESTION_MARK`.
+pub(crate) struct QuestionMarkSugg {
+    kind: QuestionMarkKind,
+    hir_id: HirId,
+    span: Span,
+    msg: &'static str,
+    sugg: String,
+    applicability: Applicability,
+    /// The feature the rewrite requires and the MSRV, if the MSRV doesn't allow it. The lint is
+    /// emitted without a suggestion then.
+    msrv_blocked: Option<(MsrvFeature, RustcVersion)>,
+    /// Edits to the code following `span`, which the rewrite requires or makes possible.
+    extra: Option<ExtraEdits>,
+}
+
+/// Edits outside of the rewritten site, see `QuestionMarkSugg::extra`.
+struct ExtraEdits {
+    /// The help message of the suggestion containing the edits.
+    help: &'static str,
+    replacements: Vec<(Span, String)>,
+    notes: Vec<(Span, String)>,
+    /// Other rewrites of the whole site for the user to pick from, with their help messages.
+    alternatives: Vec<(&'static str, String)>,
+    /// The span of the whole function body and its replacement, if the site is all the body does
+    /// besides returning success. Offered as an alternative as well.
+    body_rewrite: Option<(Span, String)>,
+}
+
+impl QuestionMarkSugg {
+    /// Emits the lint of its kind, suggesting to replace `span` with `sugg`, unless the rewrite is
+    /// blocked by the MSRV.
+    ///
+    /// If the replaced code was the only user of an explicit `use` of `None`/`Some`/`Ok`/`Err`, the
+    /// suggestion also removes that import, so that applying it doesn't leave an unused import
+    /// behind. With `machine_applicable_only`, the alternative rewrites, which may be incorrect,
+    /// are left out.
+    fn emit(self, cx: &LateContext<'_>, machine_applicable_only: bool) {
+        if let Some((feature, msrv)) = self.msrv_blocked {
+            span_lint_hir_and_then(cx, self.kind.lint(), self.hir_id, self.span, self.msg, |diag| {
+                diag.note(feature.blocked_note(msrv));
+                self.kind.docs_link(diag);
+            });
+            return;
+        }
+        let dead_imports = dead_variant_imports(cx, self.hir_id, &[self.span]);
+        span_lint_hir_and_then(cx, self.kind.lint(), self.hir_id, self.span, self.msg, |diag| {
+            let (help, replacements, notes, alternatives, body_rewrite) = match self.extra {
+                Some(extra) => (
+                    extra.help,
+                    extra.replacements,
+                    extra.notes,
+                    extra.alternatives,
+                    extra.body_rewrite,
+                ),
+                None => ("replace it with", Vec::new(), Vec::new(), Vec::new(), None),
+            };
+            for (span, note) in notes {
+                diag.span_note(span, note);
+            }
+            if dead_imports.is_empty() && replacements.is_empty() {
+                diag.span_suggestion(self.span, "replace it with", self.sugg, self.applicability);
+            } else {
+                let help = match (replacements.is_empty(), dead_imports.is_empty()) {
+                    (true, _) => "replace it and remove the unused import".to_owned(),
+                    (false, true) => help.to_owned(),
+                    (false, false) => format!("{help}, and remove the unused import"),
+                };
+                let mut suggs = vec![(self.span, self.sugg)];
+                suggs.extend(replacements);
+                suggs.extend(dead_imports.into_iter().map(|import| (import, String::new())));
+                diag.multipart_suggestion(help, suggs, self.applicability);
+            }
+            if !machine_applicable_only {
+                emit_alternatives(diag, self.span, alternatives, body_rewrite);
+            }
+            self.kind.docs_link(diag);
+        });
+    }
+}
+
+/// Offers the alternative rewrites of the site at `span`, and the one of the whole function body.
+fn emit_alternatives(
+    diag: &mut Diag<'_, ()>,
+    span: Span,
+    alternatives: Vec<(&'static str, String)>,
+    body_rewrite: Option<(Span, String)>,
+) {
+    for (help, alternative) in alternatives {
+        diag.span_suggestion(span, help, alternative, Applicability::MaybeIncorrect);
+    }
+    if let Some((body, rewrite)) = body_rewrite {
+        diag.span_suggestion(
+            body,
+            "the function body can be replaced with",
+            rewrite,
+            Applicability::MaybeIncorrect,
         );
     }
 }
 
+/// Emits a single `QUESTION_MARK` diagnostic on the signature of the body owner, with one
+/// suggestion for all of the rewrites in the body. Bodies in which only `match` expressions are
+/// rewritten get a `QUESTION_MARK_MATCH` diagnostic instead. The alternative rewrites of the sites
+/// are offered separately, unless `machine_applicable_only` is set.
+fn emit_grouped(cx: &LateContext<'_>, body: &Body<'_>, suggs: Vec<QuestionMarkSugg>, machine_applicable_only: bool) {
+    let owner = cx.tcx.hir().body_owner_def_id(body.id());
+    let hir_id = cx.tcx.local_def_id_to_hir_id(owner);
+    let mut applicability = Applicability::MachineApplicable;
+    let mut notes = Vec::with_capacity(suggs.len());
+    let mut rewritten = Vec::with_capacity(suggs.len());
+    let mut extra_notes = Vec::new();
+    let mut replacements = Vec::with_capacity(suggs.len());
+    let mut alternatives = Vec::new();
+    let mut kinds = [false; QuestionMarkKind::ALL.len()];
+    let mut only_matches = true;
+    for sugg in suggs {
+        kinds[sugg.kind as usize] = true;
+        only_matches &= matches!(sugg.kind, QuestionMarkKind::Match);
+        // The sites blocked by the MSRV are pointed out, but left as they are
+        if let Some((feature, msrv)) = sugg.msrv_blocked {
+            notes.push((sugg.span, feature.blocked_note(msrv)));
+            continue;
+        }
+        applicability = min_applicability(applicability, sugg.applicability);
+        notes.push((sugg.span, "this may be rewritten with the `?` operator".to_owned()));
+        rewritten.push(sugg.span);
+        replacements.push((sugg.span, sugg.sugg));
+        if let Some(extra) = sugg.extra {
+            replacements.extend(extra.replacements);
+            extra_notes.extend(extra.notes);
+            alternatives.push((sugg.span, extra.alternatives, extra.body_rewrite));
+        }
+    }
+    let dead_imports = dead_variant_imports(cx, hir_id, &rewritten);
+    replacements.extend(dead_imports.into_iter().map(|import| (import, String::new())));
+
+    let lint = if only_matches {
+        QUESTION_MARK_MATCH
+    } else {
+        QUESTION_MARK
+    };
+    let blocks = |count: usize| {
+        if count == 1 {
+            "1 block".to_owned()
+        } else {
+            format!("{count} blocks")
+        }
+    };
+    // Only the sites which are rewritten are counted as such, the blocked ones are listed apart
+    let msg = match (rewritten.len(), notes.len() - rewritten.len()) {
+        (rewritable, 0) => format!(
+            "this function contains {} which may be rewritten with the `?` operator",
+            blocks(rewritable)
+        ),
+        (0, blocked) => format!(
+            "this function contains {} which may be rewritten with the `?` operator under a newer MSRV",
+            blocks(blocked)
+        ),
+        (rewritable, blocked) => format!(
+            "this function contains {} which may be rewritten with the `?` operator, and {} requiring a newer MSRV",
+            blocks(rewritable),
+            blocks(blocked)
+        ),
+    };
+    span_lint_hir_and_then(cx, lint, hir_id, cx.tcx.def_span(owner), msg, |diag| {
+        for (span, note) in notes.into_iter().chain(extra_notes) {
+            diag.span_note(span, note);
+        }
+        if !replacements.is_empty() {
+            diag.multipart_suggestion("replace them with", replacements, applicability);
+        }
+        if !machine_applicable_only {
+            for (span, alternatives, body_rewrite) in alternatives {
+                emit_alternatives(diag, span, alternatives, body_rewrite);
+            }
+        }
+        for kind in QuestionMarkKind::ALL {
+            if kinds[kind as usize] {
+                kind.docs_link(diag);
+            }
+        }
+    });
+}
+
+/// Returns the less certain of the two applicabilities.
+fn min_applicability(a: Applicability, b: Applicability) -> Applicability {
+    let rank = |app| match app {
+        Applicability::MachineApplicable => 3,
+        Applicability::MaybeIncorrect => 2,
+        Applicability::HasPlaceholders => 1,
+        Applicability::Unspecified => 0,
+    };
+    if rank(a) <= rank(b) { a } else { b }
+}
+
+/// Returns the spans of the explicit `use` imports of `None`/`Some`/`Ok`/`Err` which would become
+/// unused once the `replaced` spans are rewritten to use the `?` operator.
+///
+/// This is deliberately conservative, and gives up whenever usage can't be determined with
+/// certainty: only private imports directly in the enclosing module are considered, every name
+/// imported by a `use` item has to become unused for it to be removed, and modules containing
+/// other modules are skipped entirely, since those could be using the imports through a glob.
+fn dead_variant_imports(cx: &LateContext<'_>, hir_id: HirId, replaced: &[Span]) -> Vec<Span> {
+    let module = cx.tcx.parent_module(hir_id);
+    let module_items = cx.tcx.hir_module_items(module);
+    let is_module_level = |item: &Item<'_>| {
+        cx.tcx
+            .hir()
+            .parent_owner_iter(item.hir_id())
+            .find(|(_, node)| {
+                !matches!(
+                    node,
+                    OwnerNode::Item(Item {
+                        kind: ItemKind::Use(..),
+                        ..
+                    })
+                )
+            })
+            .is_some_and(|(owner, _)| owner.def_id == module.to_local_def_id())
+    };
+
+    let mut stems = Vec::new();
+    let mut imports = Vec::new();
+    for item_id in module_items.free_items() {
+        let item = cx.tcx.hir().item(item_id);
+        match item.kind {
+            ItemKind::Mod(..) => return Vec::new(),
+            ItemKind::Use(path, kind) if !item.span.from_expansion() && is_module_level(item) => match kind {
+                UseKind::ListStem => stems.push(item),
+                UseKind::Single => {
+                    let variant_res = path.res.iter().copied().find(|&res| {
+                        [OptionNone, OptionSome, ResultOk, ResultErr]
+                            .into_iter()
+                            .any(|variant| is_res_lang_ctor(cx, res, variant))
+                    });
+                    imports.push((item, variant_res));
+                },
+                UseKind::Glob => {},
+            },
+            _ => {},
+        }
+    }
+    if imports.iter().all(|(_, res)| res.is_none()) {
+        return Vec::new();
+    }
+
+    let mut visitor = VariantUseVisitor {
+        cx,
+        replaced,
+        uses: imports
+            .iter()
+            .map(|&(item, res)| res.map(|res| (item.ident.name, res)))
+            .collect(),
+        used_inside: vec![false; imports.len()],
+        used_outside: vec![false; imports.len()],
+    };
+    for owner in module_items.definitions() {
+        if let Some(body) = cx.tcx.hir().maybe_body_owned_by(owner) {
+            visitor.visit_body(body);
+        }
+    }
+    let is_dead = |i: usize| visitor.uses[i].is_some() && visitor.used_inside[i] && !visitor.used_outside[i];
+
+    let mut dead_imports = Vec::new();
+    for (i, (item, _)) in imports.iter().enumerate() {
+        // `use std::option::Option::None;`
+        if !item.vis_span.is_empty() {
+            continue;
+        }
+        if !stems.iter().any(|stem| stem.span.contains(item.span)) && is_dead(i) {
+            dead_imports.push(item.span.with_leading_whitespace(cx).with_ctxt(item.span.ctxt()));
+        }
+    }
+    for stem in stems {
+        // `use std::option::Option::{None, Some};`
+        if !stem.vis_span.is_empty() {
+            continue;
+        }
+        let mut leaves = imports
+            .iter()
+            .enumerate()
+            .filter(|(_, (item, _))| stem.span.contains(item.span))
+            .peekable();
+        if leaves.peek().is_some() && leaves.all(|(i, _)| is_dead(i)) {
+            dead_imports.push(stem.span.with_leading_whitespace(cx).with_ctxt(stem.span.ctxt()));
+        }
+    }
+    dead_imports
+}
+
+/// Looks for single segment paths (e.g. `None`, but not `Option::None`) referring to the imported
+/// variants, and records whether they are used inside and outside of the replaced spans.
+struct VariantUseVisitor<'a, 'tcx> {
+    cx: &'a LateContext<'tcx>,
+    replaced: &'a [Span],
+    uses: Vec<Option<(Symbol, Res)>>,
+    used_inside: Vec<bool>,
+    used_outside: Vec<bool>,
+}
+
+impl<'tcx> Visitor<'tcx> for VariantUseVisitor<'_, 'tcx> {
+    type NestedFilter = nested_filter::OnlyBodies;
+
+    fn visit_path(&mut self, path: &rustc_hir::Path<'tcx>, _: HirId) {
+        if let [segment] = path.segments {
+            for (i, import) in self.uses.iter().enumerate() {
+                if *import == Some((segment.ident.name, path.res)) {
+                    if self.replaced.iter().any(|replaced| replaced.contains(path.span)) {
+                        self.used_inside[i] = true;
+                    } else {
+                        self.used_outside[i] = true;
+                    }
+                }
+            }
+        }
+        walk_path(self, path);
+    }
+
+    fn nested_visit_map(&mut self) -> Self::Map {
+        self.cx.tcx.hir()
+    }
+}
+
 fn is_early_return(smbl: Symbol, cx: &LateContext<'_>, if_block: &IfBlockType<'_>) -> bool {
     match *if_block {
         IfBlockType::IfIs(caller, caller_ty, call_sym, if_then) => {
@@ -172,8 +862,9 @@ fn is_early_return(smbl: Symbol, cx: &LateContext<'_>, if_block: &IfBlockType<'_
             is_type_diagnostic_item(cx, let_expr_ty, smbl)
                 && match smbl {
                     sym::Option => {
-                        // We only need to check `if let Some(x) = option` not `if let None = option`,
-                        // because the later one will be suggested as `if option.is_none()` thus causing conflict.
+                        // `if let None = option` is handled along with the other wildcard patterns by
+                        // `check_if_let_some_or_err_and_early_return`, and only if
+                        // `redundant_pattern_matching` (suggesting `if option.is_none()`) is allowed.
                         is_res_lang_ctor(cx, res, OptionSome)
                             && if_else.is_some()
                             && expr_return_none_or_err(smbl, cx, if_else.unwrap(), let_expr, None)
@@ -199,22 +890,44 @@ fn expr_return_none_or_err(
     cond_expr: &Expr<'_>,
     err_sym: Option<Symbol>,
 ) -> bool {
-    match peel_blocks_with_stmt(expr).kind {
+    // An identity `.into()` on the returned value doesn't change it
+    let expr = peel_identity_into(cx, peel_early_return_blocks(expr));
+    match expr.kind {
         ExprKind::Ret(Some(ret_expr)) => expr_return_none_or_err(smbl, cx, ret_expr, cond_expr, err_sym),
+        // `None?` and `Err(e)?` return just like `return None` and `return Err(e.into())` do
+        ExprKind::Match(scrutinee, _, MatchSource::TryDesugar(_)) => {
+            if let ExprKind::Call(branch, [tried]) = scrutinee.kind
+                && matches!(
+                    branch.kind,
+                    ExprKind::Path(QPath::LangItem(LangItem::TryTraitBranch, ..))
+                )
+                && path_to_local(tried).is_none()
+            {
+                expr_return_none_or_err(smbl, cx, tried, cond_expr, err_sym)
+            } else {
+                false
+            }
+        },
         ExprKind::Path(ref qpath) => match smbl {
-            sym::Option => is_res_lang_ctor(cx, cx.qpath_res(qpath, expr.hir_id), OptionNone),
+            // `qpath_res` also resolves type-relative paths, so that `Self::None` in an impl on
+            // `Option<_>` and `None` through a type alias (`MaybeFoo::None`) are recognized as well
+            sym::Option => {
+                let res = cx.qpath_res(qpath, expr.hir_id);
+                is_res_lang_ctor(cx, res, OptionNone) || is_none_const(cx, res)
+            },
             sym::Result => path_to_local(expr).is_some() && path_to_local(expr) == path_to_local(cond_expr),
             _ => false,
         },
         ExprKind::Call(call_expr, [arg]) => {
+            // Resolve the constructor instead of looking at its name, so that `Result::Err(e)` and
+            // `Err` through a type alias (`Res::Err(e)`) are recognized as well
             if smbl == sym::Result
-                && let ExprKind::Path(QPath::Resolved(_, path)) = &call_expr.kind
-                && let Some(segment) = path.segments.first()
+                && is_res_lang_ctor(cx, path_res(cx, call_expr), ResultErr)
                 && let Some(err_sym) = err_sym
-                && let ExprKind::Path(QPath::Resolved(_, arg_path)) = &arg.kind
-                && let Some(PathSegment { ident, .. }) = arg_path.segments.first()
+                && let ExprKind::Path(QPath::Resolved(None, arg_path)) = &arg.kind
+                && let [PathSegment { ident, .. }] = arg_path.segments
             {
-                return segment.ident.name == sym::Err && err_sym == ident.name;
+                return err_sym == ident.name;
             }
             false
         },
@@ -222,6 +935,106 @@ fn expr_return_none_or_err(
     }
 }
 
+/// Like `peel_blocks_with_stmt`, but also looks through `unsafe` blocks, which aren't needed
+/// around the `?` operator. Labeled blocks are kept, as they may be the target of a `break`.
+fn peel_early_return_blocks<'a>(mut expr: &'a Expr<'a>) -> &'a Expr<'a> {
+    while let ExprKind::Block(block, None) = expr.kind
+        && matches!(
+            block.rules,
+            BlockCheckMode::DefaultBlock | BlockCheckMode::UnsafeBlock(UnsafeSource::UserProvided)
+        )
+        && let Some(inner) = match (block.stmts, block.expr) {
+            ([], Some(inner)) => Some(inner),
+            (
+                [
+                    Stmt {
+                        kind: StmtKind::Expr(inner) | StmtKind::Semi(inner),
+                        ..
+                    },
+                ],
+                None,
+            ) => Some(inner),
+            _ => None,
+        }
+    {
+        expr = inner;
+    }
+    expr
+}
+
+/// Checks if `res` is a constant (e.g. `const NONE: Option<Foo> = None;`) whose value is `None`.
+fn is_none_const(cx: &LateContext<'_>, res: Res) -> bool {
+    if let Res::Def(DefKind::Const | DefKind::AssocConst, def_id) = res
+        && let ty = cx.tcx.type_of(def_id).instantiate_identity()
+        && let ty::Adt(adt, _) = ty.kind()
+        && cx.tcx.is_diagnostic_item(sym::Option, adt.did())
+        && let Ok(val) = cx.tcx.const_eval_poly(def_id)
+        && let Some(destructured) = cx.tcx.try_destructure_mir_constant_for_user_output(val, ty)
+        && let Some(variant) = destructured.variant
+    {
+        cx.tcx.lang_items().get(OptionNone) == Some(adt.variant(variant).def_id)
+    } else {
+        false
+    }
+}
+
+/// Returns the receiver of an `is_none()`/`is_err()`-like `if` condition, together with the name
+/// of the method the condition is equivalent to.
+///
+/// Besides plain method calls, this also accepts comparisons against `None` (`x == None`,
+/// `None == x`) and the degenerate `x.is_none() == true`/`x.is_some() == false` forms (and their
+/// `Result` counterparts).
+///
+/// A single `.ok()`/`.err()` adapter on a `Result` is looked through as well, so that
+/// `r.err().is_some()` and `r.ok().is_none()` are both treated as `r.is_err()`.
+fn is_none_or_err_cond<'tcx>(cx: &LateContext<'tcx>, cond: &'tcx Expr<'tcx>) -> Option<(&'tcx Expr<'tcx>, Symbol)> {
+    let (caller, name) = option_or_result_cond(cx, cond)?;
+    if let ExprKind::MethodCall(adapter, result, [], _) = caller.kind
+        && is_type_diagnostic_item(cx, typeck_results(cx).expr_ty(result), sym::Result)
+    {
+        let name = match (adapter.ident.as_str(), name.as_str()) {
+            ("err", "is_some") | ("ok", "is_none") => "is_err",
+            ("err", "is_none") | ("ok", "is_some") => "is_ok",
+            _ => return Some((caller, name)),
+        };
+        return Some((result, Symbol::intern(name)));
+    }
+    Some((caller, name))
+}
+
+fn option_or_result_cond<'tcx>(cx: &LateContext<'tcx>, cond: &'tcx Expr<'tcx>) -> Option<(&'tcx Expr<'tcx>, Symbol)> {
+    match cond.kind {
+        ExprKind::MethodCall(segment, caller, [], _) => Some((caller, segment.ident.name)),
+        ExprKind::Binary(op, lhs, rhs) if op.node == BinOpKind::Eq => {
+            let is_none_ctor = |e: &Expr<'_>| is_res_lang_ctor(cx, path_res(cx, e), OptionNone);
+            match (is_none_ctor(lhs), is_none_ctor(rhs)) {
+                (false, true) => return Some((lhs, Symbol::intern("is_none"))),
+                (true, false) => return Some((rhs, Symbol::intern("is_none"))),
+                _ => {},
+            }
+
+            let (call, lit) = match (lhs.kind, rhs.kind) {
+                (_, ExprKind::Lit(lit)) => (lhs, lit),
+                (ExprKind::Lit(lit), _) => (rhs, lit),
+                _ => return None,
+            };
+            if let ExprKind::MethodCall(segment, caller, [], _) = call.kind
+                && let LitKind::Bool(value) = lit.node
+            {
+                let name = match (segment.ident.as_str(), value) {
+                    ("is_none", true) | ("is_some", false) => "is_none",
+                    ("is_err", true) | ("is_ok", false) => "is_err",
+                    _ => return None,
+                };
+                Some((caller, Symbol::intern(name)))
+            } else {
+                None
+            }
+        },
+        _ => None,
+    }
+}
+
 /// Checks if the given expression on the given context matches the following structure:
 ///
 /// ```ignore
@@ -236,152 +1049,4333 @@ fn expr_return_none_or_err(
 /// }
 /// ```
 ///
-/// If it matches, it will suggest to use the question mark operator instead
-fn check_is_none_or_err_and_early_return<'tcx>(cx: &LateContext<'tcx>, expr: &Expr<'tcx>) {
+/// If it matches, returns the suggested replacement using the question mark operator.
+fn is_none_or_err_and_early_return_sugg<'tcx>(
+    cx: &LateContext<'tcx>,
+    expr: &Expr<'tcx>,
+    applicability: &mut Applicability,
+) -> Option<String> {
     if let Some(higher::If { cond, then, r#else }) = higher::If::hir(expr)
         && !is_else_clause(cx.tcx, expr)
-        && let ExprKind::MethodCall(segment, caller, [], _) = &cond.kind
-        && let caller_ty = cx.typeck_results().expr_ty(caller)
-        && let if_block = IfBlockType::IfIs(caller, caller_ty, segment.ident.name, then)
-        && (is_early_return(sym::Option, cx, &if_block) || is_early_return(sym::Result, cx, &if_block))
+        // Statements or attributes that were configured out are still part of the source we'd replace
+        && !span_contains_cfg_or_cfg_attr(cx, expr.span)
+        // A condition expanded from a single macro call has no receiver in the source to spell out
+        && cond.span.ctxt() == expr.span.ctxt()
+        && let Some((caller, call_sym)) = is_none_or_err_cond(cx, cond)
+        && let caller_ty = typeck_results(cx).expr_ty(caller)
+        && let derefs = borrowed_place_derefs(cx, caller)
+        && let checked_ty = if derefs.is_some() {
+            peel_receiver_ref(typeck_results(cx).expr_ty_adjusted(caller))
+        } else {
+            peel_receiver_ref(caller_ty)
+        }
+        && let if_block = IfBlockType::IfIs(caller, checked_ty, call_sym, then)
+        && (is_early_return(sym::Option, cx, &if_block)
+            || is_early_return(sym::Result, cx, &if_block)
+            || returns_caller_again(cx, expr, caller, call_sym, then, r#else))
     {
-        let mut applicability = Applicability::MachineApplicable;
-        let receiver_str = snippet_with_applicability(cx, caller.span, "..", &mut applicability);
-        let by_ref = !caller_ty.is_copy_modulo_regions(cx.tcx, cx.param_env)
-            && !matches!(caller.kind, ExprKind::Call(..) | ExprKind::MethodCall(..));
-        let sugg = if let Some(else_inner) = r#else {
-            if eq_expr_value(cx, caller, peel_blocks(else_inner)) {
-                format!("Some({receiver_str}?)")
+        // `caller` is evaluated once instead of twice
+        if !is_early_return(sym::Result, cx, &if_block) && call_sym.as_str() == "is_err" {
+            *applicability = Applicability::MaybeIncorrect;
+        }
+        if let Some(derefs) = derefs {
+            // The checked value can't be moved out of its place, nor be returned without a clone
+            if r#else.is_some() {
+                return None;
+            }
+            let receiver_str = snippet_with_applicability(cx, caller.span, "..", applicability);
+            let method_call_str = if checked_ty.is_copy_modulo_regions(cx.tcx, cx.param_env) {
+                ""
             } else {
-                return;
+                ".as_ref()"
+            };
+            if (!method_call_str.is_empty() && is_type_diagnostic_item(cx, checked_ty, sym::Result))
+                || !nested_payloads_line_up(cx, expr.hir_id, checked_ty)
+            {
+                *applicability = min_applicability(*applicability, Applicability::MaybeIncorrect);
+            }
+            // The span of an explicitly dereferenced receiver includes its parentheses
+            if derefs == 0 && receiver_str.starts_with('(') {
+                return Some(format!("{receiver_str}{method_call_str}?;"));
+            }
+            return Some(format!("({}{receiver_str}){method_call_str}?;", "*".repeat(derefs)));
+        }
+        let receiver_str = snippet_with_applicability(cx, caller.span, "..", applicability);
+        let method_call_str = receiver_method_call(cx, expr, caller, None, applicability);
+        // `Result::as_ref` also borrows the error, which then has to be converted to the returned one
+        if (!method_call_str.is_empty() && is_type_diagnostic_item(cx, peel_receiver_ref(caller_ty), sym::Result))
+            || !nested_payloads_line_up(cx, expr.hir_id, peel_receiver_ref(caller_ty))
+        {
+            *applicability = min_applicability(*applicability, Applicability::MaybeIncorrect);
+        }
+        if let Some(else_inner) = r#else {
+            // Returning the borrowed receiver itself would need it to be cloned
+            if caller_ty.is_ref() || !eq_expr_value(cx, caller, peel_blocks(else_inner)) {
+                None
+            } else if is_returned_value(cx, expr) {
+                // `needless_question_mark` would rewrite `Some(receiver?)` to this right away
+                Some(receiver_str.into_owned())
+            } else {
+                // Anywhere else, the value has to be wrapped again to keep its type
+                Some(format!("Some({receiver_str}?)"))
             }
         } else {
-            format!("{receiver_str}{}?;", if by_ref { ".as_ref()" } else { "" })
-        };
+            Some(format!("{receiver_str}{method_call_str}?;"))
+        }
+    } else {
+        None
+    }
+}
 
-        span_lint_and_sugg(
-            cx,
-            QUESTION_MARK,
-            expr.span,
-            "this block may be rewritten with the `?` operator",
-            "replace it with",
-            sugg,
-            applicability,
-        );
+/// Checks if `expr` is the value returned by the enclosing body, either as its tail expression or
+/// as the operand of `return`.
+fn is_returned_value(cx: &LateContext<'_>, expr: &Expr<'_>) -> bool {
+    if let Node::Expr(parent) = cx.tcx.parent_hir_node(expr.hir_id)
+        && let ExprKind::Ret(_) = parent.kind
+    {
+        return true;
+    }
+    cx.enclosing_body
+        .is_some_and(|body| peel_blocks(cx.tcx.hir().body(body).value).hir_id == expr.hir_id)
+}
+
+/// Checks for `if check(x).is_err() { return check(x); }` as the only statement of a function
+/// body returning `Ok(())`. The result of the call is returned again instead of being passed on,
+/// which `?` can only replace if the call gives the same result both times.
+fn returns_caller_again<'tcx>(
+    cx: &LateContext<'tcx>,
+    guard: &Expr<'tcx>,
+    caller: &Expr<'tcx>,
+    call_sym: Symbol,
+    then: &Expr<'tcx>,
+    r#else: Option<&Expr<'tcx>>,
+) -> bool {
+    if r#else.is_none()
+        && call_sym.as_str() == "is_err"
+        && matches!(caller.kind, ExprKind::Call(..) | ExprKind::MethodCall(..))
+        && let ExprKind::Ret(Some(ret)) = peel_early_return_blocks(then).kind
+    {
+        SpanlessEq::new(cx).eq_expr(ret, caller) && unit_body_rest(cx, guard, caller).is_some()
+    } else {
+        false
+    }
+}
+
+/// If `guard` is the only statement of a function body ending in `Ok(())` or `Some(())`, and its
+/// `scrutinee` has the type of the body, returns the span of the guard and the tail: the whole
+/// body can be replaced with `scrutinee`.
+fn unit_body_rest(cx: &LateContext<'_>, guard: &Expr<'_>, scrutinee: &Expr<'_>) -> Option<Span> {
+    if let Node::Stmt(stmt) = cx.tcx.parent_hir_node(guard.hir_id)
+        && let Node::Block(block) = cx.tcx.parent_hir_node(stmt.hir_id)
+        && let [only] = block.stmts
+        && only.hir_id == stmt.hir_id
+        && let Some(tail) = block.expr
+        && let ExprKind::Call(ctor, [unit]) = tail.kind
+        && matches!(unit.kind, ExprKind::Tup([]))
+        && let res = path_res(cx, ctor)
+        && (is_res_lang_ctor(cx, res, ResultOk) || is_res_lang_ctor(cx, res, OptionSome))
+        && let Node::Expr(body_expr) = cx.tcx.parent_hir_node(block.hir_id)
+        && let Some(body) = cx.enclosing_body
+        && cx.tcx.hir().body(body).value.hir_id == body_expr.hir_id
+        && !block.span.from_expansion()
+        && tail.span.ctxt() == stmt.span.ctxt()
+        && typeck_results(cx).expr_ty(scrutinee) == typeck_results(cx).expr_ty(tail)
+    {
+        Some(stmt.span.to(tail.span))
+    } else {
+        None
+    }
+}
+
+/// If `guard` is all its function body does besides returning success, and `sugg` passes its
+/// failure on unchanged, offers replacing the whole body with the checked value.
+fn add_body_rewrite<'tcx>(cx: &LateContext<'tcx>, guard: &'tcx Expr<'tcx>, sugg: &str, extra: &mut Option<ExtraEdits>) {
+    if let Some(scrutinee) = guard_scrutinee(cx, guard)
+        && let Some(scrutinee_str) = scrutinee.span.get_source_text(cx)
+        && sugg.strip_suffix(';').unwrap_or(sugg) == format!("{scrutinee_str}?")
+        && let Some(body) = unit_body_rest(cx, guard, scrutinee)
+    {
+        extra
+            .get_or_insert_with(|| ExtraEdits {
+                help: "replace it with",
+                replacements: Vec::new(),
+                notes: Vec::new(),
+                alternatives: Vec::new(),
+                body_rewrite: None,
+            })
+            .body_rewrite = Some((body, scrutinee_str.to_string()));
     }
 }
 
-fn check_if_let_some_or_err_and_early_return<'tcx>(cx: &LateContext<'tcx>, expr: &Expr<'tcx>) {
+/// Returns the expression checked by a guard which early returns when it fails, like the
+/// `check(x)` of `if check(x).is_err() { .. }` or `if let Err(e) = check(x) { .. }`.
+fn guard_scrutinee<'tcx>(cx: &LateContext<'tcx>, guard: &'tcx Expr<'tcx>) -> Option<&'tcx Expr<'tcx>> {
     if let Some(higher::IfLet {
         let_pat,
         let_expr,
-        if_then,
-        if_else,
+        if_else: None,
         ..
-    }) = higher::IfLet::hir(cx, expr)
-        && !is_else_clause(cx.tcx, expr)
-        && let PatKind::TupleStruct(ref path1, [field], ddpos) = let_pat.kind
-        && ddpos.as_opt_usize().is_none()
-        && let PatKind::Binding(BindingMode(by_ref, _), bind_id, ident, None) = field.kind
-        && let caller_ty = cx.typeck_results().expr_ty(let_expr)
-        && let if_block = IfBlockType::IfLet(
-            cx.qpath_res(path1, let_pat.hir_id),
-            caller_ty,
+    }) = higher::IfLet::hir(cx, guard)
+    {
+        let res = match let_pat.kind {
+            PatKind::TupleStruct(ref qpath, ..) => cx.qpath_res(qpath, let_pat.hir_id),
+            PatKind::Path(ref qpath) => cx.qpath_res(qpath, let_pat.hir_id),
+            _ => return None,
+        };
+        (is_res_lang_ctor(cx, res, ResultErr) || is_res_lang_ctor(cx, res, OptionNone)).then_some(let_expr)
+    } else if let Some(higher::If { cond, r#else: None, .. }) = higher::If::hir(guard) {
+        is_none_or_err_cond(cx, cond).map(|(caller, _)| caller)
+    } else {
+        None
+    }
+}
+
+/// If `ty` is a `Result` whose error type is uninhabited, i.e. `Infallible` or `!`, returns that
+/// error type. Early returning the error of such a `Result` is dead code.
+fn uninhabited_result_err_ty<'tcx>(cx: &LateContext<'tcx>, ty: Ty<'tcx>) -> Option<Ty<'tcx>> {
+    if let ty::Adt(adt, args) = ty.kind()
+        && cx.tcx.is_diagnostic_item(sym::Result, adt.did())
+        && let err_ty = args.type_at(1)
+        && (err_ty.is_never() || match_type(cx, err_ty, &paths::CONVERT_INFALLIBLE))
+    {
+        Some(err_ty)
+    } else {
+        None
+    }
+}
+
+/// Notes that `scrutinee` can't hold an error, for suggestions unwrapping it rather than using `?`.
+fn uninhabited_err_note(cx: &LateContext<'_>, scrutinee: &Expr<'_>, err_ty: Ty<'_>) -> ExtraEdits {
+    let mut app = Applicability::MaybeIncorrect;
+    let scrutinee_str = snippet_with_applicability(cx, scrutinee.span, "..", &mut app);
+    ExtraEdits {
+        help: "replace it with",
+        replacements: Vec::new(),
+        notes: vec![(
+            scrutinee.span,
+            format!("the error type of `{scrutinee_str}` is `{err_ty}`, so it is always `Ok`"),
+        )],
+        alternatives: Vec::new(),
+        body_rewrite: None,
+    }
+}
+
+/// Finds a temporary with a significant `Drop` created by `expr`, i.e. a call whose result is
+/// borrowed or projected from rather than moved, and returns its span and type.
+fn significant_temporary<'tcx>(cx: &LateContext<'tcx>, expr: &'tcx Expr<'tcx>) -> Option<(Span, Ty<'tcx>)> {
+    let typeck = typeck_results(cx);
+    let mut temporaries = HirIdSet::default();
+    for_each_expr_without_closures(expr, |e| {
+        match e.kind {
+            ExprKind::Field(base, _) | ExprKind::Index(base, ..) => {
+                temporaries.insert(base.hir_id);
+            },
+            ExprKind::MethodCall(_, receiver, ..) => {
+                if typeck
+                    .expr_adjustments(receiver)
+                    .iter()
+                    .any(|adjust| matches!(adjust.kind, Adjust::Borrow(_) | Adjust::Deref(Some(_))))
+                {
+                    temporaries.insert(receiver.hir_id);
+                }
+            },
+            _ => {},
+        }
+        if matches!(e.kind, ExprKind::Call(..) | ExprKind::MethodCall(..))
+            && temporaries.contains(&e.hir_id)
+            && let ty = typeck.expr_ty(e)
+            && needs_ordered_drop(cx, ty)
+        {
+            return ControlFlow::Break((e.span, ty));
+        }
+        ControlFlow::Continue(())
+    })
+}
+
+/// A statement following an `is_none()`-like guard which moves out of, unwraps or overwrites the
+/// local being checked.
+enum FollowingUse {
+    /// `drop(local);`, the span is the one of the whole statement.
+    Drop(Span),
+    /// `local = ..`, the span is the one of the assignment.
+    Assign(Span),
+    /// `let x = local.unwrap();` or `let x = local.expect("..");`
+    Unwrap {
+        /// The span of the whole statement.
+        stmt: Span,
+        /// The span of the statement up to the unwrapped local, i.e. `let x = `.
+        binding: Span,
+        /// `unwrap` or `expect`.
+        method: Symbol,
+        /// Whether there are other statements between the guard and this one.
+        after_others: bool,
+    },
+    /// `(local.unwrap())(..);` or `local.expect("..")(..);`, with `local` not being used
+    /// afterwards.
+    CallUnwrapped {
+        /// The span of the `unwrap` or `expect` call.
+        unwrap: Span,
+        /// Whether there are other statements between the guard and this one.
+        after_others: bool,
+    },
+    /// `if local.unwrap() > 10 { .. }` right after the guard, with the `unwrap` or `expect` call
+    /// being evaluated before anything else in the condition which could have side effects.
+    UnwrapInCondition {
+        /// The span between the guard and the `if`.
+        gap: Span,
+        /// The span of the `unwrap` or `expect` call.
+        unwrap: Span,
+        /// Whether the call is the only use of `local` after the guard.
+        only_use: bool,
+    },
+    /// `match local { Some(v) => f(v), None => unreachable!() }` right after the guard, with the
+    /// `None` or `Err` arm panicking, as it can't be reached anymore.
+    UnreachableMatch {
+        /// The span between the guard and the `match`.
+        gap: Span,
+        /// The span of the `match` expression.
+        match_span: Span,
+        /// The span of the binding of the value, e.g. `mut v`.
+        binding: Span,
+        /// The span of the body of the arm binding the value.
+        body: Span,
+        /// If the body isn't a block and uses the binding once, before anything else with side
+        /// effects, the span of that use.
+        single_use: Option<Span>,
+        /// Whether the `match` is used as a value, rather than being a statement.
+        value_position: bool,
+        /// Whether the name of the binding is used after the `match`, where a `let` statement
+        /// binding the value in place of the guard would shadow something else.
+        name_used_later: bool,
+        /// Whether replacing the `match` with the body requires a semicolon after it.
+        needs_semi: bool,
+    },
+}
+
+/// Scans the statements following `guard` in its enclosing block for an explicit `drop` of, an
+/// `unwrap` of, a call of the unwrapped, or an assignment to `local`, stopping at any other use of
+/// it. Calls to closures capturing `local` count as a use as well.
+fn following_use<'tcx>(cx: &LateContext<'tcx>, guard: &Expr<'tcx>, local: HirId) -> Option<FollowingUse> {
+    let mut parents = cx.tcx.hir().parent_iter(guard.hir_id);
+    let (Some((stmt_id, Node::Stmt(_))), Some((_, Node::Block(block)))) = (parents.next(), parents.next()) else {
+        return None;
+    };
+    let pos = block.stmts.iter().position(|stmt| stmt.hir_id == stmt_id)?;
+    if pos + 1 == block.stmts.len()
+        && let Some(tail) = block.expr
+    {
+        return unreachable_match_use(cx, guard, block, tail, local);
+    }
+    for (i, stmt) in block.stmts[pos + 1..].iter().enumerate() {
+        // The value would be moved out before a `break` or `continue` which may still need it
+        if contains_loop_exit(stmt) {
+            return None;
+        }
+        if let StmtKind::Let(LetStmt {
+            init: Some(init),
+            els: None,
+            ..
+        }) = stmt.kind
+            && let Some(method) = unwrap_of(init, local)
+            && !stmt.span.from_expansion()
+        {
+            return Some(FollowingUse::Unwrap {
+                stmt: stmt.span,
+                binding: stmt.span.until(init.span),
+                method,
+                after_others: i > 0,
+            });
+        }
+        if i == 0
+            && let StmtKind::Semi(e) | StmtKind::Expr(e) = stmt.kind
+            && let Some(higher::If { cond, .. }) = higher::If::hir(e)
+            && let Some(unwrap) = unconditional_unwrap_of(cond, local)
+            && !stmt.span.from_expansion()
+            && let gap = guard.span.between(stmt.span)
+            && !span_contains_comment(cx.sess().source_map(), gap)
+        {
+            let mut uses = 0;
+            for_each_expr(cx, e, |e| {
+                if path_to_local_id(e, local) {
+                    uses += 1;
+                }
+                ControlFlow::<()>::Continue(())
+            });
+            return Some(FollowingUse::UnwrapInCondition {
+                gap,
+                unwrap: unwrap.span,
+                only_use: uses == 1
+                    && !block.stmts[pos + 2..].iter().any(|s| is_local_used(cx, s, local))
+                    && !block.expr.is_some_and(|e| is_local_used(cx, e, local)),
+            });
+        }
+        if i == 0
+            && let StmtKind::Semi(e)
+            | StmtKind::Expr(e)
+            | StmtKind::Let(LetStmt {
+                init: Some(e),
+                els: None,
+                ..
+            }) = stmt.kind
+            && let Some(found) = unreachable_match_use(cx, guard, block, e, local)
+        {
+            return Some(found);
+        }
+        if let StmtKind::Semi(e) | StmtKind::Expr(e) = stmt.kind {
+            match e.kind {
+                // The value can be bound in place of the guard, but calling it has to stay here, as
+                // it may borrow values which are only available from here on
+                ExprKind::Call(callee, args)
+                    if unwrap_of(callee, local).is_some()
+                        && !stmt.span.from_expansion()
+                        && !args.iter().any(|arg| is_local_used(cx, arg, local))
+                        && !block.stmts[pos + i + 2..].iter().any(|s| is_local_used(cx, s, local))
+                        && !block.expr.is_some_and(|e| is_local_used(cx, e, local)) =>
+                {
+                    return Some(FollowingUse::CallUnwrapped {
+                        unwrap: callee.span,
+                        after_others: i > 0,
+                    });
+                },
+                ExprKind::Call(func, [arg])
+                    if path_to_local_id(arg, local)
+                        && path_res(cx, func)
+                            .opt_def_id()
+                            .is_some_and(|id| cx.tcx.is_diagnostic_item(sym::mem_drop, id)) =>
+                {
+                    return Some(FollowingUse::Drop(stmt.span));
+                },
+                ExprKind::Assign(lhs, _, _) if path_to_local_id(lhs, local) => {
+                    return Some(FollowingUse::Assign(e.span));
+                },
+                _ => {},
+            }
+        }
+        if is_local_used(cx, stmt, local) || calls_closure_capturing(cx, stmt, local) {
+            return None;
+        }
+    }
+    None
+}
+
+/// Checks if `e`, which is in the statement or expression of `block` right after `guard`, is a
+/// `match` on `local` with one arm binding the value and the `None` or `Err` arm panicking.
+fn unreachable_match_use<'tcx>(
+    cx: &LateContext<'tcx>,
+    guard: &Expr<'tcx>,
+    block: &'tcx Block<'tcx>,
+    e: &'tcx Expr<'tcx>,
+    local: HirId,
+) -> Option<FollowingUse> {
+    let ExprKind::Match(scrutinee, [first, second], MatchSource::Normal) = e.kind else {
+        return None;
+    };
+    if !path_to_local_id(scrutinee, local) || e.span.from_expansion() || first.guard.is_some() || second.guard.is_some()
+    {
+        return None;
+    }
+    let (value_arm, dead_arm) = if is_panicking_arm(cx, second) {
+        (first, second)
+    } else {
+        (second, first)
+    };
+    let PatKind::TupleStruct(ref path, [field], ddpos) = value_arm.pat.kind else {
+        return None;
+    };
+    let PatKind::Binding(BindingMode(ByRef::No, _), bind_id, _, None) = field.kind else {
+        return None;
+    };
+    let res = cx.qpath_res(path, value_arm.pat.hir_id);
+    if !is_panicking_arm(cx, dead_arm)
+        || ddpos.as_opt_usize().is_some()
+        || !(is_res_lang_ctor(cx, res, OptionSome) || is_res_lang_ctor(cx, res, ResultOk))
+    {
+        return None;
+    }
+    let body = value_arm.body;
+    // A body expanded from a macro (e.g. `println!("{v}")`) is kept as written
+    let body_span = walk_span_to_context(body.span, e.span.ctxt())?;
+    // Without a trailing semicolon, the body needs one in place of the `match` unless it's a block
+    let (next, value_position, needs_semi) = match cx.tcx.parent_hir_node(e.hir_id) {
+        Node::Stmt(stmt) => (stmt.span, false, matches!(stmt.kind, StmtKind::Expr(_))),
+        Node::LetStmt(let_stmt) => (let_stmt.span, true, false),
+        _ => (e.span, true, false),
+    };
+    let gap = guard.span.between(next);
+    let mut uses = 0;
+    for_each_expr(cx, body, |e| {
+        if path_to_local_id(e, bind_id) {
+            uses += 1;
+        }
+        ControlFlow::<()>::Continue(())
+    });
+    let single_use = if uses == 1
+        && !matches!(body.kind, ExprKind::Block(..))
+        && !body.span.from_expansion()
+        && !span_contains_comment(cx.sess().source_map(), gap)
+        && let Some(used) = first_evaluated_use(body, bind_id)
+        && used.span.ctxt() == body.span.ctxt()
+    {
+        Some(used.span)
+    } else {
+        None
+    };
+    let name = cx.tcx.hir().name(bind_id);
+    let name_used_later = block
+        .stmts
+        .iter()
+        .map(|stmt| stmt.span)
+        .chain(block.expr.map(|e| e.span))
+        .any(|span| span.lo() > next.hi() && span_uses_name(cx, block, span, name));
+    Some(FollowingUse::UnreachableMatch {
+        gap,
+        match_span: e.span,
+        binding: field.span,
+        body: body_span,
+        single_use,
+        value_position,
+        name_used_later,
+        needs_semi: needs_semi && !matches!(body.kind, ExprKind::Block(..)),
+    })
+}
+
+/// Checks if a path named `name` is used within `span` in `block`.
+fn span_uses_name<'tcx>(cx: &LateContext<'tcx>, block: &'tcx Block<'tcx>, span: Span, name: Symbol) -> bool {
+    for_each_expr(cx, block, |e| {
+        if span.contains(e.span)
+            && let ExprKind::Path(QPath::Resolved(None, path)) = e.kind
+            && let [segment] = path.segments
+            && segment.ident.name == name
+        {
+            ControlFlow::Break(())
+        } else {
+            ControlFlow::Continue(())
+        }
+    })
+    .is_some()
+}
+
+/// Checks if `arm` is a `None` or `Err(..)` arm whose body is a call to `unreachable!` or `panic!`.
+fn is_panicking_arm(cx: &LateContext<'_>, arm: &Arm<'_>) -> bool {
+    let is_dead_pat = match arm.pat.kind {
+        PatKind::Path(ref path) => is_res_lang_ctor(cx, cx.qpath_res(path, arm.pat.hir_id), OptionNone),
+        PatKind::TupleStruct(ref path, fields, ddpos) => {
+            is_res_lang_ctor(cx, cx.qpath_res(path, arm.pat.hir_id), ResultErr)
+                && matches!(
+                    (fields, ddpos.as_opt_usize()),
+                    (
+                        [Pat {
+                            kind: PatKind::Wild,
+                            ..
+                        }],
+                        None
+                    ) | ([], Some(0))
+                )
+        },
+        _ => false,
+    };
+    is_dead_pat
+        && arm.guard.is_none()
+        && root_macro_call_first_node(cx, peel_blocks(arm.body)).is_some_and(|macro_call| {
+            is_panic(cx, macro_call.def_id)
+                || matches!(
+                    cx.tcx.get_diagnostic_name(macro_call.def_id),
+                    Some(sym::unreachable_macro | sym::unreachable_2015_macro)
+                )
+        })
+}
+
+/// Finds the use of `binding` in `expr` if it's evaluated before anything else in `expr` which
+/// could have side effects, so that replacing it with `?` returns early just like the guard did.
+fn first_evaluated_use<'tcx>(expr: &'tcx Expr<'tcx>, binding: HirId) -> Option<&'tcx Expr<'tcx>> {
+    if path_to_local_id(expr, binding) {
+        return Some(expr);
+    }
+    // The first use within the operands, which are evaluated in order
+    let in_operands = |operands: &'tcx [Expr<'tcx>]| {
+        for operand in operands {
+            if let Some(used) = first_evaluated_use(operand, binding) {
+                return Some(used);
+            }
+            if !is_side_effect_free(operand) {
+                return None;
+            }
+        }
+        None
+    };
+    match expr.kind {
+        ExprKind::Binary(op, lhs, rhs) => first_evaluated_use(lhs, binding).or_else(|| {
+            (!op.node.is_lazy() && is_side_effect_free(lhs))
+                .then(|| first_evaluated_use(rhs, binding))
+                .flatten()
+        }),
+        ExprKind::Call(callee, args) if is_side_effect_free(callee) => in_operands(args),
+        ExprKind::MethodCall(_, receiver, args, _) => first_evaluated_use(receiver, binding)
+            .or_else(|| is_side_effect_free(receiver).then(|| in_operands(args)).flatten()),
+        ExprKind::Tup(operands) | ExprKind::Array(operands) => in_operands(operands),
+        ExprKind::Unary(_, e)
+        | ExprKind::Field(e, _)
+        | ExprKind::Cast(e, _)
+        | ExprKind::AddrOf(_, _, e)
+        | ExprKind::DropTemps(e) => first_evaluated_use(e, binding),
+        _ => None,
+    }
+}
+
+/// Checks if `stmt` contains a `break` or `continue` expression.
+fn contains_loop_exit<'tcx>(stmt: &'tcx Stmt<'tcx>) -> bool {
+    for_each_expr_without_closures(stmt, |e| {
+        if matches!(e.kind, ExprKind::Break(..) | ExprKind::Continue(_)) {
+            ControlFlow::Break(())
+        } else {
+            ControlFlow::Continue(())
+        }
+    })
+    .is_some()
+}
+
+/// Finds `local.unwrap()` or `local.expect("..")` in `cond`, where it's evaluated whenever `cond`
+/// is, and only after operands without side effects. It mustn't be on the right-hand side of `&&`
+/// or `||`, as replacing it with `?` would then only return early if the left-hand side allows it.
+fn unconditional_unwrap_of<'tcx>(cond: &'tcx Expr<'tcx>, local: HirId) -> Option<&'tcx Expr<'tcx>> {
+    if unwrap_of(cond, local).is_some() {
+        return Some(cond);
+    }
+    match cond.kind {
+        ExprKind::Binary(op, lhs, rhs) => unconditional_unwrap_of(lhs, local).or_else(|| {
+            (!op.node.is_lazy() && is_side_effect_free(lhs))
+                .then(|| unconditional_unwrap_of(rhs, local))
+                .flatten()
+        }),
+        ExprKind::Unary(_, e)
+        | ExprKind::Field(e, _)
+        | ExprKind::MethodCall(_, e, ..)
+        | ExprKind::Cast(e, _)
+        | ExprKind::AddrOf(_, _, e)
+        | ExprKind::DropTemps(e) => unconditional_unwrap_of(e, local),
+        _ => None,
+    }
+}
+
+/// If `expr` is `local.unwrap()` or `local.expect("..")`, returns the name of the method.
+fn unwrap_of(expr: &Expr<'_>, local: HirId) -> Option<Symbol> {
+    if let ExprKind::MethodCall(method, receiver, args, _) = expr.kind
+        && path_to_local_id(receiver, local)
+        && matches!(
+            (method.ident.name, args),
+            (sym::unwrap, [])
+                | (
+                    sym::expect,
+                    [Expr {
+                        kind: ExprKind::Lit(_),
+                        ..
+                    }]
+                )
+        )
+    {
+        Some(method.ident.name)
+    } else {
+        None
+    }
+}
+
+/// Checks if `stmt` calls a closure which captures `local`.
+fn calls_closure_capturing<'tcx>(cx: &LateContext<'tcx>, stmt: &'tcx Stmt<'tcx>, local: HirId) -> bool {
+    for_each_expr(cx, stmt, |e| {
+        if let ExprKind::Call(callee, _) = e.kind
+            && let ty::Closure(def_id, _) = typeck_results(cx).expr_ty(callee).peel_refs().kind()
+            && let Some(def_id) = def_id.as_local()
+            && typeck_results(cx)
+                .closure_min_captures
+                .get(&def_id)
+                .is_some_and(|captures| captures.contains_key(&local))
+        {
+            ControlFlow::Break(())
+        } else {
+            ControlFlow::Continue(())
+        }
+    })
+    .is_some()
+}
+
+/// Returns the output type of the innermost function or closure containing `hir_id`, which is what
+/// an early `return` at `hir_id`, or the `?` operator replacing it, returns from. The output of a
+/// closure is taken from its inferred signature, as it doesn't have to be written out.
+fn enclosing_output_ty<'tcx>(cx: &LateContext<'tcx>, hir_id: HirId) -> Option<Ty<'tcx>> {
+    let owner = cx.tcx.hir().enclosing_body_owner(hir_id);
+    match cx.tcx.def_kind(owner) {
+        DefKind::Fn | DefKind::AssocFn => Some(cx.tcx.fn_sig(owner).instantiate_identity().output().skip_binder()),
+        DefKind::Closure => {
+            let closure_ty = typeck_results(cx).node_type(cx.tcx.local_def_id_to_hir_id(owner));
+            match closure_ty.kind() {
+                ty::Closure(_, args) => Some(args.as_closure().sig().output().skip_binder()),
+                ty::Coroutine(_, args) => Some(args.as_coroutine().return_ty()),
+                _ => None,
+            }
+        },
+        _ => None,
+    }
+}
+
+/// Checks whether the success payload of `ty`, if it's itself an `Option` or a `Result` (e.g.
+/// `Result<Option<T>, E>`), is the same as the one of the type returned by the innermost body
+/// containing `hir_id`. Otherwise the rewrite is likely to mix up the nesting levels.
+fn nested_payloads_line_up<'tcx>(cx: &LateContext<'tcx>, hir_id: HirId, ty: Ty<'tcx>) -> bool {
+    let payload = |ty: Ty<'tcx>| match ty.kind() {
+        ty::Adt(adt, args)
+            if cx.tcx.is_diagnostic_item(sym::Option, adt.did())
+                || cx.tcx.is_diagnostic_item(sym::Result, adt.did()) =>
+        {
+            Some(args.type_at(0))
+        },
+        _ => None,
+    };
+    match (payload(ty), enclosing_output_ty(cx, hir_id)) {
+        (Some(inner), Some(ret_ty)) if payload(inner).is_some() => payload(ret_ty) == Some(inner),
+        _ => true,
+    }
+}
+
+/// Checks if the error type of the closure containing the `?` operator suggested by `sugg` can
+/// still be inferred once the suggestion is applied. A closure without a declared return type
+/// gets its error type from the values it returns, but `?` converts the error it passes on with
+/// `From`, which leaves the error type unconstrained if the replaced `return` was the only one
+/// naming it, e.g. in a `try_for_each` closure which otherwise only returns `Ok(())`.
+fn closure_err_ty_still_inferred(cx: &LateContext<'_>, sugg: &QuestionMarkSugg) -> bool {
+    let owner = cx.tcx.hir().enclosing_body_owner(sugg.hir_id);
+    let Node::Expr(Expr {
+        kind: ExprKind::Closure(closure),
+        ..
+    }) = cx.tcx.hir_node_by_def_id(owner)
+    else {
+        return true;
+    };
+    if matches!(closure.fn_decl.output, FnRetTy::Return(_))
+        || !enclosing_output_ty(cx, sugg.hir_id).is_some_and(|ty| is_type_diagnostic_item(cx, ty, sym::Result))
+    {
+        return true;
+    }
+    // Values in the replaced code don't constrain the error type anymore, nor does a plain `Ok(..)`
+    let constrains = |value: &Expr<'_>| !sugg.span.contains(value.span) && !is_untyped_ok_call(cx, value);
+    let value = cx.tcx.hir().body(closure.body).value;
+    let tail = match value.kind {
+        ExprKind::Block(block, _) => block.expr,
+        _ => Some(value),
+    };
+    tail.is_some_and(constrains)
+        || for_each_expr_without_closures(value, |e| {
+            if let ExprKind::Ret(Some(ret)) = e.kind
+                && constrains(ret)
+            {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            }
+        })
+        .is_some()
+}
+
+/// Checks if `expr` is `Ok(..)`, without naming the error type of the `Result` it creates.
+fn is_untyped_ok_call(cx: &LateContext<'_>, expr: &Expr<'_>) -> bool {
+    if let ExprKind::Call(ctor, _) = expr.kind
+        && let ExprKind::Path(QPath::Resolved(None, path)) = ctor.kind
+        && path.segments.iter().all(|segment| segment.args.is_none())
+    {
+        is_res_lang_ctor(cx, path.res, ResultOk)
+    } else {
+        false
+    }
+}
+
+/// Returns the method call needed to borrow `receiver` once `guard` is rewritten to apply the `?`
+/// operator to it, `binding` being the binding mode of the payload if `guard` binds it:
+///
+/// | `receiver`                                                | method call                  |
+/// |-----------------------------------------------------------|------------------------------|
+/// | of type `&Option<T>`/`&Result<T, E>`, e.g. a getter call  | `.as_ref()`                  |
+/// | of type `&mut Option<T>`/`&mut Result<T, E>`              | `.as_mut()` if bound mutably |
+/// | with a bound payload                                      | per `by_ref_method_call`     |
+/// | `Copy`, or a call returning an owned value                | none                         |
+/// | an owned local which isn't used anywhere but in `guard`   | none                         |
+/// | a field of an owned local which isn't used afterwards     | none                         |
+/// | any other place, as the guard itself doesn't consume it   | `.as_ref()`                  |
+fn receiver_method_call<'tcx>(
+    cx: &LateContext<'tcx>,
+    guard: &Expr<'tcx>,
+    receiver: &Expr<'tcx>,
+    binding: Option<ByRef>,
+    applicability: &mut Applicability,
+) -> &'static str {
+    let receiver_ty = typeck_results(cx).expr_ty(receiver);
+    if let ty::Ref(_, _, mutability) = *receiver_ty.kind() {
+        return match (mutability, binding) {
+            (Mutability::Mut, Some(ByRef::No | ByRef::Yes(Mutability::Mut))) => ".as_mut()",
+            _ => ".as_ref()",
+        };
+    }
+    if let Some(by_ref) = binding {
+        return by_ref_method_call(receiver, by_ref, applicability);
+    }
+    if receiver_ty.is_copy_modulo_regions(cx.tcx, cx.param_env)
+        || matches!(receiver.kind, ExprKind::Call(..) | ExprKind::MethodCall(..))
+    {
+        return "";
+    }
+    // The value is discarded either way, so a local which isn't used otherwise can be moved
+    if let Some(local) = path_to_local(receiver)
+        && only_used_by_guard(cx, local, guard)
+    {
+        return "";
+    }
+    // A field of an owned local (e.g. `self` in a builder method) can simply be moved out of,
+    // as long as the local isn't used again afterwards. If it's moved afterwards, moving the field
+    // would certainly break that, other uses may still work with the field moved out of.
+    if let Some(root) = owned_field_root(cx, receiver) {
+        match local_usage_after_guard(cx, root, guard) {
+            UsageKind::Unused => return "",
+            UsageKind::Read | UsageKind::Written => {
+                *applicability = min_applicability(*applicability, Applicability::MaybeIncorrect);
+            },
+            UsageKind::Moved => {},
+        }
+    }
+    ".as_ref()"
+}
+
+/// If the `Option`/`Result` checked through `receiver` is reached through a `Deref` impl other than
+/// the one of `Box`, e.g. `self.cached.is_none()` with `cached: Cow<'_, Option<T>>`, returns the
+/// number of dereferences autoderef applies to `receiver`. That value is a borrowed place, which
+/// can't be moved out of, so it has to be dereferenced explicitly and borrowed. Receivers which
+/// are dereferenced explicitly already, like `(*self.cached).is_none()`, return zero.
+fn borrowed_place_derefs(cx: &LateContext<'_>, receiver: &Expr<'_>) -> Option<usize> {
+    let typeck = typeck_results(cx);
+    let (derefs, overloaded) = typeck
+        .expr_adjustments(receiver)
+        .iter()
+        .map_while(|adjust| match adjust.kind {
+            Adjust::Deref(overloaded) => Some(overloaded.is_some()),
+            _ => None,
+        })
+        .fold((0, false), |(derefs, any), overloaded| (derefs + 1, any || overloaded));
+    (overloaded || (matches!(receiver.kind, ExprKind::Unary(UnOp::Deref, _)) && typeck.is_method_call(receiver)))
+        .then_some(derefs)
+}
+
+/// Strips a single reference off `ty`, for receivers like getter calls returning `&Option<T>`.
+fn peel_receiver_ref(ty: Ty<'_>) -> Ty<'_> {
+    match *ty.kind() {
+        ty::Ref(_, inner, _) => inner,
+        _ => ty,
+    }
+}
+
+/// Checks if `local` is used after `guard`.
+fn local_used_after_guard(cx: &LateContext<'_>, local: HirId, guard: &Expr<'_>) -> bool {
+    local_usage_after_guard(cx, local, guard) != UsageKind::Unused
+}
+
+/// Returns the strongest use of `local` after `guard`. Guards are usually statements of their
+/// enclosing block, in which case only the following statements have to be looked at. Otherwise
+/// any later use is reported as a read, as its kind isn't known.
+fn local_usage_after_guard(cx: &LateContext<'_>, local: HirId, guard: &Expr<'_>) -> UsageKind {
+    let mut parents = cx.tcx.hir().parent_iter(guard.hir_id);
+    if let (Some((stmt_id, Node::Stmt(_))), Some((_, Node::Block(block)))) = (parents.next(), parents.next())
+        && let Some(idx) = block.stmts.iter().position(|stmt| stmt.hir_id == stmt_id)
+        // Within a loop, the statements preceding the guard may run after it as well
+        && get_enclosing_loop_or_multi_call_closure(cx, guard).is_none()
+    {
+        used_after_stmt(cx, local, block, idx)
+    } else if local_used_after_expr(cx, local, guard) {
+        UsageKind::Read
+    } else {
+        UsageKind::Unused
+    }
+}
+
+/// Checks if `local` isn't used anywhere in its body but in `guard`, and `guard` runs at most once.
+/// Moving out of `local` there then can't conflict with a later use, nor with a borrow of it taken
+/// before `guard`.
+fn only_used_by_guard(cx: &LateContext<'_>, local: HirId, guard: &Expr<'_>) -> bool {
+    let hir = cx.tcx.hir();
+    let body = hir.body_owned_by(hir.enclosing_body_owner(guard.hir_id));
+    get_enclosing_loop_or_multi_call_closure(cx, guard).is_none()
+        && for_each_expr(cx, body.value, |e| {
+            if path_to_local_id(e, local) && !guard.span.contains(e.span) {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            }
+        })
+        .is_none()
+}
+
+/// If `expr` is a (possibly nested) field of an owned local which may be moved out of, returns
+/// the `HirId` of that local.
+fn owned_field_root(cx: &LateContext<'_>, mut expr: &Expr<'_>) -> Option<HirId> {
+    if !matches!(expr.kind, ExprKind::Field(..)) {
+        return None;
+    }
+    while let ExprKind::Field(base, _) = expr.kind {
+        // Moving out of a field requires the containing type to not implement `Drop`, and to not
+        // be reached through a deref
+        if let ty::Adt(adt, _) = typeck_results(cx).expr_ty(base).kind()
+            && !adt.has_dtor(cx.tcx)
+            && typeck_results(cx).expr_adjustments(base).is_empty()
+        {
+            expr = base;
+        } else {
+            return None;
+        }
+    }
+    path_to_local(expr)
+}
+
+fn check_is_none_or_err_and_early_return<'tcx>(
+    cx: &LateContext<'tcx>,
+    expr: &'tcx Expr<'tcx>,
+) -> Option<QuestionMarkSugg> {
+    let mut applicability = Applicability::MachineApplicable;
+    let mut sugg = is_none_or_err_and_early_return_sugg(cx, expr, &mut applicability)?;
+    let mut msg = "this block may be rewritten with the `?` operator";
+    let mut extra = None;
+    if let Some(higher::If { r#else: Some(_), .. }) = higher::If::hir(expr)
+        && is_returned_value(cx, expr)
+    {
+        msg = "this block returns the checked value either way";
+    } else if let Some(higher::If { cond, r#else: None, .. }) = higher::If::hir(expr)
+        && let Some((caller, _)) = is_none_or_err_cond(cx, cond)
+        && !typeck_results(cx).expr_ty(caller).is_ref()
+        && borrowed_place_derefs(cx, caller).is_none()
+    {
+        if let Some(err_ty) = uninhabited_result_err_ty(cx, typeck_results(cx).expr_ty(caller))
+            && let Some(receiver) = sugg.strip_suffix("?;")
+        {
+            sugg = format!("{receiver}.unwrap();");
+            msg = "the error case of this `Result` is impossible";
+            applicability = Applicability::MaybeIncorrect;
+            extra = Some(uninhabited_err_note(cx, caller, err_ty));
+        } else if let Some((binding, replacements, notes, reuse_applicability)) = lookup_reuses(cx, expr, caller) {
+            let lookup_str = snippet_with_applicability(cx, caller.span, "..", &mut applicability);
+            sugg = format!("let {binding} = {lookup_str}?;");
+            applicability = min_applicability(applicability, reuse_applicability);
+            extra = Some(ExtraEdits {
+                help: "replace it and reuse the looked up value",
+                replacements,
+                notes,
+                alternatives: Vec::new(),
+                body_rewrite: None,
+            });
+        } else if combinator_moves_used_later(cx, expr, caller) {
+            return None;
+        } else if let Some(local) = path_to_local(caller) {
+            match following_use(cx, expr, local) {
+                Some(FollowingUse::Drop(stmt_span)) => {
+                    // `?` consumes the local already, so the explicit `drop` has to go
+                    let receiver_str = snippet_with_applicability(cx, caller.span, "..", &mut applicability);
+                    sugg = format!("{receiver_str}?;");
+                    extra = Some(ExtraEdits {
+                        help: "replace it and remove the `drop`",
+                        replacements: vec![(
+                            stmt_span.with_leading_whitespace(cx).with_ctxt(stmt_span.ctxt()),
+                            String::new(),
+                        )],
+                        notes: Vec::new(),
+                        alternatives: Vec::new(),
+                        body_rewrite: None,
+                    });
+                },
+                Some(FollowingUse::Unwrap {
+                    stmt,
+                    binding,
+                    method,
+                    after_others,
+                }) => {
+                    // Bind the value right away, so that the `unwrap` can go
+                    let binding_str = snippet_with_applicability(cx, binding, "..", &mut applicability);
+                    let receiver_str = snippet_with_applicability(cx, caller.span, "..", &mut applicability);
+                    sugg = format!("{binding_str}{receiver_str}?;");
+                    // The statements in between don't use the local, but could still depend on a
+                    // reference to it taken before the guard
+                    if after_others {
+                        applicability = Applicability::MaybeIncorrect;
+                    }
+                    extra = Some(ExtraEdits {
+                        help: if method == sym::unwrap {
+                            "replace it and remove the `unwrap`"
+                        } else {
+                            "replace it and remove the `expect`"
+                        },
+                        replacements: vec![(stmt.with_leading_whitespace(cx).with_ctxt(stmt.ctxt()), String::new())],
+                        notes: Vec::new(),
+                        alternatives: Vec::new(),
+                        body_rewrite: None,
+                    });
+                },
+                Some(FollowingUse::CallUnwrapped { unwrap, after_others }) => {
+                    // Shadow the local with its value, which is then called where it was unwrapped
+                    let name = cx.tcx.hir().ident(local);
+                    sugg = format!("let {name} = {name}?;");
+                    if after_others {
+                        applicability = Applicability::MaybeIncorrect;
+                    }
+                    extra = Some(ExtraEdits {
+                        help: "replace it and call the value where it was unwrapped",
+                        replacements: vec![(unwrap, name.to_string())],
+                        notes: Vec::new(),
+                        alternatives: Vec::new(),
+                        body_rewrite: None,
+                    });
+                },
+                Some(FollowingUse::UnwrapInCondition { gap, unwrap, only_use }) => {
+                    // `?` still returns before the rest of the condition is evaluated, just like
+                    // the guard did
+                    let receiver_str = snippet_with_applicability(cx, caller.span, "..", &mut applicability);
+                    sugg = String::new();
+                    if !only_use {
+                        applicability = Applicability::MaybeIncorrect;
+                    }
+                    extra = Some(ExtraEdits {
+                        help: "remove it and unwrap the value with `?` in the following condition",
+                        replacements: vec![(gap, String::new()), (unwrap, format!("{receiver_str}?"))],
+                        notes: Vec::new(),
+                        alternatives: Vec::new(),
+                        body_rewrite: None,
+                    });
+                },
+                Some(FollowingUse::UnreachableMatch {
+                    gap,
+                    match_span,
+                    binding,
+                    body,
+                    single_use,
+                    value_position,
+                    name_used_later,
+                    needs_semi,
+                }) => {
+                    // The panicking arm can't be reached after the guard, so only the other one is
+                    // kept, with the value unwrapped by `?`
+                    let receiver_str = snippet_with_applicability(cx, caller.span, "..", &mut applicability);
+                    let semi = if needs_semi { ";" } else { "" };
+                    let (help, replacements) = if let Some(used) = single_use {
+                        let before = snippet_with_applicability(cx, body.until(used), "..", &mut applicability);
+                        let after =
+                            snippet_with_applicability(cx, used.between(body.shrink_to_hi()), "..", &mut applicability);
+                        sugg = String::new();
+                        (
+                            "remove it and unwrap the value with `?` in place of the following `match`",
+                            vec![
+                                (gap, String::new()),
+                                (match_span, format!("{before}{receiver_str}?{after}{semi}")),
+                            ],
+                        )
+                    } else {
+                        let binding_str = snippet_with_applicability(cx, binding, "..", &mut applicability);
+                        let body_str = snippet_with_applicability(cx, body, "..", &mut applicability);
+                        sugg = format!("let {binding_str} = {receiver_str}?;");
+                        if name_used_later {
+                            applicability = Applicability::MaybeIncorrect;
+                        }
+                        (
+                            "replace it and the following `match` with the arm binding the value",
+                            vec![(match_span, format!("{body_str}{semi}"))],
+                        )
+                    };
+                    if value_position {
+                        applicability = Applicability::MaybeIncorrect;
+                    }
+                    extra = Some(ExtraEdits {
+                        help,
+                        replacements,
+                        notes: Vec::new(),
+                        alternatives: Vec::new(),
+                        body_rewrite: None,
+                    });
+                },
+                Some(FollowingUse::Assign(assign_span)) => {
+                    let receiver_str = snippet_with_applicability(cx, caller.span, "..", &mut applicability);
+                    applicability = Applicability::MaybeIncorrect;
+                    extra = Some(ExtraEdits {
+                        help: "replace it with",
+                        replacements: Vec::new(),
+                        notes: vec![(assign_span, format!("`{receiver_str}` is reassigned here"))],
+                        alternatives: Vec::new(),
+                        body_rewrite: None,
+                    });
+                },
+                None => {},
+            }
+        } else if let Some(root) = owned_field_root(cx, caller)
+            && sugg.ends_with(".as_ref()?;")
+        {
+            // `receiver_method_call` only borrows fields of owned locals which are used again
+            // later, e.g. `self` in `fn refine(self) -> Option<Self>`
+            let receiver_str = snippet_with_applicability(cx, caller.span, "..", &mut applicability);
+            extra = Some(ExtraEdits {
+                help: "replace it with",
+                replacements: Vec::new(),
+                notes: vec![(
+                    caller.span,
+                    format!(
+                        "`{}` is used again later, so `{receiver_str}` is borrowed instead of moved out of",
+                        cx.tcx.hir().ident(root)
+                    ),
+                )],
+                alternatives: Vec::new(),
+                body_rewrite: None,
+            });
+        }
+        // The temporaries of the condition were dropped before the early return, but those of the
+        // rewritten expression live until the end of the enclosing statement
+        if let Some((temporary, ty)) = significant_temporary(cx, caller) {
+            applicability = Applicability::MaybeIncorrect;
+            extra
+                .get_or_insert_with(|| ExtraEdits {
+                    help: "replace it with",
+                    replacements: Vec::new(),
+                    notes: Vec::new(),
+                    alternatives: Vec::new(),
+                    body_rewrite: None,
+                })
+                .notes
+                .push((
+                    temporary,
+                    format!("the temporary `{ty}` created here would be dropped later"),
+                ));
+        }
+    }
+    add_body_rewrite(cx, expr, &sugg, &mut extra);
+    Some(QuestionMarkSugg {
+        kind: QuestionMarkKind::IfIs,
+        hir_id: expr.hir_id,
+        span: expr.span,
+        msg,
+        sugg,
+        applicability,
+        msrv_blocked: None,
+        extra,
+    })
+}
+
+/// Returns the suggestion for an `Option` guard returning an error, like
+/// `if opt.is_none() { return Err(e); }`, which can be rewritten to `opt.ok_or(e)?;`.
+///
+/// Error expressions which aren't a literal or a path are lifted into an `ok_or_else` closure,
+/// unless they can't be moved there without changing the meaning of the code. This includes macro
+/// calls constructing the error, like `anyhow!("missing {name}")`, which are copied as written.
+fn is_none_and_return_err_sugg<'tcx>(
+    cx: &LateContext<'tcx>,
+    expr: &'tcx Expr<'tcx>,
+    applicability: &mut Applicability,
+) -> Option<String> {
+    if let Some(higher::If {
+        cond,
+        then,
+        r#else: None,
+    }) = higher::If::hir(expr)
+        && !is_else_clause(cx.tcx, expr)
+        && !span_contains_cfg_or_cfg_attr(cx, expr.span)
+        && let Some((caller, call_sym)) = is_none_or_err_cond(cx, cond)
+        && call_sym.as_str() == "is_none"
+        && let caller_ty = typeck_results(cx).expr_ty(caller)
+        && is_type_diagnostic_item(cx, caller_ty, sym::Option)
+        && let ExprKind::Ret(Some(ret)) = peel_blocks_with_stmt(then).kind
+        && let ExprKind::Call(err_ctor, [err]) = ret.kind
+        && ret.span.ctxt() == expr.span.ctxt()
+        && is_res_lang_ctor(cx, path_res(cx, err_ctor), ResultErr)
+        && let Some(err_span) = walk_span_to_context(err.span, ret.span.ctxt())
+        && can_be_lifted_into_closure(cx, expr, caller, err)
+    {
+        if err_span != err.span || span_contains_comment(cx.tcx.sess.source_map(), then.span.with_lo(err_span.hi())) {
+            *applicability = Applicability::MaybeIncorrect;
+        }
+        let receiver_str = snippet_with_applicability(cx, caller.span, "..", applicability);
+        let method_call_str = receiver_method_call(cx, expr, caller, None, applicability);
+        let err_str = snippet_with_applicability(cx, err_span, "..", applicability);
+        // A function called without arguments is passed to `ok_or_else` as is, as
+        // `redundant_closure` would lint the closure otherwise
+        let err_fn_str = if is_untyped_default_call(cx, err) {
+            // `Default::default()` only gets its type from the return type, which can't be inferred
+            // through the conversion of the error by `?`, so the type has to be named
+            let default_trait_id = cx.tcx.get_diagnostic_item(sym::Default)?;
+            if !implements_trait(cx, typeck_results(cx).expr_ty(err), default_trait_id, &[]) {
+                return None;
+            }
+            let ty_str = snippet_with_applicability(cx, declared_err_ty_span(cx, expr.hir_id)?, "..", applicability);
+            if ty_str.chars().all(|c| c == ':' || c == '_' || c.is_alphanumeric()) {
+                Some(format!("{ty_str}::default"))
+            } else {
+                Some(format!("<{ty_str}>::default"))
+            }
+        } else if err_span == err.span
+            && let ExprKind::Call(callee, []) = err.kind
+            && matches!(path_res(cx, callee), Res::Def(DefKind::Fn | DefKind::AssocFn, _))
+        {
+            Some(snippet_with_applicability(cx, callee.span, "..", applicability).into_owned())
+        } else {
+            None
+        };
+        if let Some(err_fn_str) = err_fn_str {
+            Some(format!("{receiver_str}{method_call_str}.ok_or_else({err_fn_str})?;"))
+        } else if err_span == err.span && matches!(err.kind, ExprKind::Lit(_) | ExprKind::Path(_) | ExprKind::Tup([])) {
+            Some(format!("{receiver_str}{method_call_str}.ok_or({err_str})?;"))
+        } else {
+            Some(format!("{receiver_str}{method_call_str}.ok_or_else(|| {err_str})?;"))
+        }
+    } else {
+        None
+    }
+}
+
+/// Checks if `expr` is `Default::default()`, without naming the type it creates.
+fn is_untyped_default_call(cx: &LateContext<'_>, expr: &Expr<'_>) -> bool {
+    if let ExprKind::Call(path, []) = expr.kind
+        && let ExprKind::Path(ref qpath @ QPath::Resolved(None, _)) = path.kind
+        && let Some(def_id) = cx.qpath_res(qpath, path.hir_id).opt_def_id()
+    {
+        cx.tcx.is_diagnostic_item(sym::default_fn, def_id)
+    } else {
+        false
+    }
+}
+
+/// Returns the span of the error type in the declared `Result` return type of the function or
+/// closure containing `hir_id`.
+fn declared_err_ty_span(cx: &LateContext<'_>, hir_id: HirId) -> Option<Span> {
+    let owner = cx.tcx.hir().enclosing_body_owner(hir_id);
+    let decl = cx.tcx.hir().fn_decl_by_hir_id(cx.tcx.local_def_id_to_hir_id(owner))?;
+    if let FnRetTy::Return(ret_ty) = decl.output
+        && let TyKind::Path(QPath::Resolved(None, path)) = ret_ty.kind
+        && let Res::Def(DefKind::Enum, result_id) = path.res
+        && cx.tcx.is_diagnostic_item(sym::Result, result_id)
+        && let Some(args) = path.segments.last()?.args
+        && let [_, GenericArg::Type(err_ty)] = args.args
+    {
+        Some(err_ty.span)
+    } else {
+        None
+    }
+}
+
+/// Checks whether the error expression `err` returned by `guard` can be evaluated in an
+/// `ok_or_else` closure (or eagerly by `ok_or`) instead: it must not contain control flow, must not
+/// refer to the checked option `caller`, and must not move values which are still used after the
+/// guard.
+fn can_be_lifted_into_closure<'tcx>(
+    cx: &LateContext<'tcx>,
+    guard: &Expr<'tcx>,
+    caller: &Expr<'tcx>,
+    err: &'tcx Expr<'tcx>,
+) -> bool {
+    let caller_local = path_to_local(caller);
+    let has_blocker = for_each_expr(cx, err, |e| match e.kind {
+        ExprKind::Ret(_)
+        | ExprKind::Break(..)
+        | ExprKind::Continue(_)
+        | ExprKind::Yield(..)
+        | ExprKind::Match(_, _, MatchSource::TryDesugar(_) | MatchSource::AwaitDesugar) => ControlFlow::Break(()),
+        _ if eq_expr_value(cx, e, caller) || caller_local.is_some_and(|id| path_to_local_id(e, id)) => {
+            ControlFlow::Break(())
+        },
+        _ => ControlFlow::Continue(()),
+    })
+    .is_some();
+    if has_blocker {
+        return false;
+    }
+
+    let mut delegate = MoveDelegate {
+        cx,
+        moved: HirIdSet::default(),
+    };
+    ExprUseVisitor::for_clippy(cx, err.hir_id.owner.def_id, &mut delegate)
+        .walk_expr(err)
+        .into_ok();
+    !delegate
+        .moved
+        .into_iter()
+        .any(|local| local_used_after_guard(cx, local, guard))
+}
+
+/// Checks for guards with an additional condition, which may be rewritten to only keep that
+/// condition around the `?` operator:
+///
+/// ```ignore
+/// if flag && option.is_none() {
+///     return None;
+/// }
+/// ```
+///
+/// With `option.is_none() && flag`, the rewrite evaluates `flag` first, so it's only suggested if
+/// `flag` is a plain read of a local or a field.
+fn check_split_condition_and_early_return<'tcx>(
+    cx: &LateContext<'tcx>,
+    expr: &'tcx Expr<'tcx>,
+) -> Option<QuestionMarkSugg> {
+    if let Some(higher::If {
+        cond,
+        then,
+        r#else: None,
+    }) = higher::If::hir(expr)
+        && !is_else_clause(cx.tcx, expr)
+        && !span_contains_cfg_or_cfg_attr(cx, expr.span)
+        && let ExprKind::Binary(op, lhs, rhs) = cond.kind
+        && op.node == BinOpKind::And
+        && let Some((flag, (caller, call_sym))) = is_none_or_err_cond(cx, rhs).map(|guard| (lhs, guard)).or_else(|| {
+            is_none_or_err_cond(cx, lhs)
+                .filter(|_| is_plain_read(rhs))
+                .map(|guard| (rhs, guard))
+        })
+        && let caller_ty = typeck_results(cx).expr_ty(caller)
+        && let if_block = IfBlockType::IfIs(caller, caller_ty, call_sym, then)
+        && (is_early_return(sym::Option, cx, &if_block) || is_early_return(sym::Result, cx, &if_block))
+    {
+        let mut applicability = Applicability::MaybeIncorrect;
+        let flag_str = snippet_with_applicability(cx, flag.span, "..", &mut applicability);
+        let receiver_str = snippet_with_applicability(cx, caller.span, "..", &mut applicability);
+        let method_call_str = receiver_method_call(cx, expr, caller, None, &mut applicability);
+        let indent = " ".repeat(indent_of(cx, expr.span).unwrap_or(0));
+        let sugg = format!("if {flag_str} {{\n{indent}    {receiver_str}{method_call_str}?;\n{indent}}}");
+        Some(QuestionMarkSugg {
+            kind: QuestionMarkKind::IfIs,
+            hir_id: expr.hir_id,
+            span: expr.span,
+            msg: "this block may be rewritten with the `?` operator",
+            sugg,
+            applicability,
+            msrv_blocked: None,
+            extra: None,
+        })
+    } else {
+        None
+    }
+}
+
+/// Checks for a cascade of guards on nested optional values, each of which unwraps the value
+/// checked by the previous one:
+///
+/// ```ignore
+/// if cfg.server.is_none() {
+///     return None;
+/// }
+/// if cfg.server.as_ref().unwrap().port.is_none() {
+///     return None;
+/// }
+/// ```
+///
+/// Rewriting the guards one by one would leave the later ones unwrapping the value again, so they
+/// are rewritten together, binding each value for the next guard and the code following them:
+/// `let server = cfg.server.as_ref()?; let port = server.port?;`.
+fn check_guard_cascade_and_early_return<'tcx>(
+    cx: &LateContext<'tcx>,
+    expr: &'tcx Expr<'tcx>,
+) -> Option<QuestionMarkSugg> {
+    let Node::Stmt(head_stmt) = cx.tcx.parent_hir_node(expr.hir_id) else {
+        return None;
+    };
+    let Node::Block(block) = cx.tcx.parent_hir_node(head_stmt.hir_id) else {
+        return None;
+    };
+    let head_idx = block.stmts.iter().position(|stmt| stmt.hir_id == head_stmt.hir_id)?;
+    let (_, head) = cascade_guard(cx, head_stmt)?;
+    let mut levels = vec![(expr, head, None)];
+    for stmt in &block.stmts[head_idx + 1..] {
+        let Some((guard, receiver)) = cascade_guard(cx, stmt) else {
+            break;
+        };
+        let Some(unwrap) = unwrap_in_receiver(cx, receiver, levels.last()?.1) else {
+            break;
+        };
+        levels.push((guard, receiver, Some(unwrap)));
+    }
+    if levels.len() < 2 {
+        return None;
+    }
+
+    let bindings: Vec<String> = levels
+        .iter()
+        .map(|&(_, receiver, _)| match receiver.kind {
+            ExprKind::Field(_, ident) if !ident.as_str().starts_with(|c: char| c.is_ascii_digit()) => ident.to_string(),
+            _ => "value".to_string(),
+        })
+        .collect();
+    if bindings
+        .iter()
+        .enumerate()
+        .any(|(i, binding)| bindings[..i].contains(binding))
+    {
+        return None;
+    }
+
+    // Reuse the bound values in the code following the guards, as long as it doesn't mutate them
+    let mut root = head;
+    while let ExprKind::Field(base, _) | ExprKind::MethodCall(_, base, [], _) = root.kind {
+        root = base;
+    }
+    let root = path_to_local(root)?;
+    let mut applicability = Applicability::MachineApplicable;
+    let mut replacements = Vec::new();
+    let rest = block.stmts[head_idx + levels.len()..]
+        .iter()
+        .filter_map(|stmt| match stmt.kind {
+            StmtKind::Let(local) => local.init,
+            StmtKind::Expr(e) | StmtKind::Semi(e) => Some(e),
+            StmtKind::Item(_) => None,
+        })
+        .chain(block.expr);
+    for node in rest {
+        let replacements_before = replacements.len();
+        for_each_expr(cx, node, |e| {
+            if e.span.from_expansion() {
+                return ControlFlow::<()>::Continue(Descend::Yes);
+            }
+            // The innermost value is unwrapped last, so it's checked first
+            if let Some(i) = (0..levels.len()).rev().find(|&i| is_unwrap_of(cx, e, levels[i].1)) {
+                replacements.push((e.span, bindings[i].clone()));
+                return ControlFlow::Continue(Descend::No);
+            }
+            if let ExprKind::Path(QPath::Resolved(None, path)) = e.kind
+                && let [segment] = path.segments
+                && bindings.iter().any(|binding| segment.ident.as_str() == binding)
+            {
+                // The new bindings might shadow existing ones
+                applicability = Applicability::MaybeIncorrect;
+            }
+            ControlFlow::Continue(Descend::Yes)
+        });
+        if is_potentially_mutated(root, node, cx) {
+            if replacements.len() != replacements_before {
+                return None;
+            }
+            break;
+        }
+    }
+
+    let last = levels.len() - 1;
+    let last_is_reused = replacements.iter().any(|(_, binding)| *binding == bindings[last]);
+    let indent = " ".repeat(indent_of(cx, expr.span).unwrap_or(0));
+    let mut sugg = String::new();
+    for (i, &(_, receiver, unwrap)) in levels.iter().enumerate() {
+        let receiver_str = match unwrap {
+            Some(unwrap) => {
+                let before = snippet_with_applicability(cx, receiver.span.until(unwrap.span), "..", &mut applicability);
+                let after =
+                    snippet_with_applicability(cx, receiver.span.with_lo(unwrap.span.hi()), "..", &mut applicability);
+                format!("{before}{}{after}", bindings[i - 1])
+            },
+            None => snippet_with_applicability(cx, receiver.span, "..", &mut applicability).into_owned(),
+        };
+        let payload_is_copy = match typeck_results(cx).expr_ty(receiver).kind() {
+            ty::Adt(_, args) => args.type_at(0).is_copy_modulo_regions(cx.tcx, cx.param_env),
+            _ => false,
+        };
+        let as_ref = if payload_is_copy { "" } else { ".as_ref()" };
+        if i > 0 {
+            sugg.push('\n');
+            sugg.push_str(&indent);
+        }
+        if i == last && !last_is_reused {
+            sugg.push_str(&format!("{receiver_str}{as_ref}?;"));
+        } else {
+            sugg.push_str(&format!("let {} = {receiver_str}{as_ref}?;", bindings[i]));
+        }
+    }
+    let span = expr.span.to(levels[last].0.span);
+    if span_contains_comment(cx.sess().source_map(), span) {
+        applicability = Applicability::MaybeIncorrect;
+    }
+    Some(QuestionMarkSugg {
+        kind: QuestionMarkKind::IfIs,
+        hir_id: expr.hir_id,
+        span,
+        msg: "these blocks may be rewritten with the `?` operator",
+        sugg,
+        applicability,
+        msrv_blocked: None,
+        extra: (!replacements.is_empty()).then_some(ExtraEdits {
+            help: "replace them and reuse the unwrapped values",
+            replacements,
+            notes: Vec::new(),
+            alternatives: Vec::new(),
+            body_rewrite: None,
+        }),
+    })
+}
+
+/// Checks if `expr` is a later guard of a cascade checked by
+/// `check_guard_cascade_and_early_return`, which is rewritten along with the first one.
+fn continues_guard_cascade(cx: &LateContext<'_>, expr: &Expr<'_>) -> bool {
+    if let Node::Stmt(stmt) = cx.tcx.parent_hir_node(expr.hir_id)
+        && let Node::Block(block) = cx.tcx.parent_hir_node(stmt.hir_id)
+        && let Some(idx) = block.stmts.iter().position(|s| s.hir_id == stmt.hir_id)
+        && let Some((_, prev)) = idx
+            .checked_sub(1)
+            .and_then(|prev| cascade_guard(cx, &block.stmts[prev]))
+        && let Some((_, receiver)) = cascade_guard(cx, stmt)
+    {
+        unwrap_in_receiver(cx, receiver, prev).is_some()
+    } else {
+        false
+    }
+}
+
+/// Returns the guard and its receiver if `stmt` is `if receiver.is_none() { return None; }` and may
+/// be part of a cascade, i.e. the receiver only consists of fields and unwrapped values of a local.
+fn cascade_guard<'tcx>(cx: &LateContext<'tcx>, stmt: &Stmt<'tcx>) -> Option<(&'tcx Expr<'tcx>, &'tcx Expr<'tcx>)> {
+    fn is_unwrap_chain(expr: &Expr<'_>) -> bool {
+        match expr.kind {
+            ExprKind::Field(base, _) => is_unwrap_chain(base),
+            ExprKind::MethodCall(method, receiver, [], _) => {
+                matches!(method.ident.name, sym::unwrap | sym::as_ref) && is_unwrap_chain(receiver)
+            },
+            _ => path_to_local(expr).is_some(),
+        }
+    }
+
+    if let StmtKind::Expr(expr) = stmt.kind
+        && let Some(higher::If {
+            cond,
+            then,
+            r#else: None,
+        }) = higher::If::hir(expr)
+        && !expr.span.from_expansion()
+        && !span_contains_cfg_or_cfg_attr(cx, expr.span)
+        && let Some((receiver, call_sym)) = is_none_or_err_cond(cx, cond)
+        && call_sym.as_str() == "is_none"
+        && is_type_diagnostic_item(cx, typeck_results(cx).expr_ty(receiver), sym::Option)
+        && is_unwrap_chain(receiver)
+        && expr_return_none_or_err(sym::Option, cx, then, receiver, None)
+    {
+        Some((expr, receiver))
+    } else {
+        None
+    }
+}
+
+/// Finds where `receiver` unwraps the value `checked` by the previous guard of a cascade.
+fn unwrap_in_receiver<'tcx>(
+    cx: &LateContext<'_>,
+    receiver: &'tcx Expr<'tcx>,
+    checked: &Expr<'_>,
+) -> Option<&'tcx Expr<'tcx>> {
+    for_each_expr_without_closures(receiver, |e| {
+        if is_unwrap_of(cx, e, checked) {
+            ControlFlow::Break(e)
+        } else {
+            ControlFlow::Continue(())
+        }
+    })
+}
+
+/// Checks if `expr` is `checked.as_ref().unwrap()`, or `checked.unwrap()` for `Copy` values.
+fn is_unwrap_of(cx: &LateContext<'_>, expr: &Expr<'_>, checked: &Expr<'_>) -> bool {
+    if let ExprKind::MethodCall(method, receiver, [], _) = expr.kind
+        && method.ident.name == sym::unwrap
+    {
+        match receiver.kind {
+            ExprKind::MethodCall(adapter, inner, [], _) if adapter.ident.name == sym::as_ref => {
+                SpanlessEq::new(cx).eq_expr(inner, checked)
+            },
+            _ => {
+                SpanlessEq::new(cx).eq_expr(receiver, checked)
+                    && typeck_results(cx)
+                        .expr_ty(expr)
+                        .is_copy_modulo_regions(cx.tcx, cx.param_env)
+            },
+        }
+    } else {
+        false
+    }
+}
+
+/// Checks for a `loop` which returns early unless a value is present, and otherwise breaks with the
+/// unwrapped value right away:
+///
+/// ```ignore
+/// let value = loop {
+///     if option.is_none() {
+///         return None;
+///     }
+///     break option.unwrap();
+/// };
+/// ```
+///
+/// The whole loop can be replaced with `option?`.
+fn check_loop_guard_and_break<'tcx>(cx: &LateContext<'tcx>, expr: &'tcx Expr<'tcx>) -> Option<QuestionMarkSugg> {
+    let (_, caller) = loop_guard_and_break(cx, expr)?;
+    let mut applicability = Applicability::MachineApplicable;
+    let receiver_str = snippet_with_applicability(cx, caller.span, "..", &mut applicability);
+    if span_contains_comment(cx.sess().source_map(), expr.span) {
+        applicability = Applicability::MaybeIncorrect;
+    }
+    Some(QuestionMarkSugg {
+        kind: QuestionMarkKind::IfIs,
+        hir_id: expr.hir_id,
+        span: expr.span,
+        msg: "this loop may be rewritten with the `?` operator",
+        sugg: format!("{receiver_str}?"),
+        applicability,
+        msrv_blocked: None,
+        extra: None,
+    })
+}
+
+/// Returns the guard and its receiver if `expr` is a `loop` consisting of nothing but a guard and a
+/// `break` with the unwrapped receiver, see `check_loop_guard_and_break`.
+fn loop_guard_and_break<'tcx>(
+    cx: &LateContext<'tcx>,
+    expr: &Expr<'tcx>,
+) -> Option<(&'tcx Expr<'tcx>, &'tcx Expr<'tcx>)> {
+    let ExprKind::Loop(block, _, LoopSource::Loop, _) = expr.kind else {
+        return None;
+    };
+    let (guard, brk) = match (block.stmts, block.expr) {
+        (
+            [
+                guard,
+                Stmt {
+                    kind: StmtKind::Semi(brk),
+                    ..
+                },
+            ],
+            None,
+        )
+        | ([guard], Some(brk)) => (guard, brk),
+        _ => return None,
+    };
+    if let StmtKind::Expr(guard) | StmtKind::Semi(guard) = guard.kind
+        && !expr.span.from_expansion()
+        && !span_contains_cfg_or_cfg_attr(cx, expr.span)
+        && let Some(higher::If { cond, r#else: None, .. }) = higher::If::hir(guard)
+        && let Some((caller, _)) = is_none_or_err_cond(cx, cond)
+        && !typeck_results(cx).expr_ty(caller).is_ref()
+        && is_none_or_err_and_early_return_sugg(cx, guard, &mut Applicability::Unspecified).is_some()
+        && let ExprKind::Break(destination, Some(value)) = brk.kind
+        && destination.target_id == Ok(expr.hir_id)
+        && let ExprKind::MethodCall(method, receiver, [], _) = value.kind
+        && method.ident.name == sym::unwrap
+        && eq_expr_value(cx, receiver, caller)
+    {
+        Some((guard, caller))
+    } else {
+        None
+    }
+}
+
+/// Checks if `expr` is the guard of a loop checked by `check_loop_guard_and_break`, which is
+/// rewritten as a whole.
+fn is_loop_guard(cx: &LateContext<'_>, expr: &Expr<'_>) -> bool {
+    if let Node::Stmt(stmt) = cx.tcx.parent_hir_node(expr.hir_id)
+        && let Node::Block(block) = cx.tcx.parent_hir_node(stmt.hir_id)
+        && let Node::Expr(loop_expr) = cx.tcx.parent_hir_node(block.hir_id)
+    {
+        loop_guard_and_break(cx, loop_expr).is_some_and(|(guard, _)| guard.hir_id == expr.hir_id)
+    } else {
+        false
+    }
+}
+
+/// The most statements preceding the early return which `check_guard_statements_and_early_return`
+/// moves into a `let...else`.
+const MAX_GUARD_STATEMENTS: usize = 3;
+
+/// Checks for `Option` guards running some statements before returning early:
+///
+/// ```ignore
+/// if option.is_none() {
+///     debug_assert!(false, "unexpected");
+///     return None;
+/// }
+/// ```
+///
+/// Using `?` would drop the statements, so the guard is rewritten to a `let...else` running them
+/// instead, as `let Some(_) = option else { .. };` with the block kept as written. The statements
+/// may contain control flow of their own, like `if cfg!(debug_assertions) { .. }`, as long as the
+/// block ends with the unconditional early return, which keeps the `else` block diverging.
+fn check_guard_statements_and_early_return<'tcx>(
+    cx: &LateContext<'tcx>,
+    expr: &'tcx Expr<'tcx>,
+) -> Option<QuestionMarkSugg> {
+    if let Some(higher::If {
+        cond,
+        then,
+        r#else: None,
+    }) = higher::If::hir(expr)
+        && !expr.span.from_expansion()
+        && !is_else_clause(cx.tcx, expr)
+        && !span_contains_cfg_or_cfg_attr(cx, expr.span)
+        && let Some((caller, call_sym)) = is_none_or_err_cond(cx, cond)
+        && call_sym.as_str() == "is_none"
+        && is_type_diagnostic_item(cx, typeck_results(cx).expr_ty(caller), sym::Option)
+        && let ExprKind::Block(block, None) = then.kind
+        && let Some((ret, stmts)) = match (block.stmts, block.expr) {
+            (stmts, Some(ret)) => Some((ret, stmts)),
+            ([stmts @ .., last], None) => match last.kind {
+                StmtKind::Semi(ret) => Some((ret, stmts)),
+                _ => None,
+            },
+            _ => None,
+        }
+        && (1..=MAX_GUARD_STATEMENTS).contains(&stmts.len())
+        && stmts
+            .iter()
+            .all(|stmt| matches!(stmt.kind, StmtKind::Semi(_) | StmtKind::Expr(_)))
+        && let ExprKind::Ret(Some(_)) = ret.kind
+        && expr_return_none_or_err(sym::Option, cx, ret, caller, None)
+    {
+        let mut applicability = Applicability::MachineApplicable;
+        let receiver_str = snippet_with_applicability(cx, caller.span, "..", &mut applicability);
+        let block_str = snippet_with_applicability(cx, then.span, "..", &mut applicability);
+        let ctor = prelude_path(cx, expr.hir_id, "option::Option::Some")?;
+        Some(QuestionMarkSugg {
+            kind: QuestionMarkKind::IfIs,
+            hir_id: expr.hir_id,
+            span: expr.span,
+            msg: LET_ELSE_MSG,
+            sugg: let_else_guard_sugg(&ctor, "_", &receiver_str, &block_str),
+            applicability,
+            msrv_blocked: None,
+            extra: None,
+        })
+    } else {
+        None
+    }
+}
+
+/// Checks for guards setting a flag before returning early, as in code converted from C:
+///
+/// ```ignore
+/// let mut ok = true;
+/// if option.is_none() {
+///     ok = false;
+///     return None;
+/// }
+/// ```
+///
+/// The flag is a local of the function, so it's only read on the paths where the guard didn't
+/// fire, and the assignment has no effect. The guard is rewritten to `option?;`, and the flag made
+/// immutable if that was its only assignment.
+fn check_dead_flag_and_early_return<'tcx>(cx: &LateContext<'tcx>, expr: &'tcx Expr<'tcx>) -> Option<QuestionMarkSugg> {
+    if let Some(higher::If {
+        cond,
+        then,
+        r#else: None,
+    }) = higher::If::hir(expr)
+        && !expr.span.from_expansion()
+        && !is_else_clause(cx.tcx, expr)
+        && !span_contains_cfg_or_cfg_attr(cx, expr.span)
+        && let Some((caller, call_sym)) = is_none_or_err_cond(cx, cond)
+        && let ExprKind::Block(block, None) = then.kind
+        && let Some(ret) = match (block.stmts, block.expr) {
+            ([_], Some(ret)) => Some(ret),
+            ([_, last], None) => match last.kind {
+                StmtKind::Semi(ret) => Some(ret),
+                _ => None,
+            },
+            _ => None,
+        }
+        && let StmtKind::Semi(assign) = block.stmts[0].kind
+        && let ExprKind::Assign(lhs, rhs, _) = assign.kind
+        && let Some(flag) = path_to_local(lhs)
+        && is_side_effect_free(rhs)
+        && let ExprKind::Ret(Some(_)) = ret.kind
+        && let caller_ty = peel_receiver_ref(typeck_results(cx).expr_ty(caller))
+        && let if_block = IfBlockType::IfIs(caller, caller_ty, call_sym, ret)
+        && (is_early_return(sym::Option, cx, &if_block) || is_early_return(sym::Result, cx, &if_block))
+        // A local of an enclosing function can still be read once the closure returned
+        && cx.tcx.hir().enclosing_body_owner(flag) == cx.tcx.hir().enclosing_body_owner(expr.hir_id)
+        // Its drop could observe the value
+        && !typeck_results(cx).node_type(flag).needs_drop(cx.tcx, cx.param_env)
+        && let Some(body) = cx.enclosing_body
+        && let Some(other_assignments) = flag_assignments(cx, cx.tcx.hir().body(body).value, flag, assign.hir_id)
+    {
+        let mut applicability = Applicability::MaybeIncorrect;
+        let receiver_str = snippet_with_applicability(cx, caller.span, "..", &mut applicability);
+        let method_call_str = receiver_method_call(cx, expr, caller, None, &mut applicability);
+        let replacements = if other_assignments == 0
+            && let Node::Pat(pat) = cx.tcx.hir_node(flag)
+            && let PatKind::Binding(BindingMode(ByRef::No, Mutability::Mut), _, ident, None) = pat.kind
+        {
+            vec![(pat.span, ident.to_string())]
+        } else {
+            Vec::new()
+        };
+        Some(QuestionMarkSugg {
+            kind: QuestionMarkKind::IfIs,
+            hir_id: expr.hir_id,
+            span: expr.span,
+            msg: "this block may be rewritten with the `?` operator",
+            sugg: format!("{receiver_str}{method_call_str}?;"),
+            applicability,
+            msrv_blocked: None,
+            extra: Some(ExtraEdits {
+                help: "replace it and make the flag immutable",
+                replacements,
+                notes: vec![(
+                    assign.span,
+                    format!(
+                        "`{}` isn't read after returning, so this assignment has no effect",
+                        snippet_with_applicability(cx, lhs.span, "..", &mut applicability)
+                    ),
+                )],
+                alternatives: Vec::new(),
+                body_rewrite: None,
+            }),
+        })
+    } else {
+        None
+    }
+}
+
+/// Counts the assignments to `flag` in `body` other than `assign`. Returns `None` if `flag` is
+/// borrowed or captured by a closure, as its value may then be read after the function returned.
+fn flag_assignments<'tcx>(cx: &LateContext<'tcx>, body: &'tcx Expr<'tcx>, flag: HirId, assign: HirId) -> Option<usize> {
+    let mut count = 0;
+    for_each_expr(cx, body, |e| match e.kind {
+        ExprKind::Assign(lhs, ..) | ExprKind::AssignOp(_, lhs, _) if path_to_local_id(lhs, flag) => {
+            if e.hir_id != assign {
+                count += 1;
+            }
+            ControlFlow::Continue(Descend::Yes)
+        },
+        ExprKind::AddrOf(_, _, inner) if path_to_local_id(inner, flag) => ControlFlow::Break(()),
+        ExprKind::Closure(closure)
+            if cx
+                .tcx
+                .upvars_mentioned(closure.def_id)
+                .is_some_and(|upvars| upvars.contains_key(&flag)) =>
+        {
+            ControlFlow::Break(())
+        },
+        _ => ControlFlow::Continue(Descend::Yes),
+    })
+    .is_none()
+    .then_some(count)
+}
+
+/// The `let...else` statement replacing a guard which can't use `?`, with the `then` block of the
+/// guard kept as the `else` block.
+fn let_else_guard_sugg(ctor: &str, pat: &str, scrutinee: &str, block: &str) -> String {
+    format!("let {ctor}({pat}) = {scrutinee} else {block};")
+}
+
+/// Spells out `path`, an item of `core` given relative to its root like `result::Result::Err`, for
+/// a suggestion at `hir_id`. The path goes through `std`, or `core` in `#![no_std]` crates, and
+/// starts with `::` where `#[no_implicit_prelude]` takes the crates out of scope.
+///
+/// The suggestions of the lints of this pass and of `QUESTION_MARK_USED` spell out items of `core`
+/// through this or `prelude_path` only.
+pub(crate) fn core_path(cx: &LateContext<'_>, hir_id: HirId, path: &str) -> Option<String> {
+    let krate = std_or_core(cx)?;
+    if has_implicit_prelude(cx, hir_id) {
+        Some(format!("{krate}::{path}"))
+    } else {
+        Some(format!("::{krate}::{path}"))
+    }
+}
+
+/// Spells out `path`, an item of the prelude, like `core_path`, but only by its name where the
+/// prelude is in scope, e.g. `Some` for `option::Option::Some`.
+pub(crate) fn prelude_path(cx: &LateContext<'_>, hir_id: HirId, path: &str) -> Option<String> {
+    if has_implicit_prelude(cx, hir_id) {
+        path.rsplit("::").next().map(str::to_owned)
+    } else {
+        core_path(cx, hir_id, path)
+    }
+}
+
+/// Checks if the prelude is in scope at `hir_id`, i.e. neither the crate nor any of the modules
+/// around `hir_id` are marked `#[no_implicit_prelude]`.
+fn has_implicit_prelude(cx: &LateContext<'_>, hir_id: HirId) -> bool {
+    !cx.tcx.hir().parent_id_iter(hir_id).any(|id| {
+        cx.tcx
+            .hir()
+            .attrs(id)
+            .iter()
+            .any(|attr| attr.has_name(sym::no_implicit_prelude))
+    })
+}
+
+/// Checks for guards in functions returning `Poll`, like `Stream::poll_next`, which return
+/// `Poll::Ready(None)` or `Poll::Ready(Err(..))` early:
+///
+/// ```ignore
+/// if self.inner.is_none() {
+///     return Poll::Ready(None);
+/// }
+/// let inner = self.inner.as_mut().unwrap();
+/// ```
+///
+/// `?` can't return the `Poll`, so they are rewritten to a `let...else` instead, binding the
+/// value unwrapped by the following statement, if any:
+/// `let Some(inner) = self.inner.as_mut() else { return Poll::Ready(None); };`.
+fn check_poll_guard_and_early_return<'tcx>(cx: &LateContext<'tcx>, expr: &'tcx Expr<'tcx>) -> Option<QuestionMarkSugg> {
+    if let Some(higher::If {
+        cond,
+        then,
+        r#else: None,
+    }) = higher::If::hir(expr)
+        && !expr.span.from_expansion()
+        && !is_else_clause(cx.tcx, expr)
+        && !span_contains_cfg_or_cfg_attr(cx, expr.span)
+        && let Some((caller, call_sym)) = is_none_or_err_cond(cx, cond)
+        && let caller_ty = typeck_results(cx).expr_ty(caller)
+        && let ExprKind::Ret(Some(ready)) = peel_early_return_blocks(then).kind
+        && let ExprKind::Call(ready_ctor, [ready_value]) = ready.kind
+        && is_res_lang_ctor(cx, path_res(cx, ready_ctor), PollReady)
+        && let Some(ctor) = match call_sym.as_str() {
+            "is_none"
+                if is_type_diagnostic_item(cx, caller_ty, sym::Option)
+                    && is_res_lang_ctor(cx, path_res(cx, ready_value), OptionNone) =>
+            {
+                prelude_path(cx, expr.hir_id, "option::Option::Some")
+            },
+            "is_err"
+                if is_type_diagnostic_item(cx, caller_ty, sym::Result)
+                    && let ExprKind::Call(err_ctor, [_]) = ready_value.kind
+                    && is_res_lang_ctor(cx, path_res(cx, err_ctor), ResultErr) =>
+            {
+                prelude_path(cx, expr.hir_id, "result::Result::Ok")
+            },
+            _ => None,
+        }
+    {
+        let mut applicability = Applicability::MachineApplicable;
+        let block_str = snippet_with_applicability(cx, then.span, "..", &mut applicability);
+        if let Some((stmt, pat, unwrapped)) = following_place_unwrap(cx, expr, caller) {
+            let pat_str = snippet_with_applicability(cx, pat.span, "..", &mut applicability);
+            let unwrapped_str = snippet_with_applicability(cx, unwrapped.span, "..", &mut applicability);
+            return Some(QuestionMarkSugg {
+                kind: QuestionMarkKind::IfIs,
+                hir_id: expr.hir_id,
+                span: expr.span,
+                msg: LET_ELSE_MSG,
+                sugg: let_else_guard_sugg(&ctor, &pat_str, &unwrapped_str, &block_str),
+                applicability,
+                msrv_blocked: None,
+                extra: Some(ExtraEdits {
+                    help: "replace it and bind the unwrapped value",
+                    replacements: vec![(
+                        stmt.span.with_leading_whitespace(cx).with_ctxt(stmt.span.ctxt()),
+                        String::new(),
+                    )],
+                    notes: Vec::new(),
+                    alternatives: Vec::new(),
+                    body_rewrite: None,
+                }),
+            });
+        }
+        let receiver_str = snippet_with_applicability(cx, caller.span, "..", &mut applicability);
+        Some(QuestionMarkSugg {
+            kind: QuestionMarkKind::IfIs,
+            hir_id: expr.hir_id,
+            span: expr.span,
+            msg: LET_ELSE_MSG,
+            sugg: let_else_guard_sugg(&ctor, "_", &receiver_str, &block_str),
+            applicability,
+            msrv_blocked: None,
+            extra: None,
+        })
+    } else {
+        None
+    }
+}
+
+/// If the statement right after `guard` is `let x = place.unwrap();`, possibly borrowing `place`
+/// with `as_ref()`/`as_mut()` first, with `place` being the `caller` checked by the guard, returns
+/// the statement, its pattern and the unwrapped expression.
+fn following_place_unwrap<'tcx>(
+    cx: &LateContext<'tcx>,
+    guard: &Expr<'tcx>,
+    caller: &Expr<'tcx>,
+) -> Option<(&'tcx Stmt<'tcx>, &'tcx Pat<'tcx>, &'tcx Expr<'tcx>)> {
+    let mut parents = cx.tcx.hir().parent_iter(guard.hir_id);
+    let (Some((stmt_id, Node::Stmt(_))), Some((_, Node::Block(block)))) = (parents.next(), parents.next()) else {
+        return None;
+    };
+    let pos = block.stmts.iter().position(|stmt| stmt.hir_id == stmt_id)?;
+    let next = block.stmts.get(pos + 1)?;
+    if let StmtKind::Let(LetStmt {
+        pat,
+        ty: None,
+        init: Some(init),
+        els: None,
+        ..
+    }) = next.kind
+        && !next.span.from_expansion()
+        && let ExprKind::MethodCall(method, unwrapped, args, _) = init.kind
+        && matches!(
+            (method.ident.name, args),
+            (sym::unwrap, [])
+                | (
+                    sym::expect,
+                    [Expr {
+                        kind: ExprKind::Lit(_),
+                        ..
+                    }]
+                )
+        )
+        && let place = match unwrapped.kind {
+            ExprKind::MethodCall(borrow, place, [], _) if matches!(borrow.ident.as_str(), "as_ref" | "as_mut") => place,
+            _ => unwrapped,
+        }
+        && SpanlessEq::new(cx).eq_expr(place, caller)
+    {
+        Some((next, pat, unwrapped))
+    } else {
+        None
+    }
+}
+
+/// Checks if `expr` only reads a local or a (possibly nested) field of one.
+fn is_plain_read(mut expr: &Expr<'_>) -> bool {
+    while let ExprKind::Field(base, _) = expr.kind {
+        expr = base;
+    }
+    path_to_local(expr).is_some()
+}
+
+fn check_is_none_and_return_err<'tcx>(cx: &LateContext<'tcx>, expr: &'tcx Expr<'tcx>) -> Option<QuestionMarkSugg> {
+    let mut applicability = Applicability::MachineApplicable;
+    let sugg = is_none_and_return_err_sugg(cx, expr, &mut applicability)?;
+    Some(QuestionMarkSugg {
+        kind: QuestionMarkKind::IfIs,
+        hir_id: expr.hir_id,
+        span: expr.span,
+        msg: "this block may be rewritten with the `?` operator",
+        sugg,
+        applicability,
+        msrv_blocked: None,
+        extra: None,
+    })
+}
+
+/// Checks for guards on the failure case of a type listed in `question-mark-custom-try`, which
+/// return the checked value itself:
+///
+/// ```ignore
+/// if v.is_invalid() {
+///     return v;
+/// }
+/// ```
+///
+/// As `v?;` consumes the value, this is only linted if it isn't used again afterwards.
+fn check_custom_try_and_early_return<'tcx>(
+    cx: &LateContext<'tcx>,
+    expr: &'tcx Expr<'tcx>,
+    custom_try: &DefIdMap<Symbol>,
+) -> Option<QuestionMarkSugg> {
+    if !custom_try.is_empty()
+        && let Some(higher::If {
+            cond,
+            then,
+            r#else: None,
+        }) = higher::If::hir(expr)
+        && !is_else_clause(cx.tcx, expr)
+        && !span_contains_cfg_or_cfg_attr(cx, expr.span)
+        && let Some((caller, call_sym)) = is_none_or_err_cond(cx, cond)
+        && let caller_ty = typeck_results(cx).expr_ty(caller)
+        && let ty::Adt(adt, _) = caller_ty.kind()
+        && custom_try.get(&adt.did()) == Some(&call_sym)
+        && let Some(local) = path_to_local(caller)
+        && expr_return_none_or_err(sym::Result, cx, then, caller, None)
+        && (caller_ty.is_copy_modulo_regions(cx.tcx, cx.param_env) || !local_used_after_guard(cx, local, expr))
+    {
+        let mut applicability = Applicability::MachineApplicable;
+        let receiver_str = snippet_with_applicability(cx, caller.span, "..", &mut applicability);
+        Some(QuestionMarkSugg {
+            kind: QuestionMarkKind::IfIs,
+            hir_id: expr.hir_id,
+            span: expr.span,
+            msg: "this block may be rewritten with the `?` operator",
+            sugg: format!("{receiver_str}?;"),
+            applicability,
+            msrv_blocked: None,
+            extra: None,
+        })
+    } else {
+        None
+    }
+}
+
+/// Checks for guards whose condition was hoisted into a local, which isn't used anywhere else:
+///
+/// ```ignore
+/// let missing = option.is_none();
+/// if missing {
+///     return None;
+/// }
+/// ```
+///
+/// The checked value must be read directly, and not be mutated between the two statements, so that
+/// checking it in place of the guard is the same.
+fn check_condition_local_and_early_return<'tcx>(
+    cx: &LateContext<'tcx>,
+    expr: &'tcx Expr<'tcx>,
+) -> Option<QuestionMarkSugg> {
+    let higher::If {
+        cond,
+        then,
+        r#else: None,
+    } = higher::If::hir(expr)?
+    else {
+        return None;
+    };
+    let flag = path_to_local(cond)?;
+    let Node::Stmt(guard_stmt) = cx.tcx.parent_hir_node(expr.hir_id) else {
+        return None;
+    };
+    let Node::Block(block) = cx.tcx.parent_hir_node(guard_stmt.hir_id) else {
+        return None;
+    };
+    let guard_idx = block.stmts.iter().position(|stmt| stmt.hir_id == guard_stmt.hir_id)?;
+    let (let_idx, let_stmt, init) =
+        block.stmts[..guard_idx]
+            .iter()
+            .enumerate()
+            .find_map(|(idx, stmt)| match stmt.kind {
+                StmtKind::Let(LetStmt {
+                    pat,
+                    init: Some(init),
+                    els: None,
+                    ty: None,
+                    ..
+                }) if matches!(pat.kind, PatKind::Binding(BindingMode::NONE, id, _, None) if id == flag) => {
+                    Some((idx, stmt, *init))
+                },
+                _ => None,
+            })?;
+    let (caller, call_sym) = is_none_or_err_cond(cx, init)?;
+    let caller_ty = typeck_results(cx).expr_ty(caller);
+    let if_block = IfBlockType::IfIs(caller, caller_ty, call_sym, then);
+    if expr.span.from_expansion()
+        || let_stmt.span.from_expansion()
+        || span_contains_cfg_or_cfg_attr(cx, expr.span)
+        || !is_plain_read(caller)
+        || !(is_early_return(sym::Option, cx, &if_block) || is_early_return(sym::Result, cx, &if_block))
+    {
+        return None;
+    }
+    let mut root = caller;
+    while let ExprKind::Field(base, _) = root.kind {
+        root = base;
+    }
+    let root = path_to_local(root)?;
+    // The condition is the only use of the local, and the checked value stays the same until then
+    let mut flag_uses = 0;
+    for_each_expr(cx, (&block.stmts[let_idx + 1..], block.expr), |e| {
+        flag_uses += usize::from(path_to_local_id(e, flag));
+        ControlFlow::<()>::Continue(())
+    });
+    let root_name = cx.tcx.hir().name(root);
+    if flag_uses != 1
+        || block.stmts[let_idx + 1..guard_idx].iter().any(|stmt| match stmt.kind {
+            StmtKind::Let(local) => {
+                let mut shadows_root = false;
+                local
+                    .pat
+                    .each_binding(|_, _, _, ident| shadows_root |= ident.name == root_name);
+                shadows_root || local.init.is_some_and(|init| is_potentially_mutated(root, init, cx))
+            },
+            StmtKind::Expr(e) | StmtKind::Semi(e) => is_potentially_mutated(root, e, cx),
+            StmtKind::Item(_) => false,
+        })
+    {
+        return None;
+    }
+
+    let mut applicability = Applicability::MachineApplicable;
+    let receiver_str = snippet_with_applicability(cx, caller.span, "..", &mut applicability);
+    let method_call_str = receiver_method_call(cx, expr, caller, None, &mut applicability);
+    if !method_call_str.is_empty() && is_type_diagnostic_item(cx, caller_ty, sym::Result) {
+        applicability = Applicability::MaybeIncorrect;
+    }
+    Some(QuestionMarkSugg {
+        kind: QuestionMarkKind::IfIs,
+        hir_id: expr.hir_id,
+        span: expr.span,
+        msg: "this block may be rewritten with the `?` operator",
+        sugg: format!("{receiver_str}{method_call_str}?;"),
+        applicability,
+        msrv_blocked: None,
+        extra: Some(ExtraEdits {
+            help: "replace it and remove the local holding the condition",
+            replacements: vec![(
+                let_stmt
+                    .span
+                    .with_leading_whitespace(cx)
+                    .with_ctxt(let_stmt.span.ctxt()),
+                String::new(),
+            )],
+            notes: Vec::new(),
+            alternatives: Vec::new(),
+            body_rewrite: None,
+        }),
+    })
+}
+
+/// For a guard on a lookup, like `if map.get(&k).is_none() { return None; }`, finds the later
+/// uses of the same lookup in the enclosing block (`map.get(&k).unwrap()` or `map[&k]`), which can
+/// reuse the value bound by the rewritten guard instead of looking it up again.
+///
+/// Chains of `Option` adapters (`self.name.as_deref()`) are treated the same way, and the value is
+/// then bound to a variable named after the adapted field. So are combinators of a local `Option`
+/// with another one or a function (`a.or(b)`, `a.and_then(parse)`), which evaluate the operands
+/// only once that way.
+///
+/// Lookups are only followed up to the first statement which may mutate the container or the key.
+fn lookup_reuses<'tcx>(
+    cx: &LateContext<'tcx>,
+    guard: &'tcx Expr<'tcx>,
+    lookup: &'tcx Expr<'tcx>,
+) -> Option<(String, Vec<(Span, String)>, Vec<(Span, String)>, Applicability)> {
+    let ExprKind::MethodCall(segment, mut container, args, _) = lookup.kind else {
+        return None;
+    };
+    let adapter = option_adapter_mutability(segment.ident.name.as_str());
+    let (mut is_mut, index_arg) = match (segment.ident.name.as_str(), args, adapter) {
+        ("get", [arg], _) => (false, Some(arg)),
+        ("get_mut", [arg], _) => (true, Some(arg)),
+        ("first" | "last" | "upgrade", [], _) => (false, None),
+        (_, [], Some(adapter_is_mut)) => (adapter_is_mut, None),
+        (name, [_], None) if is_option_combinator(name) => (false, None),
+        _ => return None,
+    };
+    let container_ty = typeck_results(cx).expr_ty(container).peel_refs();
+    let binding = if adapter.is_some() {
+        while let ExprKind::MethodCall(segment, receiver, [], _) = container.kind
+            && let Some(adapter_is_mut) = option_adapter_mutability(segment.ident.name.as_str())
+        {
+            is_mut |= adapter_is_mut;
+            container = receiver;
+        }
+        if !is_type_diagnostic_item(cx, typeck_results(cx).expr_ty(container), sym::Option) {
+            return None;
+        }
+        match container.kind {
+            ExprKind::Field(_, ident) if !ident.as_str().starts_with(|c: char| c.is_ascii_digit()) => ident.to_string(),
+            _ => "value".to_string(),
+        }
+    } else if is_option_combinator(segment.ident.name.as_str()) {
+        if !is_type_diagnostic_item(cx, typeck_results(cx).expr_ty(container), sym::Option) {
+            return None;
+        }
+        "value".to_string()
+    } else if segment.ident.name.as_str() == "upgrade" {
+        if !matches!(
+            get_type_diagnostic_name(cx, container_ty),
+            Some(sym::RcWeak | sym::ArcWeak)
+        ) {
+            return None;
+        }
+        match container.kind {
+            ExprKind::Field(_, ident) if !ident.as_str().starts_with(|c: char| c.is_ascii_digit()) => ident.to_string(),
+            _ => "value".to_string(),
+        }
+    } else if container_ty.is_slice()
+        || container_ty.is_array()
+        || [sym::Vec, sym::VecDeque, sym::HashMap, sym::BTreeMap]
+            .into_iter()
+            .any(|diag_item| is_type_diagnostic_item(cx, container_ty, diag_item))
+    {
+        "value".to_string()
+    } else {
+        return None;
+    };
+    let mut root = container;
+    while let ExprKind::Field(base, _) = root.kind {
+        root = base;
+    }
+    let root = path_to_local(root)?;
+    let Node::Stmt(guard_stmt) = cx.tcx.parent_hir_node(guard.hir_id) else {
+        return None;
+    };
+    let Node::Block(block) = cx.tcx.parent_hir_node(guard_stmt.hir_id) else {
+        return None;
+    };
+    let guard_idx = block.stmts.iter().position(|stmt| stmt.hir_id == guard_stmt.hir_id)?;
+
+    // The locals the lookup depends on; mutable lookups are checked separately, since the reuses
+    // themselves borrow the container mutably.
+    let mut locals = Vec::new();
+    for_each_expr_without_closures(lookup, |e| {
+        if let Some(id) = path_to_local(e)
+            && !(is_mut && id == root)
+        {
+            locals.push(id);
+        }
+        ControlFlow::<()>::Continue(())
+    });
+
+    let mut reuses = Vec::new();
+    let mut applicability = Applicability::MachineApplicable;
+    let rest = block.stmts[guard_idx + 1..]
+        .iter()
+        .filter_map(|stmt| match stmt.kind {
+            StmtKind::Let(local) => local.init,
+            StmtKind::Expr(e) | StmtKind::Semi(e) => Some(e),
+            StmtKind::Item(_) => None,
+        })
+        .chain(block.expr);
+    for node in rest {
+        let reuses_before = reuses.len();
+        let mut other_root_use = false;
+        for_each_expr(cx, node, |e| {
+            if e.span.from_expansion() {
+                return ControlFlow::<()>::Continue(Descend::Yes);
+            }
+            if let ExprKind::MethodCall(method, receiver, [], _) = e.kind
+                && method.ident.name == sym::unwrap
+                // The lookup is a method call itself, which `eq_expr_value` never considers equal
+                && SpanlessEq::new(cx).eq_expr(receiver, lookup)
+            {
+                reuses.push((e.span, binding.clone()));
+                return ControlFlow::Continue(Descend::No);
+            }
+            if let Some(index_arg) = index_arg
+                && let ExprKind::Index(base, index, _) = e.kind
+                && eq_expr_value(cx, base, container)
+                && eq_expr_value(cx, index, index_arg)
+            {
+                // Indexing panics instead of returning early, which only differs if the guard
+                // wasn't actually reaching it
+                applicability = Applicability::MaybeIncorrect;
+                let auto_derefs = get_parent_expr(cx, e).is_some_and(|parent| match parent.kind {
+                    ExprKind::MethodCall(_, receiver, ..) => receiver.hir_id == e.hir_id,
+                    ExprKind::Field(base, _) => base.hir_id == e.hir_id,
+                    _ => false,
+                });
+                reuses.push((
+                    e.span,
+                    if auto_derefs {
+                        binding.clone()
+                    } else {
+                        format!("*{binding}")
+                    },
+                ));
+                return ControlFlow::Continue(Descend::No);
+            }
+            if let ExprKind::Path(QPath::Resolved(None, path)) = e.kind
+                && let [segment] = path.segments
+                && segment.ident.as_str() == binding
+            {
+                // The new binding might shadow an existing one
+                applicability = Applicability::MaybeIncorrect;
+            }
+            other_root_use |= path_to_local_id(e, root);
+            ControlFlow::Continue(Descend::Yes)
+        });
+        let mutates = (is_mut && other_root_use) || locals.iter().any(|&id| is_potentially_mutated(id, node, cx));
+        if mutates {
+            if reuses.len() != reuses_before {
+                return None;
+            }
+            break;
+        }
+    }
+    if segment.ident.name.as_str() == "upgrade" {
+        return upgrade_reuse(cx, block, guard_idx, binding, reuses, applicability);
+    }
+    (!reuses.is_empty()).then_some((binding, reuses, Vec::new(), applicability))
+}
+
+/// Reuses the value of a `Weak` upgraded by a guard, where it's upgraded again later. The upgrade
+/// may fail the second time around, and the value is not `Copy`, so it may only be reused once. If
+/// that's `let x = weak.upgrade().unwrap();`, the guard binds `x` right away.
+fn upgrade_reuse<'tcx>(
+    cx: &LateContext<'tcx>,
+    block: &'tcx Block<'tcx>,
+    guard_idx: usize,
+    mut binding: String,
+    mut reuses: Vec<(Span, String)>,
+    mut applicability: Applicability,
+) -> Option<(String, Vec<(Span, String)>, Vec<(Span, String)>, Applicability)> {
+    let [(reuse_span, _)] = reuses[..] else {
+        return None;
+    };
+    let notes = vec![(
+        reuse_span,
+        "the `Weak` is upgraded again here, which the rewrite removes".to_string(),
+    )];
+    for (i, stmt) in block.stmts[guard_idx + 1..].iter().enumerate() {
+        if let StmtKind::Let(LetStmt {
+            pat,
+            ty: None,
+            init: Some(init),
+            els: None,
+            ..
+        }) = stmt.kind
+            && init.span == reuse_span
+            && let PatKind::Binding(BindingMode(ByRef::No, _), _, ident, None) = pat.kind
+        {
+            // The binding moves up to the guard, where it may shadow another one used in between
+            applicability = if block.stmts[guard_idx + 1..guard_idx + 1 + i].iter().any(|stmt| {
+                for_each_expr_without_closures(stmt, |e| {
+                    if let ExprKind::Path(QPath::Resolved(None, path)) = e.kind
+                        && let [segment] = path.segments
+                        && segment.ident.name == ident.name
+                    {
+                        ControlFlow::Break(())
+                    } else {
+                        ControlFlow::Continue(())
+                    }
+                })
+                .is_some()
+            }) {
+                Applicability::MaybeIncorrect
+            } else {
+                Applicability::MachineApplicable
+            };
+            binding = snippet_with_applicability(cx, pat.span, "..", &mut applicability).into_owned();
+            reuses = vec![(
+                stmt.span.with_leading_whitespace(cx).with_ctxt(stmt.span.ctxt()),
+                String::new(),
+            )];
+            break;
+        }
+    }
+    Some((binding, reuses, notes, applicability))
+}
+
+/// Checks if `name` is the name of an `Option` method combining it with another `Option` or with
+/// the result of a function, like `or` or `and_then`.
+fn is_option_combinator(name: &str) -> bool {
+    matches!(name, "and" | "and_then" | "filter" | "map" | "or" | "or_else" | "xor")
+}
+
+/// Checks if the combinator chain `chain` checked by `guard` moves a non-`Copy` local which is used
+/// again after the guard. Evaluating the chain only once in place of the guard might then not be
+/// what the code relies on.
+fn combinator_moves_used_later<'tcx>(cx: &LateContext<'tcx>, guard: &Expr<'tcx>, chain: &'tcx Expr<'tcx>) -> bool {
+    let ExprKind::MethodCall(segment, ..) = chain.kind else {
+        return false;
+    };
+    if !is_option_combinator(segment.ident.name.as_str()) {
+        return false;
+    }
+    let mut delegate = MoveDelegate {
+        cx,
+        moved: HirIdSet::default(),
+    };
+    ExprUseVisitor::for_clippy(cx, chain.hir_id.owner.def_id, &mut delegate)
+        .walk_expr(chain)
+        .into_ok();
+    delegate
+        .moved
+        .into_iter()
+        .any(|local| local_used_after_guard(cx, local, guard))
+}
+
+/// Collects the non-`Copy` locals which are moved by an expression.
+struct MoveDelegate<'a, 'tcx> {
+    cx: &'a LateContext<'tcx>,
+    moved: HirIdSet,
+}
+
+impl<'tcx> Delegate<'tcx> for MoveDelegate<'_, 'tcx> {
+    fn consume(&mut self, place_with_id: &PlaceWithHirId<'tcx>, _: HirId) {
+        if let PlaceBase::Local(local) = place_with_id.place.base
+            && !place_with_id
+                .place
+                .ty()
+                .is_copy_modulo_regions(self.cx.tcx, self.cx.param_env)
+        {
+            self.moved.insert(local);
+        }
+    }
+
+    fn borrow(&mut self, _: &PlaceWithHirId<'tcx>, _: HirId, _: BorrowKind) {}
+
+    fn mutate(&mut self, _: &PlaceWithHirId<'tcx>, _: HirId) {}
+
+    fn fake_read(&mut self, _: &PlaceWithHirId<'tcx>, _: FakeReadCause, _: HirId) {}
+}
+
+/// If `name` is the name of a method converting an `Option<T>` into an `Option` of a reference,
+/// returns whether that reference is mutable.
+fn option_adapter_mutability(name: &str) -> Option<bool> {
+    match name {
+        "as_ref" | "as_deref" => Some(false),
+        "as_mut" | "as_deref_mut" => Some(true),
+        _ => None,
+    }
+}
+
+/// Checks whether `cond` is the condition of a guard that `QUESTION_MARK` rewrote to use the `?`
+/// operator.
+///
+/// Lints which would otherwise suggest rewriting the comparison itself (e.g. `opt == None` or
+/// `opt.is_none() == true`) use this to step aside, so that only one diagnostic fires per site.
+/// The guard is checked before its condition, so whether `QUESTION_MARK` emitted a suggestion
+/// for it, with all of its configuration and the lint levels taken into account, is known by
+/// then.
+pub(crate) fn is_question_mark_guard_cond(cx: &LateContext<'_>, cond: &Expr<'_>, claims: &QuestionMarkClaims) -> bool {
+    if let Some(drop_temps) = get_parent_expr(cx, cond)
+        && let ExprKind::DropTemps(_) = drop_temps.kind
+        && let Some(if_expr) = get_parent_expr(cx, drop_temps)
+        && claims.is_claimed(if_expr.span)
+    {
+        is_none_or_err_and_early_return_sugg(cx, if_expr, &mut Applicability::Unspecified).is_some()
+            || is_none_and_return_err_sugg(cx, if_expr, &mut Applicability::Unspecified).is_some()
+    } else {
+        false
+    }
+}
+
+/// Returns the method call needed to keep binding the payload of `scrutinee` by reference once
+/// the pattern binding it is replaced with the `?` operator.
+///
+/// Options produced by adapters like `as_deref()` already contain a reference, so borrowing them
+/// again is only changing the type of the binding, from `&&T` to `&T`.
+fn by_ref_method_call(scrutinee: &Expr<'_>, by_ref: ByRef, applicability: &mut Applicability) -> &'static str {
+    if by_ref != ByRef::No
+        && let ExprKind::MethodCall(segment, _, [], _) = scrutinee.kind
+        && option_adapter_mutability(segment.ident.name.as_str()).is_some()
+    {
+        *applicability = Applicability::MaybeIncorrect;
+        return "";
+    }
+    match by_ref {
+        ByRef::Yes(Mutability::Mut) => ".as_mut()",
+        ByRef::Yes(Mutability::Not) => ".as_ref()",
+        ByRef::No => "",
+    }
+}
+
+/// For rewrites borrowing the scrutinee with `.as_ref()`, which changes the type of the unwrapped
+/// value from `T` to `&T`, offers cloning the scrutinee instead if possible. This isn't done if the
+/// type of the value is spelled out in a `let` statement.
+fn clone_alternative<'tcx>(
+    cx: &LateContext<'tcx>,
+    expr: &Expr<'_>,
+    scrutinee_ty: Ty<'tcx>,
+    receiver_str: &str,
+    method_call_str: &str,
+    semi: &str,
+) -> Option<ExtraEdits> {
+    if method_call_str == ".as_ref()"
+        && !matches!(
+            cx.tcx.parent_hir_node(expr.hir_id),
+            Node::LetStmt(LetStmt { ty: Some(_), .. })
+        )
+        && let Some(clone_trait) = cx.tcx.lang_items().clone_trait()
+        && implements_trait(cx, scrutinee_ty, clone_trait, &[])
+    {
+        Some(ExtraEdits {
+            help: "replace it with",
+            replacements: Vec::new(),
+            notes: Vec::new(),
+            alternatives: vec![("or clone the value instead", format!("{receiver_str}.clone()?{semi}"))],
+            body_rewrite: None,
+        })
+    } else {
+        None
+    }
+}
+
+/// Checks for `if let` expressions unwrapping an `Option` or a `Result`, which return early
+/// otherwise:
+///
+/// ```ignore
+/// let value = if let Some(value) = option { value } else { return None };
+///
+/// if let Err(e) = result {
+///     return Err(e);
+/// }
+/// ```
+///
+/// These become `let value = option?;` and `result?;`. `if let` statements which only check for
+/// the existence of a value are rewritten as well:
+///
+/// ```ignore
+/// if let Some(_) = option {
+/// } else {
+///     return None;
+/// }
+///
+/// if let Err(_) = result {
+///     return Err(MyError);
+/// }
+/// ```
+///
+/// The first one becomes `option?;` (borrowing non-`Copy` options), the second one
+/// `result.map_err(|_| MyError)?;`. `if let None = option { return None; }` and
+/// `if let Err(_) = result { return result; }` are rewritten to `option?;` and `result?;`.
+///
+/// `redundant_pattern_matching` suggests `is_some()`-like calls for all of the latter. It leaves
+/// the ones rewritten here to this lint, except for the last two forms, which are left to it unless
+/// it's allowed: its `is_none()`-like guards are linted in turn.
+fn check_if_let_some_or_err_and_early_return<'tcx>(
+    cx: &LateContext<'tcx>,
+    expr: &'tcx Expr<'tcx>,
+) -> Option<QuestionMarkSugg> {
+    let higher::IfLet {
+        let_pat,
+        let_expr,
+        if_then,
+        if_else,
+        ..
+    } = higher::IfLet::hir(cx, expr)?;
+    if is_else_clause(cx.tcx, expr) || span_contains_cfg_or_cfg_attr(cx, expr.span) {
+        return None;
+    }
+    let mut applicability = Applicability::MachineApplicable;
+    let mut extra = None;
+    let sugg = if let Some(res) = wild_pattern_res(cx, let_pat) {
+        if !matches!(cx.tcx.parent_hir_node(expr.hir_id), Node::Stmt(_)) {
+            return None;
+        }
+        let caller_ty = typeck_results(cx).expr_ty(let_expr);
+        let receiver_str = snippet_with_applicability(cx, let_expr.span, "..", &mut applicability);
+        if is_res_lang_ctor(cx, res, OptionSome)
+            && let Some(if_else) = if_else
+            && is_empty_block(if_then)
+            && expr_return_none_or_err(sym::Option, cx, if_else, let_expr, None)
+        {
+            let method_call_str = receiver_method_call(cx, expr, let_expr, None, &mut applicability);
+            format!("{receiver_str}{method_call_str}?;")
+        } else if ((is_res_lang_ctor(cx, res, OptionNone)
+            && if_else.is_none()
+            && expr_return_none_or_err(sym::Option, cx, if_then, let_expr, None))
+            || (is_res_lang_ctor(cx, res, ResultErr)
+                && if_else.is_none()
+                && expr_return_none_or_err(sym::Result, cx, if_then, let_expr, None)))
+            && is_lint_allowed(cx, REDUNDANT_PATTERN_MATCHING, expr.hir_id)
+        {
+            let method_call_str = receiver_method_call(cx, expr, let_expr, None, &mut applicability);
+            if !method_call_str.is_empty() && is_type_diagnostic_item(cx, peel_receiver_ref(caller_ty), sym::Result) {
+                applicability = Applicability::MaybeIncorrect;
+            }
+            format!("{receiver_str}{method_call_str}?;")
+        } else if is_res_lang_ctor(cx, res, ResultErr)
+            && if_else.is_none()
+            && let ExprKind::Ret(Some(ret)) = peel_blocks_with_stmt(if_then).kind
+            && let ExprKind::Call(err_ctor, [err]) = ret.kind
+            && is_res_lang_ctor(cx, path_res(cx, err_ctor), ResultErr)
+            && !err.span.from_expansion()
+            && can_be_lifted_into_closure(cx, expr, let_expr, err)
+        {
+            // `Result::as_ref` only borrows the error, which is discarded anyway
+            let by_ref = !receiver_method_call(cx, expr, let_expr, None, &mut applicability).is_empty();
+            map_err_sugg(cx, &receiver_str, by_ref, "_", err, &mut applicability)
+        } else {
+            return None;
+        }
+    } else if let PatKind::TupleStruct(ref path1, [field], ddpos) = let_pat.kind
+        && ddpos.as_opt_usize().is_none()
+        && let PatKind::Binding(BindingMode(by_ref, _), bind_id, ident, None) = field.kind
+        && let caller_ty = peel_receiver_ref(typeck_results(cx).expr_ty(let_expr))
+        && let if_block = IfBlockType::IfLet(
+            cx.qpath_res(path1, let_pat.hir_id),
+            caller_ty,
             ident.name,
             let_expr,
             if_then,
             if_else,
         )
-        && ((is_early_return(sym::Option, cx, &if_block) && path_to_local_id(peel_blocks(if_then), bind_id))
-            || is_early_return(sym::Result, cx, &if_block))
+        // Unless the `if let` only returns the error, its value has to be the binding
+        && (path_to_local_id(peel_blocks(if_then), bind_id)
+            || (if_else.is_none() && is_early_return(sym::Result, cx, &if_block)))
+        && (is_early_return(sym::Option, cx, &if_block) || is_early_return(sym::Result, cx, &if_block))
         && if_else
             .map(|e| eq_expr_value(cx, let_expr, peel_blocks(e)))
             .filter(|e| *e)
             .is_none()
+        // A scrutinee expanded from a macro (e.g. `if let Err(e) = write!(f, "{x}")`) is replaced by
+        // the macro call itself
+        && let Some(receiver_span) = walk_span_to_context(let_expr.span, expr.span.ctxt())
+    {
+        if !nested_payloads_line_up(cx, expr.hir_id, caller_ty) {
+            applicability = Applicability::MaybeIncorrect;
+        }
+        let receiver_str = snippet_with_applicability(cx, receiver_span, "..", &mut applicability);
+        // A semicolon already following the `if let` is kept
+        let requires_semi = matches!(
+            cx.tcx.parent_hir_node(expr.hir_id),
+            Node::Stmt(Stmt {
+                kind: StmtKind::Expr(_),
+                ..
+            })
+        );
+        let method_call_str = receiver_method_call(cx, expr, let_expr, Some(by_ref), &mut applicability);
+        let semi = if requires_semi { ";" } else { "" };
+        if let Some(err_ty) = uninhabited_result_err_ty(cx, caller_ty) {
+            return Some(QuestionMarkSugg {
+                kind: QuestionMarkKind::IfLet,
+                hir_id: expr.hir_id,
+                span: expr.span,
+                msg: "the error case of this `Result` is impossible",
+                sugg: format!("{receiver_str}{method_call_str}.unwrap(){semi}"),
+                applicability: Applicability::MaybeIncorrect,
+                msrv_blocked: None,
+                extra: Some(uninhabited_err_note(cx, let_expr, err_ty)),
+            });
+        }
+        // Borrowing a referenced scrutinee binds its payload by reference just like the pattern did
+        if !typeck_results(cx).expr_ty(let_expr).is_ref() {
+            extra = clone_alternative(cx, expr, caller_ty, &receiver_str, method_call_str, semi);
+        }
+        if extra.is_some() {
+            applicability = Applicability::MaybeIncorrect;
+        }
+        format!("{receiver_str}{method_call_str}?{semi}")
+    } else {
+        return None;
+    };
+    add_body_rewrite(cx, expr, &sugg, &mut extra);
+    Some(QuestionMarkSugg {
+        kind: QuestionMarkKind::IfLet,
+        hir_id: expr.hir_id,
+        span: expr.span,
+        msg: "this block may be rewritten with the `?` operator",
+        sugg,
+        applicability,
+        msrv_blocked: None,
+        extra,
+    })
+}
+
+/// Returns the variant matched by `pat` if it only checks for the variant, like `Some(_)`,
+/// `Some(..)` or `None`.
+fn wild_pattern_res(cx: &LateContext<'_>, pat: &Pat<'_>) -> Option<Res> {
+    match pat.kind {
+        PatKind::TupleStruct(ref path, fields, ddpos)
+            if matches!(
+                (fields, ddpos.as_opt_usize()),
+                (
+                    [Pat {
+                        kind: PatKind::Wild,
+                        ..
+                    }],
+                    None
+                ) | ([], Some(0))
+            ) =>
+        {
+            Some(cx.qpath_res(path, pat.hir_id))
+        },
+        PatKind::Path(ref path) => Some(cx.qpath_res(path, pat.hir_id)),
+        _ => None,
+    }
+}
+
+/// Checks if `expr` is an `if let` statement which only checks for the existence of a value. These
+/// are checked along with their statement rather than as expressions, so that their rewrite is
+/// claimed before `redundant_pattern_matching` checks them.
+fn is_wild_if_let_stmt(cx: &LateContext<'_>, expr: &Expr<'_>) -> bool {
+    higher::IfLet::hir(cx, expr).is_some_and(|if_let| wild_pattern_res(cx, if_let.let_pat).is_some())
+        && matches!(cx.tcx.parent_hir_node(expr.hir_id), Node::Stmt(_))
+}
+
+/// Checks if `expr` is an `if let` which only checks for the existence of a value, and is
+/// rewritten by a suggestion `QUESTION_MARK` emitted. `redundant_pattern_matching` doesn't lint
+/// these.
+pub(crate) fn is_question_mark_wild_guard<'tcx>(
+    cx: &LateContext<'tcx>,
+    expr: &'tcx Expr<'tcx>,
+    claims: &QuestionMarkClaims,
+) -> bool {
+    claims.is_claimed(expr.span) && check_if_let_some_or_err_and_early_return(cx, expr).is_some()
+}
+
+/// Returns `receiver.map_err(|param| err)?;`, with `.as_ref()` called on the receiver if `by_ref`.
+fn map_err_sugg(
+    cx: &LateContext<'_>,
+    receiver_str: &str,
+    by_ref: bool,
+    param: &str,
+    err: &Expr<'_>,
+    applicability: &mut Applicability,
+) -> String {
+    let err_str = snippet_with_applicability(cx, err.span, "..", applicability);
+    format!(
+        "{receiver_str}{}.map_err(|{param}| {err_str})?;",
+        if by_ref { ".as_ref()" } else { "" }
+    )
+}
+
+/// Checks for `if let Err(e)` expressions returning the error passed through a helper:
+///
+/// ```ignore
+/// if let Err(e) = step() {
+///     return Err(self.wrap(e));
+/// }
+/// ```
+///
+/// This becomes `step().map_err(|e| self.wrap(e))?;`. The helper has to be a single function or
+/// method call taking `e` as one of its arguments, with the other arguments and the receiver being
+/// free of side effects.
+fn check_if_let_err_and_return_wrapped<'tcx>(
+    cx: &LateContext<'tcx>,
+    expr: &'tcx Expr<'tcx>,
+) -> Option<QuestionMarkSugg> {
+    if let Some(higher::IfLet {
+        let_pat,
+        let_expr,
+        if_then,
+        if_else: None,
+        ..
+    }) = higher::IfLet::hir(cx, expr)
+        && !is_else_clause(cx.tcx, expr)
+        && !span_contains_cfg_or_cfg_attr(cx, expr.span)
+        && matches!(cx.tcx.parent_hir_node(expr.hir_id), Node::Stmt(_))
+        && let PatKind::TupleStruct(ref path, [field], ddpos) = let_pat.kind
+        && ddpos.as_opt_usize().is_none()
+        && is_res_lang_ctor(cx, cx.qpath_res(path, let_pat.hir_id), ResultErr)
+        && let PatKind::Binding(mode @ BindingMode(ByRef::No, _), bind_id, ident, None) = field.kind
+        && let ExprKind::Ret(Some(ret)) = peel_blocks_with_stmt(if_then).kind
+        && let ExprKind::Call(err_ctor, [err]) = ret.kind
+        && is_res_lang_ctor(cx, path_res(cx, err_ctor), ResultErr)
+        && !err.span.from_expansion()
+        && wraps_binding(err, bind_id)
+        && can_be_lifted_into_closure(cx, expr, let_expr, err)
+        && !mutably_borrows_used_later(cx, expr, err)
+    {
+        let mut applicability = Applicability::MaybeIncorrect;
+        // Borrowing the receiver would change the type of the error passed to the helper
+        if !receiver_method_call(cx, expr, let_expr, None, &mut applicability).is_empty() {
+            return None;
+        }
+        let receiver_str = snippet_with_applicability(cx, let_expr.span, "..", &mut applicability);
+        let param = format!("{}{ident}", mode.prefix_str());
+        let sugg = map_err_sugg(cx, &receiver_str, false, &param, err, &mut applicability);
+        Some(QuestionMarkSugg {
+            kind: QuestionMarkKind::IfLet,
+            hir_id: expr.hir_id,
+            span: expr.span,
+            msg: "this block may be rewritten with the `?` operator",
+            sugg,
+            applicability,
+            msrv_blocked: None,
+            extra: None,
+        })
+    } else {
+        None
+    }
+}
+
+/// Checks for `if let Some(Err(e))` expressions returning the error, which is what the `?`
+/// operator does after transposing the `Option<Result<T, E>>`:
+///
+/// ```ignore
+/// if let Some(Err(e)) = maybe_result {
+///     return Err(e);
+/// }
+/// ```
+///
+/// This becomes `maybe_result.transpose()?;`, which consumes the scrutinee, so locals which are
+/// used afterwards are left alone. Code relying on the nesting of the scrutinee usually wants to
+/// bind the transposed `Option<T>` instead, hence the suggestion is `MaybeIncorrect`.
+fn check_if_let_some_err_and_transpose<'tcx>(
+    cx: &LateContext<'tcx>,
+    expr: &'tcx Expr<'tcx>,
+) -> Option<QuestionMarkSugg> {
+    if let Some(higher::IfLet {
+        let_pat,
+        let_expr,
+        if_then,
+        if_else: None,
+        ..
+    }) = higher::IfLet::hir(cx, expr)
+        && !is_else_clause(cx.tcx, expr)
+        && !span_contains_cfg_or_cfg_attr(cx, expr.span)
+        && matches!(cx.tcx.parent_hir_node(expr.hir_id), Node::Stmt(_))
+        && let PatKind::TupleStruct(ref some_path, [err_pat], some_ddpos) = let_pat.kind
+        && some_ddpos.as_opt_usize().is_none()
+        && is_res_lang_ctor(cx, cx.qpath_res(some_path, let_pat.hir_id), OptionSome)
+        && let PatKind::TupleStruct(ref err_path, [field], err_ddpos) = err_pat.kind
+        && err_ddpos.as_opt_usize().is_none()
+        && is_res_lang_ctor(cx, cx.qpath_res(err_path, err_pat.hir_id), ResultErr)
+        && let PatKind::Binding(BindingMode(ByRef::No, _), bind_id, _, None) = field.kind
+        && let ExprKind::Ret(Some(ret)) = peel_blocks_with_stmt(if_then).kind
+        && let ExprKind::Call(err_ctor, [err]) = ret.kind
+        && ret.span.ctxt() == expr.span.ctxt()
+        && is_res_lang_ctor(cx, path_res(cx, err_ctor), ResultErr)
+        && path_to_local_id(err, bind_id)
+        // Matching on a reference would need the `Option` to be cloned to be transposed
+        && is_type_diagnostic_item(cx, typeck_results(cx).expr_ty(let_expr), sym::Option)
+        && (matches!(let_expr.kind, ExprKind::Call(..) | ExprKind::MethodCall(..))
+            || path_to_local(let_expr).is_some_and(|local| !local_used_after_guard(cx, local, expr)))
+    {
+        let mut applicability = Applicability::MaybeIncorrect;
+        let receiver_str = snippet_with_applicability(cx, let_expr.span, "..", &mut applicability);
+        Some(QuestionMarkSugg {
+            kind: QuestionMarkKind::IfLet,
+            hir_id: expr.hir_id,
+            span: expr.span,
+            msg: "this block may be rewritten with the `?` operator",
+            sugg: format!("{receiver_str}.transpose()?;"),
+            applicability,
+            msrv_blocked: None,
+            extra: None,
+        })
+    } else {
+        None
+    }
+}
+
+/// Checks if `err` is a function or method call which takes the local `binding` as one of its
+/// arguments, and whose other arguments and receiver don't have side effects.
+fn wraps_binding(err: &Expr<'_>, binding: HirId) -> bool {
+    let (receiver, args) = match err.kind {
+        ExprKind::Call(callee, args) if matches!(callee.kind, ExprKind::Path(_)) => (None, args),
+        ExprKind::MethodCall(_, receiver, args, _) => (Some(receiver), args),
+        _ => return false,
+    };
+    args.iter().any(|arg| path_to_local_id(arg, binding))
+        && receiver
+            .into_iter()
+            .chain(args.iter().filter(|arg| !path_to_local_id(arg, binding)))
+            .all(is_side_effect_free)
+}
+
+/// Checks if `expr` doesn't call anything or assign to anything.
+fn is_side_effect_free(expr: &Expr<'_>) -> bool {
+    for_each_expr_without_closures(expr, |e| match e.kind {
+        ExprKind::Call(..)
+        | ExprKind::MethodCall(..)
+        | ExprKind::Assign(..)
+        | ExprKind::AssignOp(..)
+        | ExprKind::InlineAsm(..) => ControlFlow::Break(()),
+        _ => ControlFlow::Continue(()),
+    })
+    .is_none()
+}
+
+/// Checks if `err` borrows or mutates a local mutably which is used again after `guard`, as the
+/// `map_err` closure would have to capture it.
+fn mutably_borrows_used_later<'tcx>(cx: &LateContext<'tcx>, guard: &Expr<'tcx>, err: &'tcx Expr<'tcx>) -> bool {
+    let mut delegate = MutBorrowDelegate {
+        borrowed: HirIdSet::default(),
+    };
+    ExprUseVisitor::for_clippy(cx, err.hir_id.owner.def_id, &mut delegate)
+        .walk_expr(err)
+        .into_ok();
+    delegate
+        .borrowed
+        .into_iter()
+        .any(|local| local_used_after_guard(cx, local, guard))
+}
+
+/// Collects the locals which are borrowed mutably or mutated by an expression.
+struct MutBorrowDelegate {
+    borrowed: HirIdSet,
+}
+
+impl<'tcx> Delegate<'tcx> for MutBorrowDelegate {
+    fn consume(&mut self, _: &PlaceWithHirId<'tcx>, _: HirId) {}
+
+    fn borrow(&mut self, place_with_id: &PlaceWithHirId<'tcx>, _: HirId, kind: BorrowKind) {
+        if kind == BorrowKind::Mutable
+            && let PlaceBase::Local(local) = place_with_id.place.base
+        {
+            self.borrowed.insert(local);
+        }
+    }
+
+    fn mutate(&mut self, place_with_id: &PlaceWithHirId<'tcx>, _: HirId) {
+        if let PlaceBase::Local(local) = place_with_id.place.base {
+            self.borrowed.insert(local);
+        }
+    }
+
+    fn fake_read(&mut self, _: &PlaceWithHirId<'tcx>, _: FakeReadCause, _: HirId) {}
+}
+
+/// Checks if `expr` is an empty block, or evaluates to `()` without doing anything.
+fn is_empty_block(expr: &Expr<'_>) -> bool {
+    match expr.kind {
+        ExprKind::Block(block, None) => {
+            block.stmts.is_empty() && block.expr.map_or(true, |e| matches!(e.kind, ExprKind::Tup([])))
+        },
+        _ => false,
+    }
+}
+
+/// Checks if the given expression is a `match` expression which could be replaced by the question
+/// mark operator:
+///
+/// ```ignore
+/// match option {
+///     Some(x) => x,
+///     None => return None,
+/// }
+/// ```
+///
+/// ```ignore
+/// match result {
+///     Ok(x) => x,
+///     Err(e) => return Err(e),
+/// }
+/// ```
+///
+/// With `MatchLintBehaviour::AllTypes`, the early returning arm of an `Option` match may also use a
+/// wildcard pattern instead of spelling out `None`.
+fn check_match_some_or_ok_and_early_return<'tcx>(
+    cx: &LateContext<'tcx>,
+    expr: &Expr<'tcx>,
+    behaviour: MatchLintBehaviour,
+) -> Option<QuestionMarkSugg> {
+    if behaviour != MatchLintBehaviour::Never
+        && let ExprKind::Match(scrutinee, [first, second], MatchSource::Normal) = expr.kind
+        && !expr.span.from_expansion()
+        && first.guard.is_none()
+        && second.guard.is_none()
+        && !span_contains_cfg_or_cfg_attr(cx, expr.span)
+        && let Some(by_ref) = match_arms_unwrap_or_return(cx, scrutinee, first, second, behaviour)
+            .or_else(|| match_arms_unwrap_or_return(cx, scrutinee, second, first, behaviour))
+    {
+        let mut applicability = Applicability::MachineApplicable;
+        let receiver_str = snippet_with_applicability(cx, scrutinee.span, "..", &mut applicability);
+        let requires_semi = matches!(
+            cx.tcx.parent_hir_node(expr.hir_id),
+            Node::Stmt(Stmt {
+                kind: StmtKind::Expr(_),
+                ..
+            })
+        );
+        let method_call_str = by_ref_method_call(scrutinee, by_ref, &mut applicability);
+        let semi = if requires_semi { ";" } else { "" };
+        let extra = clone_alternative(
+            cx,
+            expr,
+            typeck_results(cx).expr_ty(scrutinee),
+            &receiver_str,
+            method_call_str,
+            semi,
+        );
+        if extra.is_some() {
+            applicability = Applicability::MaybeIncorrect;
+        }
+        Some(QuestionMarkSugg {
+            kind: QuestionMarkKind::Match,
+            hir_id: expr.hir_id,
+            span: expr.span,
+            msg: "this `match` expression may be rewritten with the `?` operator",
+            sugg: format!("{receiver_str}{method_call_str}?{semi}"),
+            applicability,
+            msrv_blocked: None,
+            extra,
+        })
+    } else {
+        None
+    }
+}
+
+/// Checks if the given expression is a `match` on the condition of a guard, which is otherwise
+/// written as an `if` expression:
+///
+/// ```ignore
+/// match option.is_none() {
+///     true => return None,
+///     false => {},
+/// }
+/// ```
+fn check_bool_match_and_early_return<'tcx>(cx: &LateContext<'tcx>, expr: &'tcx Expr<'tcx>) -> Option<QuestionMarkSugg> {
+    if let ExprKind::Match(cond, [first, second], MatchSource::Normal) = expr.kind
+        && !expr.span.from_expansion()
+        && cond.span.ctxt() == expr.span.ctxt()
+        && first.guard.is_none()
+        && second.guard.is_none()
+        && !span_contains_cfg_or_cfg_attr(cx, expr.span)
+        // The `match` evaluates to `()`, unlike the rewritten expression, so it has to be a statement
+        && let Node::Stmt(Stmt {
+            kind: StmtKind::Expr(_) | StmtKind::Semi(_),
+            ..
+        }) = cx.tcx.parent_hir_node(expr.hir_id)
+        // A wildcard arm has to come last, as the arm after it is never reached
+        && let Some((then, other)) = match (bool_lit_pat(first.pat), bool_lit_pat(second.pat)) {
+            (Some(true), Some(false)) => Some((first, second)),
+            (Some(false), Some(true)) => Some((second, first)),
+            (Some(true), None) if matches!(second.pat.kind, PatKind::Wild) => Some((first, second)),
+            _ => None,
+        }
+        && (is_empty_block(other.body) || matches!(other.body.kind, ExprKind::Tup([])))
+        && let Some((caller, call_sym)) = is_none_or_err_cond(cx, cond)
+        && borrowed_place_derefs(cx, caller).is_none()
+        && let checked_ty = peel_receiver_ref(typeck_results(cx).expr_ty(caller))
+        && let if_block = IfBlockType::IfIs(caller, checked_ty, call_sym, then.body)
+        && (is_early_return(sym::Option, cx, &if_block) || is_early_return(sym::Result, cx, &if_block))
     {
         let mut applicability = Applicability::MachineApplicable;
-        let receiver_str = snippet_with_applicability(cx, let_expr.span, "..", &mut applicability);
-        let requires_semi = matches!(cx.tcx.parent_hir_node(expr.hir_id), Node::Stmt(_));
-        let method_call_str = match by_ref {
-            ByRef::Yes(Mutability::Mut) => ".as_mut()",
-            ByRef::Yes(Mutability::Not) => ".as_ref()",
-            ByRef::No => "",
-        };
-        let sugg = format!(
-            "{receiver_str}{method_call_str}?{}",
-            if requires_semi { ";" } else { "" }
-        );
-        span_lint_and_sugg(
-            cx,
-            QUESTION_MARK,
-            expr.span,
-            "this block may be rewritten with the `?` operator",
-            "replace it with",
-            sugg,
+        let receiver_str = snippet_with_applicability(cx, caller.span, "..", &mut applicability);
+        let method_call_str = receiver_method_call(cx, expr, caller, None, &mut applicability);
+        // `Result::as_ref` also borrows the error, which then has to be converted to the returned one
+        if !method_call_str.is_empty() && is_type_diagnostic_item(cx, checked_ty, sym::Result) {
+            applicability = Applicability::MaybeIncorrect;
+        }
+        Some(QuestionMarkSugg {
+            kind: QuestionMarkKind::IfIs,
+            hir_id: expr.hir_id,
+            span: expr.span,
+            msg: "this `match` expression may be rewritten with the `?` operator",
+            sugg: format!("{receiver_str}{method_call_str}?;"),
             applicability,
-        );
+            msrv_blocked: None,
+            extra: None,
+        })
+    } else {
+        None
+    }
+}
+
+/// If `pat` is a `bool` literal, returns its value.
+fn bool_lit_pat(pat: &Pat<'_>) -> Option<bool> {
+    if let PatKind::Lit(Expr {
+        kind: ExprKind::Lit(lit),
+        ..
+    }) = pat.kind
+        && let LitKind::Bool(b) = lit.node
+    {
+        Some(b)
+    } else {
+        None
+    }
+}
+
+/// Checks whether `value_arm` unwraps the `Some`/`Ok` payload of the scrutinee, while `ret_arm`
+/// returns its `None`/`Err` case early. If so, returns the binding mode of the payload.
+fn match_arms_unwrap_or_return(
+    cx: &LateContext<'_>,
+    scrutinee: &Expr<'_>,
+    value_arm: &Arm<'_>,
+    ret_arm: &Arm<'_>,
+    behaviour: MatchLintBehaviour,
+) -> Option<ByRef> {
+    let PatKind::TupleStruct(ref value_path, [field], ddpos) = value_arm.pat.kind else {
+        return None;
+    };
+    let PatKind::Binding(BindingMode(by_ref, _), bind_id, _, None) = field.kind else {
+        return None;
+    };
+    if ddpos.as_opt_usize().is_some() || !path_to_local_id(peel_blocks(value_arm.body), bind_id) {
+        return None;
+    }
+
+    let scrutinee_ty = typeck_results(cx).expr_ty(scrutinee);
+    let value_res = cx.qpath_res(value_path, value_arm.pat.hir_id);
+    let returns_early =
+        if is_type_diagnostic_item(cx, scrutinee_ty, sym::Option) && is_res_lang_ctor(cx, value_res, OptionSome) {
+            let is_none_pat = match ret_arm.pat.kind {
+                PatKind::Path(ref qpath) => is_res_lang_ctor(cx, cx.qpath_res(qpath, ret_arm.pat.hir_id), OptionNone),
+                PatKind::Wild => behaviour == MatchLintBehaviour::AllTypes,
+                _ => false,
+            };
+            is_none_pat && expr_return_none_or_err(sym::Option, cx, ret_arm.body, scrutinee, None)
+        } else if is_type_diagnostic_item(cx, scrutinee_ty, sym::Result) && is_res_lang_ctor(cx, value_res, ResultOk) {
+            if let PatKind::TupleStruct(ref err_path, [err_field], ddpos) = ret_arm.pat.kind
+                && ddpos.as_opt_usize().is_none()
+                && is_res_lang_ctor(cx, cx.qpath_res(err_path, ret_arm.pat.hir_id), ResultErr)
+                && let PatKind::Binding(BindingMode(ByRef::No, _), _, err_ident, None) = err_field.kind
+            {
+                expr_return_none_or_err(sym::Result, cx, ret_arm.body, scrutinee, Some(err_ident.name))
+            } else {
+                false
+            }
+        } else {
+            false
+        };
+    returns_early.then_some(by_ref)
+}
+
+/// Why `QUESTION_MARK` doesn't check an expression or a statement at all.
+#[derive(Clone, Copy)]
+enum SkipReason {
+    GeneratedBody,
+    AllowedBody,
+    TryBlock,
+    Visibility,
+    FfiBody,
+    ConstContext,
+    LetElseDisabled,
+}
+
+/// Checks if `node` has the shape of any of the expressions or statements which may be rewritten,
+/// i.e. an `if`, a `match`, a `loop` or a `let...else` statement. This only looks at the syntax of
+/// `node`, so that the checks running queries (`typeck` in particular) are skipped for most nodes.
+fn may_be_candidate(node: Node<'_>) -> bool {
+    match node {
+        Node::Expr(expr) => matches!(
+            expr.kind,
+            ExprKind::If(..) | ExprKind::Match(_, _, MatchSource::Normal) | ExprKind::Loop(_, _, LoopSource::Loop, _)
+        ),
+        Node::Stmt(stmt) => matches!(stmt.kind, StmtKind::Let(LetStmt { els: Some(_), .. })),
+        _ => false,
+    }
+}
+
+/// Returns the typeck results of the enclosing body. All of the checks of this pass go through
+/// this, so that `tests/question-mark-bench.rs` can count the calls.
+fn typeck_results<'tcx>(cx: &LateContext<'tcx>) -> &'tcx TypeckResults<'tcx> {
+    #[cfg(feature = "internal")]
+    bench::count(&bench::TYPECK_RESULTS);
+    cx.typeck_results()
+}
+
+/// Counters used by `tests/question-mark-bench.rs` to measure the cost of classifying nodes.
+///
+/// Only the nodes classified for the lints of the pass itself are counted. Other lints asking
+/// whether `QUESTION_MARK` would lint a node classify it a second time, which isn't counted.
+#[cfg(feature = "internal")]
+pub mod bench {
+    use super::{Declined, QuestionMarkSugg};
+    use std::cell::Cell;
+    use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+    use std::time::Instant;
+
+    /// Whether nodes which can't be rewritten are skipped before running any of the checks. With
+    /// this turned off, every check runs on every node.
+    pub static FAST_PATH: AtomicBool = AtomicBool::new(true);
+    /// The number of expressions and statements classified.
+    pub static NODES: AtomicUsize = AtomicUsize::new(0);
+    /// The number of nodes skipped by the fast path.
+    pub static FAST_PATH_BAILS: AtomicUsize = AtomicUsize::new(0);
+    /// The number of rewrites found, whether they are emitted or not.
+    pub static SUGGESTIONS: AtomicUsize = AtomicUsize::new(0);
+    /// The number of calls to `LateContext::typeck_results` made while classifying nodes.
+    pub static TYPECK_RESULTS: AtomicUsize = AtomicUsize::new(0);
+    /// The total time spent classifying nodes.
+    pub static CLASSIFY_NANOS: AtomicU64 = AtomicU64::new(0);
+
+    thread_local! {
+        /// Whether a node is being classified by `record`.
+        static RECORDING: Cell<bool> = const { Cell::new(false) };
+    }
+
+    pub(super) fn fast_path() -> bool {
+        FAST_PATH.load(Ordering::Relaxed)
+    }
+
+    /// Increments `counter` if a node is being classified by `record`.
+    pub(super) fn count(counter: &AtomicUsize) {
+        if RECORDING.get() {
+            counter.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Runs `classify` on a node checked by the pass itself, counting it and what it runs.
+    pub(super) fn record(
+        classify: impl FnOnce() -> Result<QuestionMarkSugg, Declined>,
+    ) -> Result<QuestionMarkSugg, Declined> {
+        RECORDING.set(true);
+        let start = Instant::now();
+        let res = classify();
+        let nanos = u64::try_from(start.elapsed().as_nanos()).unwrap_or(u64::MAX);
+        RECORDING.set(false);
+        CLASSIFY_NANOS.fetch_add(nanos, Ordering::Relaxed);
+        NODES.fetch_add(1, Ordering::Relaxed);
+        if res.is_ok() {
+            SUGGESTIONS.fetch_add(1, Ordering::Relaxed);
+        }
+        res
+    }
+}
+
+/// Why `QUESTION_MARK` doesn't suggest a rewrite for an expression or a statement.
+enum Declined {
+    /// The node isn't checked, or none of the shapes which can be rewritten match it.
+    Structural,
+    /// A shape matches, but every rewrite of it requires a feature which isn't available at the
+    /// MSRV. Holds the rewrite which would have been suggested otherwise.
+    Msrv(MsrvFeature, QuestionMarkSugg),
+}
+
+/// The features required by some of the rewrites, which aren't available at every MSRV.
+#[derive(Clone, Copy)]
+enum MsrvFeature {
+    LetElse,
+    OptionTranspose,
+}
+
+impl MsrvFeature {
+    fn version(self) -> RustcVersion {
+        match self {
+            Self::LetElse => msrvs::LET_ELSE,
+            Self::OptionTranspose => msrvs::OPTION_TRANSPOSE,
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            Self::LetElse => "`let...else`",
+            Self::OptionTranspose => "`Option::transpose`",
+        }
+    }
+
+    /// The note explaining why a rewrite requiring the feature isn't suggested at `msrv`.
+    fn blocked_note(self, msrv: RustcVersion) -> String {
+        let required = self.version();
+        format!(
+            "rewriting requires Rust {}.{} for {} (current MSRV {}.{})",
+            required.major,
+            required.minor,
+            self.description(),
+            msrv.major,
+            msrv.minor,
+        )
+    }
+}
+
+#[cfg(feature = "internal")]
+impl SkipReason {
+    fn description(self) -> &'static str {
+        match self {
+            Self::GeneratedBody => "the body is generated code",
+            Self::AllowedBody => "`question_mark` and `manual_let_else` are allowed throughout the body",
+            Self::TryBlock => "it is inside of a `try` block",
+            Self::Visibility => "the body is excluded by `question-mark-visibility`",
+            Self::FfiBody => "the body belongs to an `extern` function, or a skipped `#[no_mangle]` one",
+            Self::ConstContext => "it is in a const context",
+            Self::LetElseDisabled => "`question-mark-lint-let-else` is disabled",
+        }
     }
 }
 
 impl QuestionMark {
+    /// Checks if `QUESTION_MARK_USED` lints uses of the `?` operator at `hir_id`, in which case
+    /// they aren't suggested, and only `let...else` rewrites are made.
+    ///
+    /// `QUESTION_MARK` fires wherever `?` would be acceptable at the exact node it lints, so this
+    /// is only checked for the node of a suggestion, never for the statement or expression
+    /// containing it.
+    fn question_mark_used_applies(&self, cx: &LateContext<'_>, hir_id: HirId) -> bool {
+        !is_lint_allowed(cx, QUESTION_MARK_USED, hir_id)
+            && !is_in_allowed_trait_impl(cx, hir_id, &self.question_mark_used_allowed_traits)
+    }
+
+    /// Tracks the state `check_stmt` and `check_expr` depend on, to be called from `check_body`.
+    pub(crate) fn enter_body(&mut self, cx: &LateContext<'_>, body: &Body<'_>) {
+        // Closures are part of the body they are defined in, whose suggestions may contain them
+        if self.generated_body_stack.is_empty() {
+            self.claims.clear();
+        }
+        self.try_blocks.enter_body();
+        // Closures inside of generated code are generated as well
+        let generated = self.inside_generated_body() || is_generated_body(cx, body);
+        self.generated_body_stack.push(generated);
+        let visibility_skipped = self.is_visibility_skipped(cx, body);
+        self.visibility_skip_stack.push(visibility_skipped);
+        self.allowed_body_stack.push(is_allowed_body(cx, body));
+        let ffi_skipped = self.is_ffi_skipped(cx, body);
+        self.ffi_skip_stack.push(ffi_skipped);
+    }
+
+    /// To be called from `check_body_post`.
+    pub(crate) fn exit_body(&mut self) {
+        self.try_blocks.exit_body();
+        self.generated_body_stack.pop();
+        self.visibility_skip_stack.pop();
+        self.allowed_body_stack.pop();
+        self.ffi_skip_stack.pop();
+    }
+
+    /// To be called from `check_block`.
+    pub(crate) fn enter_block(&mut self, cx: &LateContext<'_>, block: &Block<'_>) {
+        self.try_blocks.enter_block(cx, block);
+    }
+
+    /// To be called from `check_block_post`.
+    pub(crate) fn exit_block(&mut self, cx: &LateContext<'_>, block: &Block<'_>) {
+        self.try_blocks.exit_block(cx, block);
+    }
+
     fn inside_try_block(&self) -> bool {
-        self.try_block_depth_stack.last() > Some(&0)
+        self.try_blocks.inside_try_block()
+    }
+
+    fn inside_generated_body(&self) -> bool {
+        self.generated_body_stack.last() == Some(&true)
+    }
+
+    fn inside_visibility_skipped_body(&self) -> bool {
+        self.visibility_skip_stack.last() == Some(&true)
+    }
+
+    fn inside_allowed_body(&self) -> bool {
+        self.allowed_body_stack.last() == Some(&true)
+    }
+
+    fn inside_ffi_skipped_body(&self) -> bool {
+        self.ffi_skip_stack.last() == Some(&true)
+    }
+
+    /// Checks if the body is excluded by `question-mark-visibility`. Closures are checked using the
+    /// visibility of the item containing them.
+    fn is_visibility_skipped(&self, cx: &LateContext<'_>, body: &Body<'_>) -> bool {
+        let owner = cx.tcx.hir().body_owner_def_id(body.id());
+        let item = cx.tcx.typeck_root_def_id(owner.to_def_id()).expect_local();
+        match self.visibility {
+            QuestionMarkVisibility::All => false,
+            QuestionMarkVisibility::PublicOnly => !cx.effective_visibilities.is_exported(item),
+            QuestionMarkVisibility::PrivateOnly => cx.effective_visibilities.is_exported(item),
+        }
+    }
+
+    /// Checks if the body belongs to a function with a non-Rust ABI, or to a `#[no_mangle]` one
+    /// excluded by `question-mark-skip-no-mangle`. Closures are checked using the function
+    /// containing them.
+    fn is_ffi_skipped(&self, cx: &LateContext<'_>, body: &Body<'_>) -> bool {
+        let owner = cx.tcx.hir().body_owner_def_id(body.id());
+        let item = cx.tcx.typeck_root_def_id(owner.to_def_id());
+        matches!(cx.tcx.def_kind(item), DefKind::Fn | DefKind::AssocFn)
+            && (cx.tcx.fn_sig(item).skip_binder().abi() != Abi::Rust
+                || (self.skip_no_mangle && cx.tcx.has_attr(item, sym::no_mangle)))
+    }
+
+    /// Checks whether `QUESTION_MARK` would lint `node`, an expression or a statement, returning
+    /// the kind of the rewritten code.
+    ///
+    /// Other lints of this pass use this to avoid suggesting changes which `QUESTION_MARK` would
+    /// then lint again.
+    pub(crate) fn question_mark_candidate<'tcx>(
+        &self,
+        cx: &LateContext<'tcx>,
+        node: Node<'tcx>,
+    ) -> Option<QuestionMarkKind> {
+        self.find_sugg(cx, node)
+            .ok()
+            .filter(|sugg| !is_lint_allowed(cx, sugg.kind.lint(), sugg.hir_id))
+            .map(|sugg| sugg.kind)
+    }
+
+    /// Returns why `QUESTION_MARK` doesn't check `node`, an expression or a statement, at all.
+    fn skip_reason(&self, cx: &LateContext<'_>, node: Node<'_>) -> Option<SkipReason> {
+        if self.inside_generated_body() {
+            Some(SkipReason::GeneratedBody)
+        } else if self.inside_allowed_body() {
+            Some(SkipReason::AllowedBody)
+        } else if self.inside_try_block() {
+            Some(SkipReason::TryBlock)
+        } else if self.inside_visibility_skipped_body() {
+            Some(SkipReason::Visibility)
+        } else if self.inside_ffi_skipped_body() {
+            Some(SkipReason::FfiBody)
+        } else if let Node::Expr(Expr { hir_id, .. }) | Node::Stmt(Stmt { hir_id, .. }) = node
+            && is_in_const_body(cx, *hir_id)
+        {
+            Some(SkipReason::ConstContext)
+        } else if matches!(node, Node::Stmt(_)) && !self.lint_let_else {
+            Some(SkipReason::LetElseDisabled)
+        } else {
+            None
+        }
+    }
+
+    /// Finds the rewrite `QUESTION_MARK` suggests for `node`, regardless of the lint level, or why
+    /// there is none.
+    fn find_sugg<'tcx>(&self, cx: &LateContext<'tcx>, node: Node<'tcx>) -> Result<QuestionMarkSugg, Declined> {
+        #[cfg(feature = "internal")]
+        let fast_path = bench::fast_path();
+        #[cfg(not(feature = "internal"))]
+        let fast_path = true;
+        self.classify(cx, node, fast_path)
+    }
+
+    /// Implements `find_sugg`. Unless `fast_path` is false, nodes which can't be rewritten by any
+    /// of the checks are skipped up front.
+    fn classify<'tcx>(
+        &self,
+        cx: &LateContext<'tcx>,
+        node: Node<'tcx>,
+        fast_path: bool,
+    ) -> Result<QuestionMarkSugg, Declined> {
+        if fast_path && !may_be_candidate(node) {
+            #[cfg(feature = "internal")]
+            bench::count(&bench::FAST_PATH_BAILS);
+            return Err(Declined::Structural);
+        }
+        if self.skip_reason(cx, node).is_some() {
+            return Err(Declined::Structural);
+        }
+        let mut msrv_blocked = None;
+        let sugg = match node {
+            Node::Stmt(stmt) => check_let_some_else_return_none(cx, stmt),
+            // Rewritten along with the first guard of the cascade
+            Node::Expr(expr) if continues_guard_cascade(cx, expr) => None,
+            // Rewritten along with the loop around it
+            Node::Expr(expr) if is_loop_guard(cx, expr) => None,
+            Node::Expr(expr) => {
+                // Only one of the kinds may lint a given expression, otherwise we would emit two
+                // overlapping suggestions for it.
+                check_guard_cascade_and_early_return(cx, expr)
+                    .or_else(|| check_loop_guard_and_break(cx, expr))
+                    .or_else(|| check_is_none_or_err_and_early_return(cx, expr))
+                    .or_else(|| check_is_none_and_return_err(cx, expr))
+                    .or_else(|| check_custom_try_and_early_return(cx, expr, &self.custom_try))
+                    .or_else(|| {
+                        self.track_condition_locals
+                            .then(|| check_condition_local_and_early_return(cx, expr))
+                            .flatten()
+                    })
+                    .or_else(|| check_if_let_some_or_err_and_early_return(cx, expr))
+                    .or_else(|| check_if_let_err_and_return_wrapped(cx, expr))
+                    .or_else(|| {
+                        self.msrv_gated(MsrvFeature::OptionTranspose, &mut msrv_blocked, || {
+                            check_if_let_some_err_and_transpose(cx, expr)
+                        })
+                    })
+                    .or_else(|| check_match_some_or_ok_and_early_return(cx, expr, self.matches_for_question_mark))
+                    .or_else(|| check_bool_match_and_early_return(cx, expr))
+                    .or_else(|| {
+                        self.split_conditions
+                            .then(|| check_split_condition_and_early_return(cx, expr))
+                            .flatten()
+                    })
+                    .or_else(|| check_dead_flag_and_early_return(cx, expr))
+                    .or_else(|| {
+                        self.msrv_gated(MsrvFeature::LetElse, &mut msrv_blocked, || {
+                            check_poll_guard_and_early_return(cx, expr)
+                        })
+                    })
+                    .or_else(|| {
+                        self.msrv_gated(MsrvFeature::LetElse, &mut msrv_blocked, || {
+                            check_guard_statements_and_early_return(cx, expr)
+                        })
+                    })
+            },
+            _ => None,
+        };
+        let Some(sugg) = sugg else {
+            return Err(match msrv_blocked {
+                // Pointing out that a newer MSRV allows using `?` is no help where it's banned
+                Some((feature, sugg))
+                    if sugg.msg == LET_ELSE_MSG || !self.question_mark_used_applies(cx, sugg.hir_id) =>
+                {
+                    Declined::Msrv(feature, sugg)
+                },
+                _ => Declined::Structural,
+            });
+        };
+        // `?` returns from the innermost body, which may be a closure whose return type is inferred
+        if sugg.msg != LET_ELSE_MSG && !closure_err_ty_still_inferred(cx, &sugg) {
+            return Err(Declined::Structural);
+        }
+        if sugg.msg == LET_ELSE_MSG || !self.question_mark_used_applies(cx, sugg.hir_id) {
+            return Ok(sugg);
+        }
+        // With `?` banned, an `if let` or `match` expression initializing a local can still become a
+        // `let...else` statement. The other shapes can only be improved by using `?`.
+        match (node, sugg.kind) {
+            (Node::Expr(expr), QuestionMarkKind::IfLet | QuestionMarkKind::Match) => {
+                self.let_else_fallback(cx, expr, sugg.kind)
+            },
+            _ => None,
+        }
+        .ok_or(Declined::Structural)
+    }
+
+    /// Lints `node` if it may be rewritten.
+    fn check_node<'tcx>(&mut self, cx: &LateContext<'tcx>, node: Node<'tcx>) {
+        #[cfg(feature = "internal")]
+        let res = bench::record(|| self.find_sugg(cx, node));
+        #[cfg(not(feature = "internal"))]
+        let res = self.find_sugg(cx, node);
+        match res {
+            Ok(sugg) => self.emit_or_group(cx, sugg),
+            Err(Declined::Msrv(feature, sugg)) => self.emit_msrv_blocked(cx, feature, sugg),
+            Err(Declined::Structural) => {},
+        }
+    }
+
+    /// Runs `check`, a suggestion builder whose rewrite requires `feature`. If the MSRV doesn't
+    /// meet it, no rewrite is returned, but the first one which would have been is kept in
+    /// `blocked`, so that the site can be linted without a suggestion.
+    fn msrv_gated(
+        &self,
+        feature: MsrvFeature,
+        blocked: &mut Option<(MsrvFeature, QuestionMarkSugg)>,
+        check: impl FnOnce() -> Option<QuestionMarkSugg>,
+    ) -> Option<QuestionMarkSugg> {
+        if self.msrv.meets(feature.version()) {
+            check()
+        } else {
+            if blocked.is_none() {
+                *blocked = check().map(|sugg| (feature, sugg));
+            }
+            None
+        }
+    }
+
+    /// Rewrites the `let` statement initialized by `init` to a `let...else` statement, like
+    /// `MANUAL_LET_ELSE` does.
+    fn let_else_fallback(
+        &self,
+        cx: &LateContext<'_>,
+        init: &Expr<'_>,
+        kind: QuestionMarkKind,
+    ) -> Option<QuestionMarkSugg> {
+        if let Node::LetStmt(local) = cx.tcx.parent_hir_node(init.hir_id)
+            && let Node::Stmt(stmt) = cx.tcx.parent_hir_node(local.hir_id)
+            && let Some(rewrite) = self.let_else_rewrite(cx, stmt)
+            && rewrite.init.hir_id == init.hir_id
+        {
+            let mut applicability = Applicability::MaybeIncorrect;
+            let sugg = manual_let_else::let_else_sugg(
+                cx,
+                stmt.span,
+                rewrite.scrutinee,
+                &rewrite.ident_map,
+                rewrite.pat,
+                None,
+                rewrite.else_body,
+                self.suggestion_max_width,
+                &mut applicability,
+            );
+            Some(QuestionMarkSugg {
+                kind,
+                hir_id: stmt.hir_id,
+                span: stmt.span,
+                msg: LET_ELSE_MSG,
+                sugg,
+                applicability,
+                msrv_blocked: None,
+                extra: None,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Checks for an uninitialized `let x;` which is initialized by the following `if let`, and
+    /// returns early otherwise:
+    ///
+    /// ```ignore
+    /// let x;
+    /// if let Some(v) = option {
+    ///     x = v;
+    /// } else {
+    ///     return None;
+    /// }
+    /// ```
+    ///
+    /// This becomes `let x = option?;`. Returns whether it was linted, as `manual_let_else` lints
+    /// the same statements otherwise.
+    pub(crate) fn check_deferred_init_question_mark<'tcx>(
+        &mut self,
+        cx: &LateContext<'tcx>,
+        init: &DeferredInit<'tcx>,
+    ) -> bool {
+        // The `if let` is checked, the `let...else` configuration doesn't apply to the declaration
+        if self.skip_reason(cx, Node::Expr(init.if_let.let_expr)).is_some()
+            || self.question_mark_used_applies(cx, init.hir_id)
+            || is_lint_allowed(cx, QUESTION_MARK, init.hir_id)
+            || span_contains_cfg_or_cfg_attr(cx, init.span)
+        {
+            return false;
+        }
+        let higher::IfLet { let_pat, let_expr, .. } = init.if_let;
+        if let PatKind::TupleStruct(ref qpath, [field], ddpos) = let_pat.kind
+            && ddpos.as_opt_usize().is_none()
+            && let PatKind::Binding(_, _, ident, None) = field.kind
+            && ident == init.binding
+            && let caller_ty = typeck_results(cx).expr_ty(let_expr)
+            && let if_block = IfBlockType::IfLet(
+                cx.qpath_res(qpath, let_pat.hir_id),
+                caller_ty,
+                ident.name,
+                let_expr,
+                init.if_let.if_then,
+                Some(init.if_else),
+            )
+            && (is_early_return(sym::Option, cx, &if_block) || is_early_return(sym::Result, cx, &if_block))
+        {
+            let mut applicability = if nested_payloads_line_up(cx, init.hir_id, caller_ty)
+                && !span_contains_comment(cx.sess().source_map(), init.span)
+            {
+                Applicability::MachineApplicable
+            } else {
+                Applicability::MaybeIncorrect
+            };
+            let pat_str = snippet_with_applicability(cx, init.local.pat.span, "..", &mut applicability);
+            let ty_str = init.local.ty.map_or(String::new(), |ty| {
+                format!(
+                    ": {}",
+                    snippet_with_applicability(cx, ty.span, "..", &mut applicability)
+                )
+            });
+            let receiver_str = snippet_with_applicability(cx, let_expr.span, "..", &mut applicability);
+            let sugg = QuestionMarkSugg {
+                kind: QuestionMarkKind::IfLet,
+                hir_id: init.hir_id,
+                span: init.span,
+                msg: "this declaration and `if let` may be rewritten with the `?` operator",
+                sugg: format!("let {pat_str}{ty_str} = {receiver_str}?;"),
+                applicability,
+                msrv_blocked: None,
+                extra: None,
+            };
+            if !closure_err_ty_still_inferred(cx, &sugg) {
+                return false;
+            }
+            self.emit_or_group(cx, sugg);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Emits the lint for `sugg`, or collects it for the diagnostic of its body when `group_per_fn`
+    /// is set. Every rewrite suggested by `QUESTION_MARK` goes through here, so that it's checked
+    /// against the source and the configuration the same way.
+    fn emit_or_group(&mut self, cx: &LateContext<'_>, mut sugg: QuestionMarkSugg) {
+        sugg.span = snap_to_first_token(cx, sugg.span);
+        // A snippet which couldn't be read back from the source was replaced by a `..` placeholder,
+        // which only matters if the rewrite is suggested
+        if (sugg.applicability == Applicability::HasPlaceholders && sugg.msrv_blocked.is_none())
+            || !sugg_spans_match_source(cx, &sugg)
+        {
+            return;
+        }
+        annotate_doctest_ok(cx, &mut sugg);
+        trim_stmt_semi(cx, &mut sugg);
+        if let Some(note) = cfg_sibling_note(cx, &sugg) {
+            sugg.applicability = Applicability::MaybeIncorrect;
+            sugg.extra
+                .get_or_insert_with(|| ExtraEdits {
+                    help: "replace it with",
+                    replacements: Vec::new(),
+                    notes: Vec::new(),
+                    alternatives: Vec::new(),
+                    body_rewrite: None,
+                })
+                .notes
+                .push(note);
+        }
+        // Checked last, as the applicability may still have been lowered above
+        if self.machine_applicable_only && sugg.applicability != Applicability::MachineApplicable {
+            return;
+        }
+        if let Some(wrapped) = wrap_let_sugg(
+            &sugg.sugg,
+            indent_of(cx, sugg.span).unwrap_or(0),
+            self.suggestion_max_width,
+        ) {
+            sugg.sugg = wrapped;
+        }
+        if !is_lint_allowed(cx, sugg.kind.lint(), sugg.hir_id) {
+            // `--fix` only applies one of two overlapping suggestions. The nodes are visited from
+            // the outside in, so the outermost one is kept, and the ones inside of it are left to
+            // the next run rather than being discarded by `--fix`.
+            let spans: Vec<Span> = iter::once(sugg.span)
+                .chain(
+                    sugg.extra
+                        .iter()
+                        .flat_map(|extra| extra.replacements.iter().map(|(span, _)| *span)),
+                )
+                .collect();
+            // Without a suggestion nothing is rewritten, so the lints leaving the guard to this
+            // one must still lint it
+            let claimed = if sugg.msrv_blocked.is_some() {
+                !self.claims.overlaps(&spans)
+            } else {
+                self.claims.claim(spans)
+            };
+            if !claimed {
+                return;
+            }
+            self.candidate_counts[sugg.kind as usize] += 1;
+        }
+        if self.group_per_fn
+            && let Some(group) = self.grouped_suggs_stack.last_mut()
+        {
+            if !is_lint_allowed(cx, sugg.kind.lint(), sugg.hir_id) {
+                group.push(sugg);
+            }
+        } else {
+            sugg.emit(cx, self.machine_applicable_only);
+        }
+    }
+
+    /// Emits the lint of the kind of `sugg` without any suggestion, as its rewrite requires
+    /// `feature`, noting the Rust version which would allow it.
+    fn emit_msrv_blocked(&mut self, cx: &LateContext<'_>, feature: MsrvFeature, mut sugg: QuestionMarkSugg) {
+        // Without a suggestion, there is nothing `--fix` could apply
+        if self.machine_applicable_only {
+            return;
+        }
+        let Some(msrv) = self.msrv.current() else {
+            return;
+        };
+        sugg.msrv_blocked = Some((feature, msrv));
+        self.emit_or_group(cx, sugg);
+    }
+}
+
+/// Checks that the code replaced by `sugg` is actually there in the source. Proc macros can give
+/// the code they generate the spans of the user's code, which makes it look like it was written by
+/// the user. Applying a suggestion to such code would edit whatever the spans point to instead.
+///
+/// The replaced site has to start (and, if it's a single expression, end) with the tokens of its
+/// HIR node, and all of the replaced spans need balanced delimiters.
+fn sugg_spans_match_source(cx: &LateContext<'_>, sugg: &QuestionMarkSugg) -> bool {
+    let (first, last) = match cx.tcx.hir_node(sugg.hir_id) {
+        Node::Expr(Expr {
+            kind: ExprKind::If(..), ..
+        }) => (Some("if"), Some("}")),
+        Node::Expr(Expr {
+            kind: ExprKind::Match(..),
+            ..
+        }) => (Some("match"), Some("}")),
+        Node::Stmt(Stmt {
+            kind: StmtKind::Let(_), ..
+        })
+        | Node::LetStmt(_) => (Some("let"), None),
+        _ => (None, None),
+    };
+    source_tokens_match(cx, sugg.span, first, last)
+        && sugg
+            .extra
+            .iter()
+            .flat_map(|extra| &extra.replacements)
+            .all(|&(span, _)| source_tokens_match(cx, span, None, None))
+}
+
+/// Moves the start of `span` to the first token of the code it covers, past any whitespace,
+/// comments and attributes. The span of a statement at the start of a block must never cover the
+/// inner attributes of the block, which would otherwise be replaced along with it.
+fn snap_to_first_token(cx: &LateContext<'_>, span: Span) -> Span {
+    span.map_range(cx, |src, range| {
+        let text = src.get(range.clone())?;
+        let mut in_attr = false;
+        let mut depth = 0usize;
+        for (kind, _, inner) in tokenize_with_text(text) {
+            match kind {
+                TokenKind::Whitespace | TokenKind::LineComment { .. } | TokenKind::BlockComment { .. } => {},
+                TokenKind::Pound if depth == 0 => in_attr = true,
+                TokenKind::OpenBracket if in_attr => depth += 1,
+                TokenKind::CloseBracket if in_attr => {
+                    depth = depth.saturating_sub(1);
+                    in_attr = depth != 0;
+                },
+                _ if in_attr => {},
+                _ => return Some(range.start + inner.start..range.end),
+            }
+        }
+        None
+    })
+    .map_or(span, |range| range.with_ctxt(span.ctxt()))
+}
+
+/// Checks that the source text of `span` starts with the token `first` and ends with `last`, if
+/// given, and that its delimiters are balanced.
+fn source_tokens_match(cx: &LateContext<'_>, span: Span, first: Option<&str>, last: Option<&str>) -> bool {
+    span.check_source_text(cx, |src| {
+        let mut depth = 0usize;
+        let mut tokens = tokenize_with_text(src)
+            .filter(|(kind, ..)| {
+                !matches!(
+                    kind,
+                    TokenKind::Whitespace | TokenKind::LineComment { .. } | TokenKind::BlockComment { .. }
+                )
+            })
+            .peekable();
+        if first.is_some_and(|first| tokens.peek().is_none_or(|&(_, text, _)| text != first)) {
+            return false;
+        }
+        let mut last_text = "";
+        for (kind, text, _) in tokens {
+            match kind {
+                TokenKind::OpenBrace | TokenKind::OpenParen | TokenKind::OpenBracket => depth += 1,
+                TokenKind::CloseBrace | TokenKind::CloseParen | TokenKind::CloseBracket => {
+                    let Some(outer) = depth.checked_sub(1) else {
+                        return false;
+                    };
+                    depth = outer;
+                },
+                _ => {},
+            }
+            last_text = text;
+        }
+        depth == 0 && last.is_none_or(|last| last_text == last)
+    })
+}
+
+/// Suggestions for guards end with a semicolon, as the guards are usually statements of their own.
+/// If the replaced expression is already followed by one (`if x.is_none() { return None; };`), that
+/// one is kept and the suggested one dropped, so that the statement ends exactly once.
+fn trim_stmt_semi(cx: &LateContext<'_>, sugg: &mut QuestionMarkSugg) {
+    if let Node::Stmt(Stmt {
+        kind: StmtKind::Semi(_),
+        span: stmt_span,
+        ..
+    }) = cx.tcx.parent_hir_node(sugg.hir_id)
+        && stmt_span.ctxt() == sugg.span.ctxt()
+        && stmt_span
+            .with_lo(sugg.span.hi())
+            .check_source_text(cx, |src| src.trim_start() == ";")
+        && sugg.sugg.ends_with(';')
+    {
+        sugg.sugg.pop();
+        if let Some(extra) = &mut sugg.extra {
+            for (_, alternative) in &mut extra.alternatives {
+                if alternative.ends_with(';') {
+                    alternative.pop();
+                }
+            }
+        }
+    }
+}
+
+/// Looks for a statement next to the one being rewritten which is configured out by a `#[cfg]`
+/// attribute and mentions one of the locals used by the rewritten code, like the
+/// `#[cfg(debug_assertions)]` counterpart of a guard. It's not part of the HIR, so only its source
+/// is checked. Applying the rewrite without adjusting that statement as well could break the code
+/// under the other configuration.
+fn cfg_sibling_note(cx: &LateContext<'_>, sugg: &QuestionMarkSugg) -> Option<(Span, String)> {
+    let stmt = match cx.tcx.hir_node(sugg.hir_id) {
+        Node::Stmt(stmt) => stmt,
+        Node::Expr(expr) => match cx.tcx.parent_hir_node(expr.hir_id) {
+            Node::Stmt(stmt) => stmt,
+            _ => return None,
+        },
+        _ => return None,
+    };
+    let Node::Block(block) = cx.tcx.parent_hir_node(stmt.hir_id) else {
+        return None;
+    };
+    let idx = block.stmts.iter().position(|s| s.hir_id == stmt.hir_id)?;
+    if block.span.from_expansion() || stmt.span.ctxt() != block.span.ctxt() {
+        return None;
+    }
+    // The attributes of the statement itself aren't part of its span
+    let stmt_lo = [stmt.hir_id, sugg.hir_id]
+        .into_iter()
+        .flat_map(|id| cx.tcx.hir().attrs(id))
+        .map(|attr| attr.span.lo())
+        .fold(stmt.span.lo(), BytePos::min);
+    // The block may start with `unsafe`, and end with a closing brace preceded by any character
+    let source_map = cx.sess().source_map();
+    let before_lo = idx.checked_sub(1).map_or_else(
+        || source_map.span_through_char(block.span, '{').hi(),
+        |prev| block.stmts[prev].span.hi(),
+    );
+    let after_hi = block
+        .stmts
+        .get(idx + 1)
+        .map(|next| next.span)
+        .or(block.expr.map(|e| e.span))
+        .map_or_else(|| source_map.end_point(block.span).lo(), Span::lo);
+
+    let mut names = Vec::new();
+    for_each_expr(cx, stmt, |e| {
+        if let Some(local) = path_to_local(e) {
+            names.push(cx.tcx.hir().name(local));
+        }
+        ControlFlow::<()>::Continue(())
+    });
+    [
+        block.span.with_lo(before_lo).with_hi(stmt_lo),
+        block.span.with_lo(stmt.span.hi()).with_hi(after_hi),
+    ]
+    .into_iter()
+    .find_map(|gap| {
+        let src = gap.get_source_text(cx)?;
+        let name = tokenize_with_text(&src)
+            .find(|&(kind, text, _)| kind == TokenKind::Ident && names.iter().any(|name| name.as_str() == text))?
+            .1;
+        if !span_contains_cfg(cx, gap) {
+            return None;
+        }
+        // Point at the configured out code rather than at the surrounding whitespace
+        let code = gap
+            .map_range(cx, |src, range| {
+                let text = src.get(range.clone())?;
+                let start = range.start + (text.len() - text.trim_start().len());
+                Some(start..range.start + text.trim_end().len())
+            })?
+            .with_ctxt(gap.ctxt());
+        Some((
+            code,
+            format!("`{name}` is also used by code which is configured out here, and which may need the same rewrite"),
+        ))
+    })
+}
+
+/// Rustdoc wraps doctests ending in `Ok(())` in
+/// `fn main() { fn _inner() -> Result<(), impl core::fmt::Debug> { .. } _inner().unwrap() }`,
+/// whose error type is inferred from the errors returned by the example. Once these returns are
+/// replaced with the `?` operator, the error type can't be inferred anymore, so the suggestion
+/// spells it out on the final `Ok(())`, the way the rustdoc book recommends.
+fn annotate_doctest_ok(cx: &LateContext<'_>, sugg: &mut QuestionMarkSugg) {
+    let owner = cx.tcx.hir().enclosing_body_owner(sugg.hir_id);
+    if let Some(name) = cx.tcx.opt_item_name(owner.to_def_id())
+        && (name.as_str() == "_inner" || name.as_str().starts_with("_doctest_main_"))
+        && cx.tcx.opt_item_name(cx.tcx.local_parent(owner).to_def_id()) == Some(sym::main)
+        && let ty::Adt(adt, args) = cx
+            .tcx
+            .fn_sig(owner)
+            .instantiate_identity()
+            .output()
+            .skip_binder()
+            .kind()
+        && cx.tcx.is_diagnostic_item(sym::Result, adt.did())
+        && let ty::Alias(ty::Opaque, opaque) = args.type_at(1).kind()
+        && let err_ty = cx.tcx.type_of(opaque.def_id).instantiate_identity()
+        && !err_ty.references_error()
+        && let ExprKind::Block(block, _) = cx.tcx.hir().body_owned_by(owner).value.kind
+        && let Some(tail) = block.expr
+        && let ExprKind::Call(ctor, [unit]) = tail.kind
+        && let ExprKind::Path(QPath::Resolved(None, path)) = ctor.kind
+        && path.segments.last().is_some_and(|segment| segment.args.is_none())
+        && is_res_lang_ctor(cx, path.res, ResultOk)
+        && matches!(unit.kind, ExprKind::Tup([]))
+        && !tail.span.from_expansion()
+        && !sugg.span.overlaps(tail.span)
+    {
+        let mut app = Applicability::MaybeIncorrect;
+        let ctor_str = snippet_with_applicability(cx, ctor.span, "Ok", &mut app);
+        let replacement = (tail.span, format!("{ctor_str}::<(), {err_ty}>(())"));
+        // The type may not be nameable in the example, e.g. if it's private to the documented crate
+        sugg.applicability = Applicability::MaybeIncorrect;
+        match &mut sugg.extra {
+            // The help only describes the edits when there are some besides the rewrite
+            Some(extra) if extra.replacements.is_empty() => {
+                extra.help = "replace it and spell out the error type of the example";
+                extra.replacements.push(replacement);
+            },
+            Some(extra) => extra.replacements.push(replacement),
+            None => {
+                sugg.extra = Some(ExtraEdits {
+                    help: "replace it and spell out the error type of the example",
+                    replacements: vec![replacement],
+                    notes: Vec::new(),
+                    alternatives: Vec::new(),
+                    body_rewrite: None,
+                });
+            },
+        }
+    }
+}
+
+/// Splits a suggested `let` statement after its `=` if it's wider than `max_width` when placed at
+/// `indent`, the way rustfmt would format it.
+fn wrap_let_sugg(sugg: &str, indent: usize, max_width: u64) -> Option<String> {
+    if !sugg.starts_with("let ") || sugg.contains('\n') || (indent + sugg.chars().count()) as u64 <= max_width {
+        return None;
+    }
+    // The `=` of the statement is the first one outside of the brackets of the pattern and type
+    let mut depth = 0i32;
+    for (i, c) in sugg.char_indices() {
+        match c {
+            '<' | '(' | '[' | '{' => depth += 1,
+            '>' if !sugg[..i].ends_with('-') => depth -= 1,
+            ')' | ']' | '}' => depth -= 1,
+            '=' if depth == 0 && sugg[..i].ends_with(' ') && sugg[i + 1..].starts_with(' ') => {
+                let indent = " ".repeat(indent + 4);
+                return Some(format!("{} =\n{indent}{}", &sugg[..i - 1], &sugg[i + 2..]));
+            },
+            _ => {},
+        }
+    }
+    None
+}
+
+/// Checks if `QUESTION_MARK`, `QUESTION_MARK_MATCH` and `MANUAL_LET_ELSE` are all allowed on the
+/// owner of the body, and can't be enabled again inside of it. Rather than looking for lint
+/// attributes in particular, any attribute on something within the owner counts as a possible
+/// override.
+fn is_allowed_body(cx: &LateContext<'_>, body: &Body<'_>) -> bool {
+    let owner = cx.tcx.hir().body_owner_def_id(body.id());
+    let hir_id = cx.tcx.local_def_id_to_hir_id(owner);
+    is_lint_allowed(cx, QUESTION_MARK, hir_id)
+        && is_lint_allowed(cx, QUESTION_MARK_MATCH, hir_id)
+        && is_lint_allowed(cx, MANUAL_LET_ELSE, hir_id)
+        && cx
+            .tcx
+            .hir_attrs(hir_id.owner)
+            .map
+            .iter()
+            .all(|(local_id, _)| *local_id == ItemLocalId::ZERO)
+}
+
+/// Lints `let x = e?;` statements which are directly followed by a `None`/`Err` check of `x`.
+fn check_rechecked_question_mark<'tcx>(cx: &LateContext<'tcx>, block: &'tcx Block<'tcx>) {
+    let nexts = block
+        .stmts
+        .iter()
+        .skip(1)
+        .map(|stmt| match stmt.kind {
+            StmtKind::Expr(e) | StmtKind::Semi(e) => Some(e),
+            StmtKind::Let(_) | StmtKind::Item(_) => None,
+        })
+        .chain([block.expr]);
+    for (stmt, next) in block.stmts.iter().zip(nexts) {
+        if let StmtKind::Let(LetStmt {
+            pat,
+            init: Some(init),
+            els: None,
+            ..
+        }) = stmt.kind
+            && let PatKind::Binding(_, binding, ..) = pat.kind
+            && let ExprKind::Match(_, _, MatchSource::TryDesugar(_)) = init.kind
+            && !stmt.span.from_expansion()
+            && let Some(higher::If { cond, .. }) = next.and_then(higher::If::hir)
+            && let Some((caller, call_sym)) = is_none_or_err_cond(cx, cond)
+            && path_to_local_id(caller, binding)
+            && let caller_ty = typeck_results(cx).expr_ty(caller)
+            && match call_sym.as_str() {
+                "is_none" => is_type_diagnostic_item(cx, caller_ty, sym::Option),
+                "is_err" => is_type_diagnostic_item(cx, caller_ty, sym::Result),
+                _ => false,
+            }
+        {
+            span_lint_hir_and_then(
+                cx,
+                QUESTION_MARK_RECHECKED,
+                cond.hir_id,
+                cond.span,
+                "the result of the `?` operator is checked again right away",
+                |diag| {
+                    diag.span_note(init.span, "the `?` operator is applied here");
+                    diag.help("check whether the nested value or the unwrapped one should be checked");
+                },
+            );
+        }
     }
 }
 
-fn is_try_block(cx: &LateContext<'_>, bl: &Block<'_>) -> bool {
-    if let Some(expr) = bl.expr
-        && let ExprKind::Call(callee, [_]) = expr.kind
+/// Checks if `body` is generated code, i.e. it comes from an external macro, a derive, or a file
+/// which was `include!`d into a module (as done for e.g. protobuf or bindgen output).
+fn is_generated_body(cx: &LateContext<'_>, body: &Body<'_>) -> bool {
+    let span = body.value.span;
+    let owner = cx.tcx.hir().body_owner(body.id());
+    if in_external_macro(cx.sess(), span) || in_automatically_derived(cx.tcx, owner) {
+        return true;
+    }
+    // `include!`d code isn't part of a macro expansion, but it lives in a different file than the
+    // module containing it
+    let (_, module_span, _) = cx.tcx.hir().get_module(cx.tcx.parent_module(owner));
+    let source_map = cx.sess().source_map();
+    !span.from_expansion()
+        && !module_span.from_expansion()
+        && source_map.lookup_source_file_idx(span.lo()) != source_map.lookup_source_file_idx(module_span.lo())
+}
+
+/// Checks if `hir_id` is in the body of a `const fn` or another const context. Unlike
+/// `is_in_const_context`, this looks at the innermost body containing `hir_id` rather than the
+/// body the lint pass is in, so that neither an inner `fn` item nor a closure of a `const fn` is
+/// mistaken for being const.
+fn is_in_const_body(cx: &LateContext<'_>, hir_id: HirId) -> bool {
+    let hir = cx.tcx.hir();
+    is_const_body(cx.tcx, hir.body_owned_by(hir.enclosing_body_owner(hir_id)).id())
+}
+
+/// Classifies `expr` the way the checks of `if` and `if let` expressions do, returning the
+/// binding mode of the pattern of an `if let`, or the reason it can't be classified.
+#[cfg(feature = "internal")]
+fn checked_type<'tcx>(
+    cx: &LateContext<'tcx>,
+    expr: &'tcx Expr<'tcx>,
+) -> Result<(IfBlockType<'tcx>, Option<ByRef>), &'static str> {
+    if let Some(higher::IfLet {
+        let_pat,
+        let_expr,
+        if_then,
+        if_else,
+        ..
+    }) = higher::IfLet::hir(cx, expr)
     {
-        is_path_lang_item(cx, callee, LangItem::TryTraitFromOutput)
+        if let PatKind::TupleStruct(ref qpath, [field], ddpos) = let_pat.kind
+            && ddpos.as_opt_usize().is_none()
+            && let PatKind::Binding(BindingMode(by_ref, _), _, ident, None) = field.kind
+        {
+            let if_block = IfBlockType::IfLet(
+                cx.qpath_res(qpath, let_pat.hir_id),
+                peel_receiver_ref(typeck_results(cx).expr_ty(let_expr)),
+                ident.name,
+                let_expr,
+                if_then,
+                if_else,
+            );
+            Ok((if_block, Some(by_ref)))
+        } else {
+            Err("the pattern isn't a variant with a single binding")
+        }
+    } else if let Some(higher::If { cond, then, .. }) = higher::If::hir(expr) {
+        let (caller, call_sym) =
+            is_none_or_err_cond(cx, cond).ok_or("the condition doesn't check for `None` or `Err`")?;
+        let caller_ty = peel_receiver_ref(typeck_results(cx).expr_ty(caller));
+        Ok((IfBlockType::IfIs(caller, caller_ty, call_sym, then), None))
     } else {
-        false
+        Err("it isn't an `if` or `if let` expression")
+    }
+}
+
+#[cfg(feature = "internal")]
+impl QuestionMark {
+    /// Describes how `QUESTION_MARK` handles `node`, an expression or a statement, for the
+    /// internal `QUESTION_MARK_DUMP` lint.
+    pub(crate) fn dump_notes<'tcx>(&self, cx: &LateContext<'tcx>, node: Node<'tcx>) -> Vec<String> {
+        let mut notes = Vec::new();
+        let (hir_id, span) = match node {
+            Node::Expr(expr) => {
+                match checked_type(cx, expr) {
+                    Ok((if_block, binding)) => {
+                        let (shape, scrutinee, scrutinee_ty) = match if_block {
+                            IfBlockType::IfIs(caller, caller_ty, call_sym, _) => {
+                                (format!("`IfIs` calling `{call_sym}`"), caller, caller_ty)
+                            },
+                            IfBlockType::IfLet(res, let_expr_ty, let_pat_sym, let_expr, ..) => {
+                                let variant = res
+                                    .opt_def_id()
+                                    .map_or_else(|| "_".to_owned(), |id| cx.tcx.item_name(id).to_string());
+                                (
+                                    format!("`IfLet` matching `{variant}({let_pat_sym})`"),
+                                    let_expr,
+                                    let_expr_ty,
+                                )
+                            },
+                        };
+                        notes.push(format!("shape: {shape}"));
+                        notes.push(format!(
+                            "scrutinee: `{}` of type `{scrutinee_ty}`",
+                            scrutinee.span.get_source_text(cx).as_deref().unwrap_or("..")
+                        ));
+                        let mut applicability = Applicability::MachineApplicable;
+                        let method_call_str = receiver_method_call(cx, expr, scrutinee, binding, &mut applicability);
+                        if method_call_str.is_empty() {
+                            notes.push("by_ref: moved".to_owned());
+                        } else {
+                            notes.push(format!("by_ref: borrowed with `{method_call_str}`"));
+                        }
+                        notes.push(format!(
+                            "returns early: `Option` {}, `Result` {}",
+                            is_early_return(sym::Option, cx, &if_block),
+                            is_early_return(sym::Result, cx, &if_block),
+                        ));
+                    },
+                    Err(reason) => notes.push(format!("shape: none, {reason}")),
+                }
+                notes.push(format!("else clause: {}", is_else_clause(cx.tcx, expr)));
+                (expr.hir_id, expr.span)
+            },
+            Node::Stmt(stmt) => {
+                if let StmtKind::Let(LetStmt { els: Some(_), .. }) = stmt.kind {
+                    notes.push("shape: `let...else`".to_owned());
+                } else {
+                    notes.push("shape: none, it isn't a `let...else` statement".to_owned());
+                }
+                (stmt.hir_id, stmt.span)
+            },
+            _ => return notes,
+        };
+        notes.push(format!(
+            "configured out code: {}",
+            span_contains_cfg_or_cfg_attr(cx, span)
+        ));
+        notes.push(format!(
+            "comments: {}",
+            span_contains_comment(cx.sess().source_map(), span)
+        ));
+        notes.push(format!("const context: {}", is_in_const_body(cx, hir_id)));
+        notes.push(format!("inside `try` block: {}", self.inside_try_block()));
+        notes.push(format!(
+            "msrv: `let...else` {}, `Option::transpose` {}",
+            self.msrv.meets(msrvs::LET_ELSE),
+            self.msrv.meets(msrvs::OPTION_TRANSPOSE),
+        ));
+        match self.skip_reason(cx, node) {
+            Some(reason) => notes.push(format!("skipped: {}", reason.description())),
+            None => notes.push("skipped: no".to_owned()),
+        }
+        notes.push(format!(
+            "`question_mark_used` applies: {}",
+            self.question_mark_used_applies(cx, hir_id)
+        ));
+        match self.find_sugg(cx, node) {
+            Ok(sugg) => notes.push(format!("suggestion: `{}`", sugg.sugg)),
+            Err(Declined::Msrv(feature, _)) => {
+                notes.push(format!("suggestion: none, requires {}", feature.description()));
+            },
+            Err(Declined::Structural) => notes.push("suggestion: none".to_owned()),
+        }
+        notes
     }
 }
 
 impl<'tcx> LateLintPass<'tcx> for QuestionMark {
     fn check_stmt(&mut self, cx: &LateContext<'tcx>, stmt: &'tcx Stmt<'_>) {
-        if !is_lint_allowed(cx, QUESTION_MARK_USED, stmt.hir_id) {
+        if self.inside_generated_body() || self.inside_allowed_body() {
             return;
         }
 
-        if !self.inside_try_block() && !is_in_const_context(cx) {
-            check_let_some_else_return_none(cx, stmt);
+        self.check_node(cx, Node::Stmt(stmt));
+        if let StmtKind::Expr(expr) | StmtKind::Semi(expr) = stmt.kind
+            && is_wild_if_let_stmt(cx, expr)
+        {
+            self.check_node(cx, Node::Expr(expr));
         }
         self.check_manual_let_else(cx, stmt);
     }
-    fn check_expr(&mut self, cx: &LateContext<'tcx>, expr: &'tcx Expr<'_>) {
-        if !self.inside_try_block() && !is_in_const_context(cx) && is_lint_allowed(cx, QUESTION_MARK_USED, expr.hir_id)
-        {
-            check_is_none_or_err_and_early_return(cx, expr);
-            check_if_let_some_or_err_and_early_return(cx, expr);
+    fn check_expr(&mut self, cx: &LateContext<'tcx>, expr: &'tcx Expr<'tcx>) {
+        // Already checked along with the statement
+        if is_wild_if_let_stmt(cx, expr) {
+            return;
+        }
+        self.check_node(cx, Node::Expr(expr));
+    }
+
+    fn check_crate_post(&mut self, cx: &LateContext<'tcx>) {
+        let total: usize = self.candidate_counts.iter().sum();
+        if total == 0 {
+            return;
         }
+        span_lint_hir_and_then(
+            cx,
+            QUESTION_MARK_CANDIDATES_SUMMARY,
+            CRATE_HIR_ID,
+            cx.tcx.def_span(CRATE_DEF_ID),
+            format!("this crate contains {total} expressions which may be rewritten with the `?` operator"),
+            |diag| {
+                for kind in QuestionMarkKind::ALL {
+                    let count = self.candidate_counts[kind as usize];
+                    if count > 0 {
+                        diag.note(format!("{count} {}", kind.description()));
+                    }
+                }
+            },
+        );
     }
 
     fn check_block(&mut self, cx: &LateContext<'tcx>, block: &'tcx Block<'tcx>) {
-        if is_try_block(cx, block) {
-            *self
-                .try_block_depth_stack
-                .last_mut()
-                .expect("blocks are always part of bodies and must have a depth") += 1;
+        self.enter_block(cx, block);
+        if !self.inside_generated_body() {
+            check_rechecked_question_mark(cx, block);
         }
     }
 
-    fn check_body(&mut self, _: &LateContext<'tcx>, _: &Body<'tcx>) {
-        self.try_block_depth_stack.push(0);
+    fn check_body(&mut self, cx: &LateContext<'tcx>, body: &Body<'tcx>) {
+        self.enter_body(cx, body);
+        if self.group_per_fn {
+            self.grouped_suggs_stack.push(Vec::new());
+        }
     }
 
-    fn check_body_post(&mut self, _: &LateContext<'tcx>, _: &Body<'tcx>) {
-        self.try_block_depth_stack.pop();
+    fn check_body_post(&mut self, cx: &LateContext<'tcx>, body: &Body<'tcx>) {
+        self.exit_body();
+        if self.group_per_fn
+            && let Some(mut suggs) = self.grouped_suggs_stack.pop()
+        {
+            if suggs.len() > 1 {
+                emit_grouped(cx, body, suggs, self.machine_applicable_only);
+            } else if let Some(sugg) = suggs.pop() {
+                sugg.emit(cx, self.machine_applicable_only);
+            }
+        }
     }
 
     fn check_block_post(&mut self, cx: &LateContext<'tcx>, block: &'tcx Block<'tcx>) {
-        if is_try_block(cx, block) {
-            *self
-                .try_block_depth_stack
-                .last_mut()
-                .expect("blocks are always part of bodies and must have a depth") -= 1;
+        // Only now the `#[clippy::msrv]` attributes of the statements are known
+        if !self.inside_generated_body() && !self.inside_allowed_body() {
+            self.check_deferred_init(cx, block);
         }
+        self.exit_block(cx, block);
+    }
+
+    fn check_attributes(&mut self, cx: &LateContext<'tcx>, attrs: &'tcx [Attribute]) {
+        let span = cx.tcx.hir().span(cx.last_node_with_lint_attrs);
+        self.msrv.check_attributes_at(cx.sess(), attrs, span);
+    }
+
+    fn check_attributes_post(&mut self, cx: &LateContext<'tcx>, attrs: &'tcx [Attribute]) {
+        self.msrv.check_attributes_post_at(cx.sess(), attrs);
     }
-    extract_msrv_attr!(LateContext);
 }
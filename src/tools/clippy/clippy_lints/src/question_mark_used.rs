@@ -1,9 +1,19 @@
+use crate::question_mark::core_path;
+use clippy_config::Conf;
 use clippy_utils::diagnostics::span_lint_and_then;
+use clippy_utils::{TryBlockTracker, def_path_def_ids, is_in_test};
 
 use clippy_utils::macros::span_is_local;
-use rustc_hir::{Expr, ExprKind, MatchSource};
+use clippy_utils::source::{indent_of, snippet_with_context};
+use clippy_utils::ty::is_type_diagnostic_item;
+use rustc_errors::Applicability;
+use rustc_hir::def::DefKind;
+use rustc_hir::def_id::DefIdSet;
+use rustc_hir::{Arm, Block, Body, CoroutineKind, CoroutineSource, Expr, ExprKind, FnRetTy, HirId, MatchSource};
 use rustc_lint::{LateContext, LateLintPass};
-use rustc_session::declare_lint_pass;
+use rustc_middle::ty::{self, Ty, TyCtxt};
+use rustc_session::impl_lint_pass;
+use rustc_span::{Span, sym};
 
 declare_clippy_lint! {
     /// ### What it does
@@ -24,31 +34,251 @@
     /// ```ignore
     /// utility_macro!(expr);
     /// ```
+    ///
+    /// ### Configuration
+    /// Implementations of the traits listed in `question-mark-used-allowed-traits`, such as
+    /// `core::fmt::Display`, may use the question mark operator.
+    ///
+    /// With `question-mark-used-in-tests-suggest-expect`, uses in test code are rewritten to
+    /// `.expect()` or `.unwrap()`, which panic at the failure location.
+    ///
+    /// With `question-mark-used-allow-in-try-blocks`, uses inside of `try` blocks are allowed.
     #[clippy::version = "1.69.0"]
     pub QUESTION_MARK_USED,
     restriction,
     "complains if the question mark operator is used"
 }
 
-declare_lint_pass!(QuestionMarkUsed => [QUESTION_MARK_USED]);
+pub struct QuestionMarkUsed {
+    allowed_traits: DefIdSet,
+    in_tests_suggest_expect: bool,
+    allow_in_try_blocks: bool,
+    try_blocks: TryBlockTracker,
+}
+
+impl QuestionMarkUsed {
+    pub fn new(tcx: TyCtxt<'_>, conf: &'static Conf) -> Self {
+        Self {
+            allowed_traits: allowed_traits(tcx, conf),
+            in_tests_suggest_expect: conf.question_mark_used_in_tests_suggest_expect,
+            allow_in_try_blocks: conf.question_mark_used_allow_in_try_blocks,
+            try_blocks: TryBlockTracker::default(),
+        }
+    }
+}
+
+impl_lint_pass!(QuestionMarkUsed => [QUESTION_MARK_USED]);
+
+/// Resolves the traits of `question-mark-used-allowed-traits`.
+pub(crate) fn allowed_traits(tcx: TyCtxt<'_>, conf: &'static Conf) -> DefIdSet {
+    conf.question_mark_used_allowed_traits
+        .iter()
+        .flat_map(|p| def_path_def_ids(tcx, &p.split("::").collect::<Vec<_>>()))
+        .collect()
+}
+
+/// Checks if `hir_id` is part of a method implementing one of the `allowed_traits`, where the
+/// question mark operator may be used.
+pub(crate) fn is_in_allowed_trait_impl(cx: &LateContext<'_>, hir_id: HirId, allowed_traits: &DefIdSet) -> bool {
+    if allowed_traits.is_empty() {
+        return false;
+    }
+    let owner = cx.tcx.hir().get_parent_item(hir_id);
+    cx.tcx
+        .impl_of_method(owner.to_def_id())
+        .and_then(|impl_id| cx.tcx.trait_id_of_impl(impl_id))
+        .is_some_and(|trait_id| allowed_traits.contains(&trait_id))
+}
+
+/// Describes what the `?` operator `expr` returns from, along with the span of its return type if
+/// it's written out.
+fn returning_body(cx: &LateContext<'_>, expr: &Expr<'_>, arms: &[Arm<'_>]) -> (String, Option<Span>) {
+    // Inside of `try` blocks, the residual is passed to the end of the block rather than returned
+    if let [_, residual_arm] = arms
+        && let ExprKind::Break(..) = residual_arm.body.kind
+    {
+        return ("the `try` block".to_owned(), None);
+    }
+    let mut def_id = cx.tcx.hir().enclosing_body_owner(expr.hir_id);
+    // The body of an `async fn` is an `async` block
+    if let Some(CoroutineKind::Desugared(_, CoroutineSource::Fn)) = cx.tcx.coroutine_kind(def_id) {
+        def_id = cx.tcx.local_parent(def_id);
+    }
+    let ret_span = cx
+        .tcx
+        .hir_node_by_def_id(def_id)
+        .fn_decl()
+        .and_then(|decl| match decl.output {
+            FnRetTy::Return(ty) => Some(ty.span),
+            FnRetTy::DefaultReturn(_) => None,
+        });
+    let desc = match cx.tcx.def_kind(def_id) {
+        DefKind::Fn | DefKind::AssocFn => format!("`fn {}`", cx.tcx.item_name(def_id.to_def_id())),
+        DefKind::Closure if cx.tcx.coroutine_kind(def_id).is_some() => "the `async` block".to_owned(),
+        _ => "the closure".to_owned(),
+    };
+    (desc, ret_span)
+}
+
+/// In test code, returns `receiver.expect("<test name>")` for `Result`s and `receiver.unwrap()`
+/// for `Option`s, to replace `receiver?` with. Tests returning a `Result` themselves are skipped,
+/// as they use the `?` operator to report failures.
+fn test_expect_sugg<'tcx>(
+    cx: &LateContext<'tcx>,
+    expr: &Expr<'_>,
+    receiver: &Expr<'_>,
+    receiver_ty: Ty<'tcx>,
+) -> Option<String> {
+    if !is_in_test(cx.tcx, expr.hir_id) {
+        return None;
+    }
+    let owner = cx.tcx.hir().get_parent_item(expr.hir_id);
+    if !matches!(cx.tcx.def_kind(owner), DefKind::Fn | DefKind::AssocFn) {
+        return None;
+    }
+    let output = cx.tcx.fn_sig(owner).instantiate_identity().skip_binder().output();
+    if is_type_diagnostic_item(cx, output, sym::Result) {
+        return None;
+    }
+    let mut app = Applicability::MaybeIncorrect;
+    let (receiver_str, _) = snippet_with_context(cx, receiver.span, expr.span.ctxt(), "..", &mut app);
+    if is_type_diagnostic_item(cx, receiver_ty, sym::Result) {
+        Some(format!(
+            "{receiver_str}.expect(\"{}\")",
+            cx.tcx.item_name(owner.to_def_id())
+        ))
+    } else if is_type_diagnostic_item(cx, receiver_ty, sym::Option) {
+        Some(format!("{receiver_str}.unwrap()"))
+    } else {
+        None
+    }
+}
+
+/// Spells out the `match` that `receiver?` desugars to, for `Option` and `Result` receivers
+/// returned from a body of the same type. Paths go through `core` in `#![no_std]` crates.
+fn expansion_sugg<'tcx>(
+    cx: &LateContext<'tcx>,
+    expr: &Expr<'_>,
+    receiver: &Expr<'_>,
+    receiver_ty: Ty<'tcx>,
+    arms: &[Arm<'_>],
+) -> Option<String> {
+    // Inside of `try` blocks, the residual arm breaks out of the block instead
+    let [_, residual_arm] = arms else { return None };
+    let ExprKind::Ret(Some(ret)) = residual_arm.body.kind else {
+        return None;
+    };
+    let ret_ty = cx.typeck_results().expr_ty(ret);
+    let path = |path: &str| core_path(cx, expr.hir_id, path);
+    let (ok, residual) = if is_type_diagnostic_item(cx, receiver_ty, sym::Result)
+        && is_type_diagnostic_item(cx, ret_ty, sym::Result)
+    {
+        let err = path("result::Result::Err")?;
+        (
+            format!("{}(val)", path("result::Result::Ok")?),
+            format!("{err}(err) => return {err}({}(err))", path("convert::From::from")?),
+        )
+    } else if is_type_diagnostic_item(cx, receiver_ty, sym::Option) && is_type_diagnostic_item(cx, ret_ty, sym::Option)
+    {
+        let none = path("option::Option::None")?;
+        (
+            format!("{}(val)", path("option::Option::Some")?),
+            format!("{none} => return {none}"),
+        )
+    } else {
+        return None;
+    };
+    let mut app = Applicability::MaybeIncorrect;
+    let (receiver_str, _) = snippet_with_context(cx, receiver.span, expr.span.ctxt(), "..", &mut app);
+    let indent = " ".repeat(indent_of(cx, expr.span).unwrap_or(0));
+    Some(format!(
+        "match {receiver_str} {{\n{indent}    {ok} => val,\n{indent}    {residual},\n{indent}}}"
+    ))
+}
 
 impl<'tcx> LateLintPass<'tcx> for QuestionMarkUsed {
     fn check_expr(&mut self, cx: &LateContext<'tcx>, expr: &'tcx Expr<'_>) {
-        if let ExprKind::Match(_, _, MatchSource::TryDesugar(_)) = expr.kind {
-            if !span_is_local(expr.span) {
+        if let ExprKind::Match(scrutinee, arms, MatchSource::TryDesugar(_)) = expr.kind {
+            if !span_is_local(expr.span)
+                || is_in_allowed_trait_impl(cx, expr.hir_id, &self.allowed_traits)
+                || (self.allow_in_try_blocks && self.try_blocks.inside_try_block())
+            {
                 return;
             }
 
-            #[expect(clippy::collapsible_span_lint_calls, reason = "rust-clippy#7797")]
+            // The scrutinee is `Try::branch(receiver)`
+            let typeck = cx.typeck_results();
+            let receiver = match scrutinee.kind {
+                ExprKind::Call(_, [receiver]) => receiver,
+                _ => scrutinee,
+            };
+            let receiver_ty = typeck.expr_ty(receiver);
+            let residual = match receiver_ty.kind() {
+                ty::Adt(_, args) if is_type_diagnostic_item(cx, receiver_ty, sym::Result) => {
+                    format!("`{}`", args.type_at(1))
+                },
+                _ if is_type_diagnostic_item(cx, receiver_ty, sym::Option) => "`None`".to_owned(),
+                _ => format!("the residual of `{receiver_ty}`"),
+            };
+            let (body_desc, ret_span) = returning_body(cx, expr, arms);
+            let expect_sugg = if self.in_tests_suggest_expect {
+                test_expect_sugg(cx, expr, receiver, receiver_ty)
+            } else {
+                None
+            };
+            let expansion = if expect_sugg.is_none() {
+                expansion_sugg(cx, expr, receiver, receiver_ty, arms)
+            } else {
+                None
+            };
+
             span_lint_and_then(
                 cx,
                 QUESTION_MARK_USED,
                 expr.span,
-                "question mark operator was used",
+                format!("the `?` operator here propagates {residual} out of {body_desc}"),
                 |diag| {
-                    diag.help("consider using a custom macro or match expression");
+                    if let Some(ret_span) = ret_span {
+                        diag.span_label(ret_span, "propagated into this return type");
+                    }
+                    if let Some(sugg) = expect_sugg {
+                        diag.span_suggestion(
+                            expr.span,
+                            "in tests, panic at the failure location instead",
+                            sugg,
+                            Applicability::MaybeIncorrect,
+                        );
+                    } else {
+                        diag.span_label(expr.span, format!("this evaluates to `{}`", typeck.expr_ty(expr)));
+                        if let Some(expansion) = expansion {
+                            diag.span_suggestion_verbose(
+                                expr.span,
+                                "consider using a custom macro or match expression, such as",
+                                expansion,
+                                Applicability::MaybeIncorrect,
+                            );
+                        } else {
+                            diag.help("consider using a custom macro or match expression");
+                        }
+                    }
                 },
             );
         }
     }
+
+    fn check_block(&mut self, cx: &LateContext<'tcx>, block: &'tcx Block<'tcx>) {
+        self.try_blocks.enter_block(cx, block);
+    }
+
+    fn check_block_post(&mut self, cx: &LateContext<'tcx>, block: &'tcx Block<'tcx>) {
+        self.try_blocks.exit_block(cx, block);
+    }
+
+    fn check_body(&mut self, _: &LateContext<'tcx>, _: &Body<'tcx>) {
+        self.try_blocks.enter_body();
+    }
+
+    fn check_body_post(&mut self, _: &LateContext<'tcx>, _: &Body<'tcx>) {
+        self.try_blocks.exit_body();
+    }
 }
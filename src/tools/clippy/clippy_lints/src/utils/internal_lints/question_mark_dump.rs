@@ -0,0 +1,106 @@
+use crate::question_mark::{QuestionMark, QuestionMarkClaims};
+use clippy_config::Conf;
+use clippy_utils::diagnostics::span_lint_hir_and_then;
+use clippy_utils::get_attr;
+use rustc_ast::Attribute;
+use rustc_hir::{Block, Body, Expr, HirId, Node, Stmt, StmtKind};
+use rustc_lint::{LateContext, LateLintPass, LintContext};
+use rustc_middle::ty::TyCtxt;
+use rustc_session::impl_lint_pass;
+
+declare_clippy_lint! {
+    /// ### What it does
+    /// Not an actual lint. It describes how `question_mark` classifies expressions and statements
+    /// marked with `#[clippy::question_mark_dump]`, and why it doesn't lint them.
+    ///
+    /// ### Why is this bad?
+    /// It's only meant for debugging `question_mark`.
+    ///
+    /// ### Example
+    /// ```rust,ignore
+    /// #[clippy::question_mark_dump]
+    /// if x.is_none() {
+    ///     return None;
+    /// }
+    /// ```
+    pub QUESTION_MARK_DUMP,
+    internal,
+    "helper to dump the `question_mark` classification of expressions"
+}
+
+/// Runs the checks of `question_mark` with the same state as its own pass, without linting.
+pub struct QuestionMarkDump {
+    question_mark: QuestionMark,
+}
+
+impl QuestionMarkDump {
+    pub fn new(tcx: TyCtxt<'_>, conf: &'static Conf) -> Self {
+        Self {
+            question_mark: QuestionMark::new(tcx, conf, QuestionMarkClaims::default()),
+        }
+    }
+
+    fn dump<'tcx>(&self, cx: &LateContext<'tcx>, hir_id: HirId, node: Node<'tcx>) {
+        span_lint_hir_and_then(
+            cx,
+            QUESTION_MARK_DUMP,
+            hir_id,
+            cx.tcx.hir().span(hir_id),
+            "`question_mark` classification",
+            |diag| {
+                for note in self.question_mark.dump_notes(cx, node) {
+                    diag.note(note);
+                }
+            },
+        );
+    }
+}
+
+impl_lint_pass!(QuestionMarkDump => [QUESTION_MARK_DUMP]);
+
+impl<'tcx> LateLintPass<'tcx> for QuestionMarkDump {
+    fn check_expr(&mut self, cx: &LateContext<'tcx>, expr: &'tcx Expr<'tcx>) {
+        if has_attr(cx, expr.hir_id) {
+            self.dump(cx, expr.hir_id, Node::Expr(expr));
+        }
+    }
+
+    fn check_stmt(&mut self, cx: &LateContext<'tcx>, stmt: &'tcx Stmt<'tcx>) {
+        // The attributes of expression statements are dumped with the expression
+        if let StmtKind::Let(_) = stmt.kind
+            && has_attr(cx, stmt.hir_id)
+        {
+            self.dump(cx, stmt.hir_id, Node::Stmt(stmt));
+        }
+    }
+
+    fn check_body(&mut self, cx: &LateContext<'tcx>, body: &Body<'tcx>) {
+        self.question_mark.enter_body(cx, body);
+    }
+
+    fn check_body_post(&mut self, _: &LateContext<'tcx>, _: &Body<'tcx>) {
+        self.question_mark.exit_body();
+    }
+
+    fn check_block(&mut self, cx: &LateContext<'tcx>, block: &'tcx Block<'tcx>) {
+        self.question_mark.enter_block(cx, block);
+    }
+
+    fn check_block_post(&mut self, cx: &LateContext<'tcx>, block: &'tcx Block<'tcx>) {
+        self.question_mark.exit_block(cx, block);
+    }
+
+    fn check_attributes(&mut self, cx: &LateContext<'tcx>, attrs: &'tcx [Attribute]) {
+        let span = cx.tcx.hir().span(cx.last_node_with_lint_attrs);
+        self.question_mark.msrv.check_attributes_at(cx.sess(), attrs, span);
+    }
+
+    fn check_attributes_post(&mut self, cx: &LateContext<'tcx>, attrs: &'tcx [Attribute]) {
+        self.question_mark.msrv.check_attributes_post_at(cx.sess(), attrs);
+    }
+}
+
+fn has_attr(cx: &LateContext<'_>, hir_id: HirId) -> bool {
+    let attrs = cx.tcx.hir().attrs(hir_id);
+    get_attr(cx.sess(), attrs, "question_mark_dump").count() > 0
+}
@@ -28,6 +28,7 @@ pub enum DeprecationStatus {
     ("dump",                  DeprecationStatus::None),
     ("msrv",                  DeprecationStatus::None),
     ("has_significant_drop",  DeprecationStatus::None),
+    ("question_mark_dump",    DeprecationStatus::None),
 ];
 
 pub struct LimitStack {
@@ -179,10 +180,19 @@ pub fn has_non_exhaustive_attr(tcx: TyCtxt<'_>, adt: AdtDef<'_>) -> bool {
 
 /// Checks if the given span contains a `#[cfg(..)]` attribute
 pub fn span_contains_cfg(cx: &LateContext<'_>, s: Span) -> bool {
+    span_contains_attr_named(cx, s, &["cfg"])
+}
+
+/// Checks if the given span contains a `#[cfg(..)]` or `#[cfg_attr(..)]` attribute
+pub fn span_contains_cfg_or_cfg_attr(cx: &LateContext<'_>, s: Span) -> bool {
+    span_contains_attr_named(cx, s, &["cfg", "cfg_attr"])
+}
+
+fn span_contains_attr_named(cx: &LateContext<'_>, s: Span, names: &[&str]) -> bool {
     s.check_source_text(cx, |src| {
         let mut iter = tokenize_with_text(src);
 
-        // Search for the token sequence [`#`, `[`, `cfg`]
+        // Search for the token sequence [`#`, `[`, `<name>`]
         while iter.any(|(t, ..)| matches!(t, TokenKind::Pound)) {
             let mut iter = iter.by_ref().skip_while(|(t, ..)| {
                 matches!(
@@ -191,7 +201,7 @@ pub fn span_contains_cfg(cx: &LateContext<'_>, s: Span) -> bool {
                 )
             });
             if matches!(iter.next(), Some((TokenKind::OpenBracket, ..)))
-                && matches!(iter.next(), Some((TokenKind::Ident, "cfg", _)))
+                && matches!(iter.next(), Some((TokenKind::Ident, name, _)) if names.contains(&name))
             {
                 return true;
             }
@@ -17,19 +17,38 @@
 use std::env;
 
 fn docs_link(diag: &mut Diag<'_, ()>, lint: &'static Lint) {
-    if env::var("CLIPPY_DISABLE_DOCS_LINKS").is_err() {
-        if let Some(lint) = lint.name_lower().strip_prefix("clippy::") {
-            diag.help(format!(
-                "for further information visit https://rust-lang.github.io/rust-clippy/{}/index.html#{lint}",
-                &option_env!("RUST_RELEASE_NUM").map_or("master".to_string(), |n| {
-                    // extract just major + minor version and ignore patch versions
-                    format!("rust-{}", n.rsplit_once('.').unwrap().1)
-                })
-            ));
-        }
+    if let Some(url) = docs_url(lint, None) {
+        diag.help(format!("for further information visit {url}"));
+    }
+}
+
+/// Adds a help message linking to a section of the documentation of `lint`, for lints which cover
+/// several cases. The section has to be a heading with the anchor `{#<lint name>-<section>}`, and
+/// is described by `what`.
+///
+/// Like the link added to every lint, this is left out if `CLIPPY_DISABLE_DOCS_LINKS` is set.
+pub fn docs_section_link(diag: &mut Diag<'_, ()>, lint: &'static Lint, section: &str, what: &str) {
+    if let Some(url) = docs_url(lint, Some(section)) {
+        diag.help(format!("for further information on {what} visit {url}"));
     }
 }
 
+fn docs_url(lint: &'static Lint, section: Option<&str>) -> Option<String> {
+    if env::var("CLIPPY_DISABLE_DOCS_LINKS").is_ok() {
+        return None;
+    }
+    let name = lint.name_lower();
+    let lint = name.strip_prefix("clippy::")?;
+    let version = option_env!("RUST_RELEASE_NUM").map_or("master".to_string(), |n| {
+        // extract just major + minor version and ignore patch versions
+        format!("rust-{}", n.rsplit_once('.').unwrap().1)
+    });
+    let anchor = section.map_or_else(|| lint.to_owned(), |section| format!("{lint}-{section}"));
+    Some(format!(
+        "https://rust-lang.github.io/rust-clippy/{version}/index.html#{anchor}"
+    ))
+}
+
 /// Makes sure that a diagnostic is well formed.
 ///
 /// rustc debug asserts a few properties about spans,
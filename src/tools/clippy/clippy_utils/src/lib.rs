@@ -102,11 +102,11 @@
 use rustc_hir::hir_id::{HirIdMap, HirIdSet};
 use rustc_hir::intravisit::{FnKind, Visitor, walk_expr};
 use rustc_hir::{
-    self as hir, Arm, ArrayLen, BindingMode, Block, BlockCheckMode, Body, ByRef, Closure, ConstArgKind, ConstContext,
-    Destination, Expr, ExprField, ExprKind, FnDecl, FnRetTy, GenericArgs, HirId, Impl, ImplItem, ImplItemKind,
-    ImplItemRef, Item, ItemKind, LangItem, LetStmt, MatchSource, Mutability, Node, OwnerId, OwnerNode, Param, Pat,
-    PatKind, Path, PathSegment, PrimTy, QPath, Stmt, StmtKind, TraitItem, TraitItemKind, TraitItemRef, TraitRef,
-    TyKind, UnOp, def,
+    self as hir, Arm, ArrayLen, BindingMode, Block, BlockCheckMode, Body, BodyId, ByRef, Closure, ConstArgKind,
+    ConstContext, Destination, Expr, ExprField, ExprKind, FnDecl, FnRetTy, GenericArgs, HirId, Impl, ImplItem,
+    ImplItemKind, ImplItemRef, Item, ItemKind, LangItem, LetStmt, MatchSource, Mutability, Node, OwnerId, OwnerNode,
+    Param, Pat, PatKind, Path, PathSegment, PrimTy, QPath, Stmt, StmtKind, TraitItem, TraitItemKind, TraitItemRef,
+    TraitRef, TyKind, UnOp, def,
 };
 use rustc_lexer::{TokenKind, tokenize};
 use rustc_lint::{LateContext, Level, Lint, LintContext};
@@ -224,12 +224,16 @@ pub fn local_is_initialized(cx: &LateContext<'_>, local: HirId) -> bool {
 /// like `check_path` or `check_ty` may or may not have one.
 pub fn is_in_const_context(cx: &LateContext<'_>) -> bool {
     debug_assert!(cx.enclosing_body.is_some(), "`LateContext` has no enclosing body");
-    cx.enclosing_body.is_some_and(|id| {
-        cx.tcx
-            .hir()
-            .body_const_context(cx.tcx.hir().body_owner_def_id(id))
-            .is_some()
-    })
+    cx.enclosing_body.is_some_and(|id| is_const_body(cx.tcx, id))
+}
+
+/// Checks if `body` is required to be const (e.g. the body of a `const fn`, or a `static`/`const`
+/// initializer). Like [`is_in_const_context`], but for any body rather than the current one.
+///
+/// Only the owner of `body` matters: closures and nested items defined in a const context aren't
+/// const themselves, unless they're declared so (e.g. `const` closures).
+pub fn is_const_body(tcx: TyCtxt<'_>, body: BodyId) -> bool {
+    tcx.hir().body_const_context(tcx.hir().body_owner_def_id(body)).is_some()
 }
 
 /// Returns `true` if the given `HirId` is inside an always constant context.
@@ -485,6 +489,65 @@ pub fn is_path_diagnostic_item<'tcx>(
     path_def_id(cx, maybe_path).map_or(false, |id| cx.tcx.is_diagnostic_item(diag_item, id))
 }
 
+/// Checks if `block` is the block of a `try` block expression.
+pub fn is_try_block(cx: &LateContext<'_>, block: &Block<'_>) -> bool {
+    if let Some(expr) = block.expr
+        && let ExprKind::Call(callee, [_]) = expr.kind
+    {
+        is_path_lang_item(cx, callee, LangItem::TryTraitFromOutput)
+    } else {
+        false
+    }
+}
+
+/// Keeps track of the `try` blocks a late lint pass is in, so that it can quickly tell whether
+/// the residual of a `?` is captured by one, without walking up the parents of each expression.
+///
+/// Each body (including closures and `async` blocks) starts at a depth of zero, as `?` inside of
+/// them propagates out of the body rather than to a `try` block around it. The pass has to call
+/// all of the `enter_*` and `exit_*` methods from the corresponding `check_*` methods.
+#[derive(Default)]
+pub struct TryBlockTracker {
+    depth_stack: Vec<u32>,
+}
+
+impl TryBlockTracker {
+    /// To be called from `check_body`.
+    pub fn enter_body(&mut self) {
+        self.depth_stack.push(0);
+    }
+
+    /// To be called from `check_body_post`.
+    pub fn exit_body(&mut self) {
+        self.depth_stack.pop();
+    }
+
+    /// To be called from `check_block`.
+    pub fn enter_block(&mut self, cx: &LateContext<'_>, block: &Block<'_>) {
+        if is_try_block(cx, block) {
+            *self
+                .depth_stack
+                .last_mut()
+                .expect("blocks are always part of bodies and must have a depth") += 1;
+        }
+    }
+
+    /// To be called from `check_block_post`.
+    pub fn exit_block(&mut self, cx: &LateContext<'_>, block: &Block<'_>) {
+        if is_try_block(cx, block) {
+            *self
+                .depth_stack
+                .last_mut()
+                .expect("blocks are always part of bodies and must have a depth") -= 1;
+        }
+    }
+
+    /// Checks if the pass is currently inside of a `try` block of the current body.
+    pub fn inside_try_block(&self) -> bool {
+        self.depth_stack.last() > Some(&0)
+    }
+}
+
 /// THIS METHOD IS DEPRECATED. Matches a `Path` against a slice of segment string literals.
 ///
 /// This method is deprecated and will eventually be removed since it does not match against the
@@ -2550,6 +2613,30 @@ pub fn peel_ref_operators<'hir>(cx: &LateContext<'_>, mut expr: &'hir Expr<'hir>
     expr
 }
 
+/// Removes a single `.into()` or `Into::into(..)` call which converts a value to its own type, i.e.
+/// which resolves to the identity conversion `impl<T> From<T> for T`.
+pub fn peel_identity_into<'hir>(cx: &LateContext<'_>, expr: &'hir Expr<'hir>) -> &'hir Expr<'hir> {
+    let arg = match expr.kind {
+        ExprKind::MethodCall(segment, recv, [], _)
+            if segment.ident.name.as_str() == "into" && is_trait_method(cx, expr, sym::Into) =>
+        {
+            recv
+        },
+        ExprKind::Call(callee, [arg])
+            if path_def_id(cx, callee).is_some_and(|did| is_diag_trait_item(cx, did, sym::Into)) =>
+        {
+            arg
+        },
+        _ => return expr,
+    };
+    let typeck = cx.typeck_results();
+    if typeck.expr_ty(arg) == typeck.expr_ty(expr) {
+        arg
+    } else {
+        expr
+    }
+}
+
 pub fn is_hir_ty_cfg_dependant(cx: &LateContext<'_>, ty: &hir::Ty<'_>) -> bool {
     if let TyKind::Path(QPath::Resolved(_, path)) = ty.kind {
         if let Res::Def(_, def_id) = path.res {
@@ -3027,7 +3114,9 @@ pub fn pat_and_expr_can_be_question_mark<'a, 'hir>(
         && !is_refutable(cx, inner_pat)
         && let else_body = peel_blocks(else_body)
         && let ExprKind::Ret(Some(ret_val)) = else_body.kind
+        && let ret_val = peel_identity_into(cx, ret_val)
         && let ExprKind::Path(ret_path) = ret_val.kind
+        // Accepts any spelling resolving to the constructor, e.g. `Option::None` or `Self::None`
         && is_res_lang_ctor(cx, cx.qpath_res(&ret_path, ret_val.hir_id), OptionNone)
     {
         Some(inner_pat)
@@ -1,14 +1,17 @@
+use crate::ty::is_copy;
 use crate::visitors::{Descend, Visitable, for_each_expr, for_each_expr_without_closures};
 use crate::{self as utils, get_enclosing_loop_or_multi_call_closure};
 use core::ops::ControlFlow;
 use hir::def::Res;
+use rustc_hir::def_id::LocalDefId;
 use rustc_hir::intravisit::{self, Visitor};
-use rustc_hir::{self as hir, Expr, ExprKind, HirId, HirIdSet};
+use rustc_hir::{self as hir, Expr, ExprKind, HirId, HirIdSet, Mutability};
 use rustc_hir_typeck::expr_use_visitor::{Delegate, ExprUseVisitor, Place, PlaceBase, PlaceWithHirId};
 use rustc_lint::LateContext;
 use rustc_middle::hir::nested_filter;
 use rustc_middle::mir::FakeReadCause;
-use rustc_middle::ty;
+use rustc_middle::ty::adjustment::{Adjust, AutoBorrow, AutoBorrowMutability};
+use rustc_middle::ty::{self, BorrowKind, UpvarCapture};
 
 /// Returns a set of mutated local variable IDs, or `None` if mutations could not be determined.
 pub fn mutated_variables<'tcx>(expr: &'tcx Expr<'_>, cx: &LateContext<'tcx>) -> Option<HirIdSet> {
@@ -195,3 +198,101 @@ pub fn local_used_after_expr(cx: &LateContext<'_>, local_id: HirId, after: &Expr
     })
     .is_some()
 }
+
+/// How a local is used, see [`used_after_stmt`]. Ordered from the weakest to the strongest use.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum UsageKind {
+    /// The local isn't used at all.
+    Unused,
+    /// The local (or a field of it) is copied, or borrowed immutably.
+    Read,
+    /// The local (or a field of it) is assigned to, or borrowed mutably.
+    Written,
+    /// The local (or a field of it) is moved out of.
+    Moved,
+}
+
+/// Returns the strongest use of `local` in the statements of `block` following the one at
+/// `stmt_idx`, including the trailing expression of the block.
+///
+/// Capturing the local in a closure counts as using it the way it's captured, e.g. a `move`
+/// closure moves it, unless only `Copy` fields of it are captured. Uses which happen before the
+/// statement in an enclosing loop aren't considered, see [`local_used_after_expr`] for that.
+pub fn used_after_stmt<'tcx>(
+    cx: &LateContext<'tcx>,
+    local: HirId,
+    block: &'tcx hir::Block<'tcx>,
+    stmt_idx: usize,
+) -> UsageKind {
+    let mut usage = UsageKind::Unused;
+    let mut visit = |e: &'tcx Expr<'tcx>| {
+        let kind = if utils::path_to_local_id(e, local) {
+            local_usage_kind(cx, e)
+        } else if let ExprKind::Closure(closure) = e.kind {
+            closure_capture_kind(cx, closure.def_id, local)
+        } else {
+            return ControlFlow::Continue(());
+        };
+        usage = usage.max(kind);
+        if usage == UsageKind::Moved {
+            ControlFlow::Break(())
+        } else {
+            ControlFlow::Continue(())
+        }
+    };
+    let stmts = block.stmts.get(stmt_idx + 1..).unwrap_or_default();
+    if stmts.iter().any(|stmt| for_each_expr(cx, stmt, &mut visit).is_some()) {
+        return usage;
+    }
+    if let Some(expr) = block.expr {
+        for_each_expr(cx, expr, &mut visit);
+    }
+    usage
+}
+
+/// Determines how the closure `closure` uses `local` by capturing it or a field of it.
+fn closure_capture_kind(cx: &LateContext<'_>, closure: LocalDefId, local: HirId) -> UsageKind {
+    cx.typeck_results()
+        .closure_min_captures_flattened(closure)
+        .filter(|capture| matches!(capture.place.base, PlaceBase::Upvar(var) if var.var_path.hir_id == local))
+        .map(|capture| match capture.info.capture_kind {
+            UpvarCapture::ByValue if is_copy(cx, capture.place.ty()) => UsageKind::Read,
+            UpvarCapture::ByValue => UsageKind::Moved,
+            UpvarCapture::ByRef(BorrowKind::Immutable) => UsageKind::Read,
+            UpvarCapture::ByRef(BorrowKind::UniqueImmutable | BorrowKind::Mutable) => UsageKind::Written,
+        })
+        .max()
+        .unwrap_or(UsageKind::Unused)
+}
+
+/// Determines how the path expression `e` to a local uses it, looking through field accesses.
+fn local_usage_kind<'tcx>(cx: &LateContext<'tcx>, e: &'tcx Expr<'tcx>) -> UsageKind {
+    let typeck = cx.typeck_results();
+    let mut place = e;
+    while let Some(parent) = utils::get_parent_expr(cx, place)
+        && let ExprKind::Field(base, _) = parent.kind
+        && base.hir_id == place.hir_id
+    {
+        place = parent;
+    }
+    match utils::get_parent_expr(cx, place).map(|parent| parent.kind) {
+        Some(ExprKind::Assign(lhs, ..) | ExprKind::AssignOp(_, lhs, _)) if lhs.hir_id == place.hir_id => {
+            return UsageKind::Written;
+        },
+        Some(ExprKind::AddrOf(_, Mutability::Mut, _)) => return UsageKind::Written,
+        Some(ExprKind::AddrOf(_, Mutability::Not, _)) => return UsageKind::Read,
+        _ => {},
+    }
+    for adjust in typeck.expr_adjustments(place) {
+        match adjust.kind {
+            Adjust::Borrow(AutoBorrow::Ref(AutoBorrowMutability::Mut { .. })) => return UsageKind::Written,
+            Adjust::Borrow(_) | Adjust::Deref(_) => return UsageKind::Read,
+            _ => {},
+        }
+    }
+    if is_copy(cx, typeck.expr_ty(place)) {
+        UsageKind::Read
+    } else {
+        UsageKind::Moved
+    }
+}
@@ -520,6 +520,7 @@ struct LintMetadata {
     docs: String,
     version: &'static str,
     applicability: Applicability,
+    shapes: &'static [&'static str],
 }
 
 impl LintMetadata {
@@ -559,6 +560,7 @@ fn new(lint: &LintInfo, applicabilities: &HashMap<String, Applicability>, config
             docs,
             version: lint.version.unwrap(),
             applicability,
+            shapes: lint.shapes(),
         }
     }
 
@@ -581,9 +583,15 @@ fn new_deprecated(name: &str, reason: &str, version: &'static str) -> Self {
                 ### Deprecation reason\n\n{reason}.\n",
             ),
             applicability: Applicability::Unspecified,
+            shapes: &[],
         }
     }
 
+    /// The syntactic shapes recognized by the lint, as a JSON array for tools consuming the page
+    fn shapes_json(&self) -> String {
+        serde_json::to_string(self.shapes).unwrap()
+    }
+
     fn applicability_str(&self) -> &str {
         match self.applicability {
             Applicability::MachineApplicable => "MachineApplicable",
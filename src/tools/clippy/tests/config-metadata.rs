@@ -1,6 +1,7 @@
 #![feature(rustc_private)]
 
 use clippy_config::{ClippyConfiguration, get_configuration_metadata};
+use clippy_lints::QUESTION_MARK_CONF_OPTIONS;
 use itertools::Itertools;
 use regex::Regex;
 use std::borrow::Cow;
@@ -76,3 +77,24 @@ fn changelog() {
         }
     }
 }
+
+/// Every configuration option read when constructing the `QuestionMark` pass has to be registered
+/// as affecting one of its lints, otherwise it won't show up in `cargo clippy --explain`.
+#[test]
+fn question_mark_pass_options() {
+    let configs = get_configuration_metadata();
+    for &name in QUESTION_MARK_CONF_OPTIONS {
+        let config = configs
+            .iter()
+            .find(|config| config.name == name)
+            .unwrap_or_else(|| panic!("unknown configuration option `{name}`"));
+        assert!(
+            config
+                .lints
+                .iter()
+                .any(|&lint| matches!(lint, "question_mark" | "manual_let_else" | "question_mark_used")),
+            "`{name}` is read by the `QuestionMark` pass, but isn't registered as affecting any of its lints, \
+             add them to its `#[lints(..)]` attribute in `clippy_config/src/conf.rs`",
+        );
+    }
+}
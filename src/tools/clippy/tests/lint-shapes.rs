@@ -0,0 +1,20 @@
+#![feature(rustc_private)]
+
+use clippy_lints::declared_lints::LINTS;
+
+#[test]
+fn question_mark_shapes() {
+    let lint = LINTS.iter().find(|lint| lint.name_lower() == "question_mark").unwrap();
+    // Serialized the same way as in the lint metadata produced by `compile-test`
+    let shapes = serde_json::to_string(lint.shapes()).unwrap();
+    assert_eq!(shapes, r#"["if","if-let","let-else","match"]"#);
+}
+
+#[test]
+fn no_shapes_by_default() {
+    let lint = LINTS
+        .iter()
+        .find(|lint| lint.name_lower() == "manual_let_else")
+        .unwrap();
+    assert!(lint.shapes().is_empty());
+}
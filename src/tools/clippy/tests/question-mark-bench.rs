@@ -0,0 +1,215 @@
+//! Measures the cost of the `question_mark` pass on its own. Use `cargo dev bench-question-mark` to
+//! run it on larger inputs than the default.
+//!
+//! Every fixture in `tests/question_mark_bench` is included into many modules of a crate, which is
+//! then checked with nothing but the `question_mark` lints registered, once with the fast path of
+//! the pass skipping nodes which can't be rewritten and once running every check on every node.
+//! Each check runs in a child process, as the counters of the pass are global and the warnings
+//! are discarded.
+
+#![feature(rustc_private)]
+#![warn(rust_2018_idioms, unused_lifetimes)]
+
+#[cfg(feature = "internal")]
+extern crate rustc_driver;
+#[cfg(feature = "internal")]
+extern crate rustc_interface;
+
+use test_utils::IS_RUSTC_TEST_SUITE;
+
+mod test_utils;
+
+fn main() {
+    if IS_RUSTC_TEST_SUITE {
+        return;
+    }
+
+    // The counters of the pass only exist with internal lints
+    #[cfg(feature = "internal")]
+    bench::run();
+}
+
+#[cfg(feature = "internal")]
+mod bench {
+    use clippy_config::Conf;
+    use clippy_lints::question_mark_bench::{
+        CLASSIFY_NANOS, FAST_PATH, FAST_PATH_BAILS, NODES, SUGGESTIONS, TYPECK_RESULTS,
+    };
+    use rustc_interface::interface;
+    use std::env;
+    use std::fmt::Write as _;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+    use std::process::Command;
+    use std::sync::atomic::Ordering;
+    use std::time::{Duration, Instant};
+
+    /// Set in the child processes to the crate to check.
+    const CRATE_VAR: &str = "__QUESTION_MARK_BENCH_CRATE";
+    /// Set in the child processes to `1` if the fast path is enabled.
+    const FAST_PATH_VAR: &str = "__QUESTION_MARK_BENCH_FAST_PATH";
+    /// The number of modules including each fixture, defaults to `DEFAULT_COPIES`.
+    const COPIES_VAR: &str = "QUESTION_MARK_BENCH_COPIES";
+    const DEFAULT_COPIES: usize = 10;
+
+    /// The fixtures, and whether they contain any rewritable nodes.
+    const FIXTURES: [(&str, bool); 2] = [("guards", true), ("non_candidates", false)];
+
+    /// What a child process measured while checking a crate.
+    struct Measurement {
+        nodes: usize,
+        fast_path_bails: usize,
+        suggestions: usize,
+        classifying: Duration,
+        typeck_results: usize,
+        total: Duration,
+    }
+
+    impl Measurement {
+        fn parse(output: &str) -> Self {
+            let fields: Vec<u64> = output
+                .split_whitespace()
+                .map(|field| field.parse().expect("invalid output of the child process"))
+                .collect();
+            let &[nodes, fast_path_bails, suggestions, classifying, typeck_results, total] = fields.as_slice() else {
+                panic!("invalid output of the child process: `{output}`");
+            };
+            let count = |n: u64| usize::try_from(n).unwrap();
+            Self {
+                nodes: count(nodes),
+                fast_path_bails: count(fast_path_bails),
+                suggestions: count(suggestions),
+                classifying: Duration::from_nanos(classifying),
+                typeck_results: count(typeck_results),
+                total: Duration::from_nanos(total),
+            }
+        }
+
+        fn report(&self, label: &str) {
+            println!(
+                "  {label:<13}{} nodes, {} skipped by the fast path, {} rewrites, {} `typeck_results` calls, \
+                 {:.2?} classifying, {:.2?} in total",
+                self.nodes, self.fast_path_bails, self.suggestions, self.typeck_results, self.classifying, self.total,
+            );
+        }
+    }
+
+    pub fn run() {
+        if let Ok(krate) = env::var(CRATE_VAR) {
+            let fast_path = env::var(FAST_PATH_VAR).is_ok_and(|fast_path| fast_path == "1");
+            check_crate(Path::new(&krate), fast_path);
+            return;
+        }
+
+        let copies = env::var(COPIES_VAR).map_or(DEFAULT_COPIES, |copies| {
+            copies
+                .parse()
+                .unwrap_or_else(|_| panic!("`{COPIES_VAR}` must be a number"))
+        });
+        let fixtures_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/question_mark_bench");
+        let tmp_dir = tempfile::tempdir().unwrap();
+
+        for (fixture, has_candidates) in FIXTURES {
+            let krate = write_crate(tmp_dir.path(), &fixtures_dir.join(format!("{fixture}.rs")), copies);
+            println!("{fixture} (included {copies} times)");
+            let fast = measure(&krate, true);
+            fast.report("fast path:");
+            let slow = measure(&krate, false);
+            slow.report("no fast path:");
+
+            assert_eq!(
+                fast.suggestions, slow.suggestions,
+                "the fast path changes the rewrites found in `{fixture}`"
+            );
+            if has_candidates {
+                assert!(fast.suggestions > 0, "no rewrites found in `{fixture}`");
+            } else {
+                // Every node of the fixture has to be skipped by the fast path, before any of the
+                // checks looks at the types.
+                assert_eq!(
+                    fast.fast_path_bails, fast.nodes,
+                    "the fast path doesn't skip every node of `{fixture}`"
+                );
+                assert_eq!(
+                    fast.typeck_results, 0,
+                    "the fast path doesn't skip the type checks of `{fixture}`"
+                );
+                assert_eq!(fast.suggestions, 0, "rewrites found in `{fixture}`");
+            }
+        }
+    }
+
+    /// Writes a crate including `fixture` into `copies` modules, and returns the path to its root.
+    fn write_crate(dir: &Path, fixture: &Path, copies: usize) -> PathBuf {
+        let include = fixture.display().to_string();
+        let mut source = String::new();
+        for i in 0..copies {
+            writeln!(source, "pub mod m{i} {{ include!({include:?}); }}").unwrap();
+        }
+        let krate = dir.join(fixture.file_name().unwrap());
+        fs::write(&krate, source).unwrap();
+        krate
+    }
+
+    /// Checks `krate` in a child process.
+    fn measure(krate: &Path, fast_path: bool) -> Measurement {
+        let output = Command::new(env::current_exe().unwrap())
+            .env(CRATE_VAR, krate)
+            .env(FAST_PATH_VAR, if fast_path { "1" } else { "0" })
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "checking `{}` failed:\n{}",
+            krate.display(),
+            String::from_utf8_lossy(&output.stderr),
+        );
+        Measurement::parse(&String::from_utf8(output.stdout).unwrap())
+    }
+
+    /// Checks `krate` with only the `question_mark` lints, and prints what was measured for the
+    /// parent process.
+    fn check_crate(krate: &Path, fast_path: bool) {
+        FAST_PATH.store(fast_path, Ordering::Relaxed);
+        let out_dir = krate.with_extension("out");
+        let mut args = vec![
+            "rustc".to_owned(),
+            "--crate-type=lib".to_owned(),
+            "--edition=2021".to_owned(),
+            "--emit=metadata".to_owned(),
+            "--out-dir".to_owned(),
+            out_dir.display().to_string(),
+            krate.display().to_string(),
+        ];
+        if let Ok(sysroot) = env::var("SYSROOT") {
+            args.extend(["--sysroot".to_owned(), sysroot]);
+        }
+
+        let start = Instant::now();
+        let result = rustc_driver::RunCompiler::new(&args, &mut BenchCallbacks).run();
+        let total = start.elapsed();
+        assert!(result.is_ok(), "`{}` doesn't compile", krate.display());
+
+        println!(
+            "{} {} {} {} {} {}",
+            NODES.load(Ordering::Relaxed),
+            FAST_PATH_BAILS.load(Ordering::Relaxed),
+            SUGGESTIONS.load(Ordering::Relaxed),
+            CLASSIFY_NANOS.load(Ordering::Relaxed),
+            TYPECK_RESULTS.load(Ordering::Relaxed),
+            total.as_nanos(),
+        );
+    }
+
+    struct BenchCallbacks;
+
+    impl rustc_driver::Callbacks for BenchCallbacks {
+        fn config(&mut self, config: &mut interface::Config) {
+            config.register_lints = Some(Box::new(|sess, lint_store| {
+                // Use the default configuration, rather than the one of Clippy itself
+                let conf = Conf::read(sess, &Ok((None, Vec::new())));
+                clippy_lints::register_question_mark_lints(lint_store, conf);
+            }));
+        }
+    }
+}
@@ -0,0 +1,140 @@
+// Representative guards `question_mark` rewrites, along with conditionals it has to check and
+// leave alone. Included into many modules by `tests/question-mark-bench.rs`.
+
+use std::collections::HashMap;
+
+#[derive(Debug)]
+pub struct ParseError;
+
+pub struct Config {
+    pub name: Option<String>,
+    pub port: Option<u16>,
+    pub retries: Result<u32, ParseError>,
+}
+
+pub fn is_none(x: Option<u32>) -> Option<u32> {
+    if x.is_none() {
+        return None;
+    }
+    x
+}
+
+pub fn is_err(x: Result<u32, ParseError>) -> Result<u32, ParseError> {
+    if x.is_err() {
+        return x;
+    }
+    x
+}
+
+pub fn if_let_some_else(x: Option<u32>) -> Option<u32> {
+    let y = if let Some(y) = x { y } else { return None };
+    Some(y + 1)
+}
+
+pub fn if_let_err(x: Result<u32, ParseError>) -> Result<u32, ParseError> {
+    if let Err(e) = x {
+        return Err(e);
+    }
+    Ok(0)
+}
+
+pub fn let_else(config: &Config) -> Option<u16> {
+    let Some(port) = config.port else { return None };
+    Some(port)
+}
+
+pub fn match_some(x: Option<u32>) -> Option<u32> {
+    let y = match x {
+        Some(y) => y,
+        None => return None,
+    };
+    Some(y * 2)
+}
+
+pub fn match_ok(x: Result<u32, ParseError>) -> Result<u32, ParseError> {
+    let y = match x {
+        Ok(y) => y,
+        Err(e) => return Err(e),
+    };
+    Ok(y * 2)
+}
+
+pub fn field_guard(config: &Config) -> Option<usize> {
+    if config.name.is_none() {
+        return None;
+    }
+    config.name.as_ref().map(String::len)
+}
+
+pub fn map_lookup(map: &HashMap<u32, u32>, key: u32) -> Option<u32> {
+    let Some(value) = map.get(&key) else {
+        return None;
+    };
+    Some(*value)
+}
+
+pub fn cascade(config: &Config) -> Option<u16> {
+    if config.name.is_none() {
+        return None;
+    }
+    if config.port.is_none() {
+        return None;
+    }
+    config.port
+}
+
+pub fn retries(config: &Config) -> Result<u32, ParseError> {
+    let retries = match config.retries {
+        Ok(retries) => retries,
+        Err(_) => return Err(ParseError),
+    };
+    Ok(retries)
+}
+
+pub fn not_a_guard(x: Option<u32>) -> u32 {
+    if x.is_none() {
+        return 0;
+    }
+    if x == Some(3) { 1 } else { 2 }
+}
+
+pub fn bool_match(flag: bool, x: u32) -> u32 {
+    match flag {
+        true => x,
+        false => x + 1,
+    }
+}
+
+pub fn nested(x: Option<Option<u32>>) -> Option<u32> {
+    let inner = if let Some(inner) = x { inner } else { return None };
+    if inner.is_none() {
+        return None;
+    }
+    inner
+}
+
+pub fn loop_guard(x: Option<u32>) -> Option<u32> {
+    let mut total = 0;
+    for i in 0..4 {
+        if i == 2 {
+            continue;
+        }
+        total += i;
+    }
+    if x.is_none() {
+        return None;
+    }
+    x.map(|x| x + total)
+}
+
+pub fn closure(values: &[Option<u32>]) -> Vec<Option<u32>> {
+    values
+        .iter()
+        .map(|x| {
+            if x.is_none() {
+                return None;
+            }
+            x.map(|x| x + 1)
+        })
+        .collect()
+}
@@ -0,0 +1,56 @@
+// Code without any conditionals, so the fast path of `question_mark` has to skip every expression
+// and statement of it. Included into many modules by `tests/question-mark-bench.rs`.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Point {
+    pub x: i64,
+    pub y: i64,
+}
+
+impl Point {
+    pub fn new(x: i64, y: i64) -> Self {
+        Self { x, y }
+    }
+
+    pub fn manhattan(self, other: Self) -> i64 {
+        (self.x - other.x).abs() + (self.y - other.y).abs()
+    }
+
+    pub fn scale(self, factor: i64) -> Self {
+        Self::new(self.x * factor, self.y * factor)
+    }
+}
+
+pub fn sum(values: &[i64]) -> i64 {
+    let mut total = 0;
+    for value in values {
+        total += value;
+    }
+    total
+}
+
+pub fn doubled(values: &[i64]) -> Vec<i64> {
+    values.iter().map(|x| x * 2).filter(|x| *x > 4).collect()
+}
+
+pub fn first_len(values: &[String]) -> Option<usize> {
+    let first = values.first()?;
+    Some(first.len())
+}
+
+pub fn parse(input: &str) -> Result<i64, std::num::ParseIntError> {
+    let value = input.trim().parse::<i64>()?;
+    Ok(value * 10)
+}
+
+pub fn centroid(points: &[Point]) -> Point {
+    let len = i64::try_from(points.len()).unwrap_or(1).max(1);
+    let x = points.iter().map(|p| p.x).sum::<i64>();
+    let y = points.iter().map(|p| p.y).sum::<i64>();
+    Point::new(x / len, y / len)
+}
+
+pub fn describe(point: Point) -> String {
+    let scaled = point.scale(3);
+    format!("{point:?} -> {scaled:?} ({})", point.manhattan(scaled))
+}
@@ -0,0 +1,52 @@
+#![warn(clippy::question_mark_dump)]
+
+fn is_none(x: Option<u32>) -> Option<u32> {
+    #[clippy::question_mark_dump]
+    if x.is_none() {
+        return None;
+    }
+    x
+}
+
+fn if_let_err(r: Result<String, ()>) -> Result<usize, ()> {
+    #[clippy::question_mark_dump]
+    if let Err(e) = r {
+        return Err(e);
+    }
+    Ok(1)
+}
+
+fn let_else(x: Option<String>) -> Option<usize> {
+    #[clippy::question_mark_dump]
+    let Some(s) = x else {
+        return None;
+    };
+    Some(s.len())
+}
+
+const fn in_const(x: Option<u32>) -> Option<u32> {
+    #[clippy::question_mark_dump]
+    if x.is_none() {
+        return None;
+    }
+    x
+}
+
+fn unclassified(x: u32) -> Option<u32> {
+    #[clippy::question_mark_dump]
+    if x > 1 {
+        return None;
+    }
+    Some(x)
+}
+
+#[clippy::msrv = "1.32"]
+fn nested(x: Option<Result<u32, ()>>) -> Result<u32, ()> {
+    #[clippy::question_mark_dump]
+    if let Some(Err(e)) = x {
+        return Err(e);
+    }
+    Ok(1)
+}
+
+fn main() {}
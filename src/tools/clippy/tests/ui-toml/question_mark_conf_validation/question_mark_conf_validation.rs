@@ -0,0 +1,11 @@
+#![warn(clippy::question_mark)]
+
+// The options are still read despite the warnings
+fn f(a: Option<u32>) -> Option<u32> {
+    if a.is_none() {
+        return None;
+    }
+    a
+}
+
+fn main() {}
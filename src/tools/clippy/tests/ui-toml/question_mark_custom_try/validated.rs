@@ -0,0 +1,76 @@
+#![feature(try_trait_v2)]
+#![warn(clippy::question_mark)]
+#![allow(dead_code)]
+
+// `Validated` stands in for a type from another crate listed in `question-mark-custom-try`
+
+use std::convert::Infallible;
+use std::ops::{ControlFlow, FromResidual, Try};
+
+pub enum Validated<T, E> {
+    Valid(T),
+    Invalid(Vec<E>),
+}
+
+impl<T, E> Validated<T, E> {
+    pub fn is_invalid(&self) -> bool {
+        matches!(self, Self::Invalid(_))
+    }
+}
+
+impl<T, E> Try for Validated<T, E> {
+    type Output = T;
+    type Residual = Validated<Infallible, E>;
+
+    fn from_output(output: T) -> Self {
+        Self::Valid(output)
+    }
+
+    fn branch(self) -> ControlFlow<Self::Residual, T> {
+        match self {
+            Self::Valid(value) => ControlFlow::Continue(value),
+            Self::Invalid(errors) => ControlFlow::Break(Validated::Invalid(errors)),
+        }
+    }
+}
+
+impl<T, E> FromResidual<Validated<Infallible, E>> for Validated<T, E> {
+    fn from_residual(residual: Validated<Infallible, E>) -> Self {
+        match residual {
+            Validated::Valid(never) => match never {},
+            Validated::Invalid(errors) => Self::Invalid(errors),
+        }
+    }
+}
+
+/// Not listed in the configuration
+pub struct Checked(bool);
+
+impl Checked {
+    pub fn is_invalid(&self) -> bool {
+        !self.0
+    }
+}
+
+fn validate(v: Validated<u32, String>) -> Validated<u32, String> {
+    if v.is_invalid() {
+        return v;
+    }
+    Validated::Valid(0)
+}
+
+fn used_later(v: Validated<u32, String>) -> Validated<u32, String> {
+    if v.is_invalid() {
+        return v;
+    }
+    v
+}
+
+fn other_type(c: Checked) -> Checked {
+    if c.is_invalid() {
+        return c;
+    }
+    Checked(true)
+}
+
+fn main() {}
@@ -0,0 +1,39 @@
+#![warn(clippy::question_mark)]
+
+fn grouped(a: Option<u32>, b: Option<u32>, c: Option<u32>) -> Option<u32> {
+    if a.is_none() {
+        return None;
+    }
+    if b.is_none() {
+        return None;
+    }
+    let Some(c) = c else {
+        return None;
+    };
+    Some(c)
+}
+
+// Sites whose rewrite requires a newer MSRV are only pointed out
+#[clippy::msrv = "1.64"]
+fn grouped_msrv(a: Option<u32>, b: Option<u32>, opt: Option<u32>, count: &mut u32) -> Option<u32> {
+    if a.is_none() {
+        return None;
+    }
+    if b.is_none() {
+        return None;
+    }
+    if opt.is_none() {
+        *count += 1;
+        return None;
+    }
+    opt
+}
+
+fn single(a: Option<u32>) -> Option<u32> {
+    if a.is_none() {
+        return None;
+    }
+    a
+}
+
+fn main() {}
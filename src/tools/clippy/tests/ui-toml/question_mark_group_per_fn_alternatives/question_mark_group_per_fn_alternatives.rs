@@ -0,0 +1,12 @@
+#![warn(clippy::question_mark)]
+
+// The alternative rewrites of the sites are offered next to the grouped suggestion
+fn grouped(a: Option<u32>, opt: Option<String>) -> Option<usize> {
+    if a.is_none() {
+        return None;
+    }
+    let s = if let Some(ref s) = opt { s } else { return None };
+    Some(s.len() + opt.map_or(0, |s| s.len()))
+}
+
+fn main() {}
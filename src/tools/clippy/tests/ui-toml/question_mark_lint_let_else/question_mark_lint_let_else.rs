@@ -0,0 +1,13 @@
+#![warn(clippy::question_mark)]
+
+fn let_else_is_ignored(a: Option<u32>) -> Option<u32> {
+    let Some(b) = a else { return None };
+    Some(b + 1)
+}
+
+fn if_let_still_lints(a: Option<u32>) -> Option<u32> {
+    let b = if let Some(b) = a { b } else { return None };
+    Some(b + 1)
+}
+
+fn main() {}
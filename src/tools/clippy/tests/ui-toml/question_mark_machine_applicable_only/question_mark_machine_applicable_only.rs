@@ -0,0 +1,50 @@
+//@revisions: default only
+//@[default] rustc-env:CLIPPY_CONF_DIR=tests/ui-toml/question_mark_machine_applicable_only/default
+//@[only] rustc-env:CLIPPY_CONF_DIR=tests/ui-toml/question_mark_machine_applicable_only/only
+
+#![warn(clippy::question_mark)]
+#![allow(dead_code)]
+
+fn step() -> Result<u32, String> {
+    Ok(1)
+}
+
+fn if_is(a: Option<u32>) -> Option<u32> {
+    if a.is_none() {
+        return None;
+    }
+    a
+}
+
+fn if_let(x: u32) -> Result<u32, String> {
+    if let Err(e) = step() {
+        return Err(e);
+    }
+    Ok(x)
+}
+
+// The payloads don't line up, so the suggestion isn't machine applicable
+fn if_is_nested(opt: Option<Result<u32, u32>>) -> Option<u32> {
+    if opt.is_none() {
+        return None;
+    }
+    Some(0)
+}
+
+// The payloads don't line up, so the suggestion isn't machine applicable
+fn if_let_nested(outer: Result<Option<u32>, u32>) -> Result<u32, u32> {
+    if let Err(e) = outer {
+        return Err(e);
+    }
+    Ok(0)
+}
+
+// `let...else` rewrites are never machine applicable
+fn let_else(a: Option<u32>) -> Option<u32> {
+    let Some(x) = a else {
+        return None;
+    };
+    Some(x + 1)
+}
+
+fn main() {}
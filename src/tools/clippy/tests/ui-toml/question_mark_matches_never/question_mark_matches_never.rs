@@ -0,0 +1,17 @@
+#![warn(clippy::question_mark)]
+#![allow(clippy::manual_let_else)]
+
+fn match_is_ignored(a: Option<u32>) -> Option<u32> {
+    let b = match a {
+        Some(b) => b,
+        None => return None,
+    };
+    Some(b + 1)
+}
+
+fn if_let_still_lints(a: Option<u32>) -> Option<u32> {
+    let b = if let Some(b) = a { b } else { return None };
+    Some(b + 1)
+}
+
+fn main() {}
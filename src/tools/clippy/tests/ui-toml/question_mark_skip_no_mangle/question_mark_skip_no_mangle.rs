@@ -0,0 +1,59 @@
+//@revisions: default skip
+//@[default] rustc-env:CLIPPY_CONF_DIR=tests/ui-toml/question_mark_skip_no_mangle/default
+//@[skip] rustc-env:CLIPPY_CONF_DIR=tests/ui-toml/question_mark_skip_no_mangle/skip
+
+#![warn(clippy::question_mark)]
+#![allow(dead_code)]
+
+// Functions with a non-Rust ABI are never linted
+extern "C" fn ffi(a: Option<&u32>) -> Option<&u32> {
+    if a.is_none() {
+        return None;
+    }
+    a
+}
+
+#[no_mangle]
+extern "C" fn exported_ffi(a: Option<&u32>) -> Option<&u32> {
+    if a.is_none() {
+        return None;
+    }
+    a
+}
+
+extern "C" fn ffi_closure(a: Option<&u32>) -> Option<&u32> {
+    let f = || {
+        if a.is_none() {
+            return None;
+        }
+        a
+    };
+    f()
+}
+
+extern "C" fn ffi_deferred_init(a: Option<u32>) -> Option<u32> {
+    let x;
+    if let Some(v) = a {
+        x = v;
+    } else {
+        return None;
+    }
+    Some(x)
+}
+
+#[no_mangle]
+pub fn exported(a: Option<&u32>) -> Option<&u32> {
+    if a.is_none() {
+        return None;
+    }
+    a
+}
+
+pub fn rust(a: Option<&u32>) -> Option<&u32> {
+    if a.is_none() {
+        return None;
+    }
+    a
+}
+
+fn main() {}
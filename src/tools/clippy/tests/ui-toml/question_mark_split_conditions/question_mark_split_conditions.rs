@@ -0,0 +1,41 @@
+#![warn(clippy::question_mark)]
+#![allow(dead_code)]
+
+struct Config {
+    enabled: bool,
+}
+
+fn check() -> bool {
+    true
+}
+
+fn flag_first(flag: bool, opt: Option<u32>) -> Option<u32> {
+    if flag && opt.is_none() {
+        return None;
+    }
+    Some(opt.unwrap_or(0))
+}
+
+fn flag_last(config: &Config, opt: Option<u32>) -> Option<u32> {
+    if opt.is_none() && config.enabled {
+        return None;
+    }
+    Some(opt.unwrap_or(0))
+}
+
+fn result(strict: bool, r: Result<u32, u32>) -> Result<u32, u32> {
+    if strict && r.is_err() {
+        return r;
+    }
+    Ok(0)
+}
+
+// Moving `check()` before `opt.is_none()` changes the evaluation order
+fn call_last(opt: Option<u32>) -> Option<u32> {
+    if opt.is_none() && check() {
+        return None;
+    }
+    Some(opt.unwrap_or(0))
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+#![warn(clippy::question_mark)]
+
+fn hoisted(a: Option<u32>) -> Option<u32> {
+    let missing = a.is_none();
+    if missing {
+        return None;
+    }
+    a
+}
+
+fn main() {}
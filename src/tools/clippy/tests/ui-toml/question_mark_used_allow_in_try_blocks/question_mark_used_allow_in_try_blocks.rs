@@ -0,0 +1,22 @@
+#![feature(try_blocks)]
+#![warn(clippy::question_mark_used)]
+
+fn in_try_block(x: Option<u32>, y: Option<u32>) -> Option<u32> {
+    let sum: Option<u32> = try { x? + y? };
+    sum
+}
+
+fn closure_in_try_block(x: Option<u32>) -> Option<u32> {
+    let r: Option<u32> = try {
+        let f = || x?.checked_add(1);
+        f()?
+    };
+    r
+}
+
+fn outside(x: Option<u32>) -> Option<u32> {
+    let x = x?;
+    Some(x)
+}
+
+fn main() {}
@@ -0,0 +1,44 @@
+#![warn(clippy::question_mark_used, clippy::question_mark)]
+
+use std::fmt;
+
+struct Point {
+    x: u32,
+    y: u32,
+}
+
+impl fmt::Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, ", self.x)?;
+        write!(f, "{})", self.y)
+    }
+}
+
+impl fmt::Debug for Point {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Point {} ", self.x)?;
+        write!(f, "{}", self.y)
+    }
+}
+
+struct Name(String);
+
+impl fmt::Display for Name {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let r = write!(f, "name: ");
+        if r.is_err() {
+            return r;
+        }
+        write!(f, "{}", self.0)
+    }
+}
+
+fn free(x: Option<u32>, y: Option<u32>) -> Option<u32> {
+    if y.is_none() {
+        return None;
+    }
+    let x = x?;
+    Some(x)
+}
+
+fn main() {}
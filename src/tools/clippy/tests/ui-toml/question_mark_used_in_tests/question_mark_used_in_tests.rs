@@ -0,0 +1,29 @@
+//@compile-flags: --test
+#![warn(clippy::question_mark_used)]
+#![allow(dead_code)]
+
+#[derive(Debug)]
+struct ParseError;
+
+fn parse(s: &str) -> Result<u32, ParseError> {
+    s.parse().map_err(|_| ParseError)
+}
+
+fn helper(s: &str) -> Result<u32, ParseError> {
+    let n = parse(s)?;
+    Ok(n + 1)
+}
+
+#[test]
+fn parses_numbers() {
+    let parse_plus_one = |s: &str| -> Result<u32, ParseError> { Ok(parse(s)? + 1) };
+    let first = |v: &[u32]| -> Option<u32> { Some(*v.first()? + 1) };
+    assert!(parse_plus_one("1").is_ok());
+    assert_eq!(first(&[1]), Some(2));
+}
+
+#[test]
+fn returns_result() -> Result<(), ParseError> {
+    parse("1")?;
+    Ok(())
+}
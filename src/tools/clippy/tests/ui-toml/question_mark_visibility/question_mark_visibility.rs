@@ -0,0 +1,32 @@
+//@revisions: public_only private_only
+//@[public_only] rustc-env:CLIPPY_CONF_DIR=tests/ui-toml/question_mark_visibility/public_only
+//@[private_only] rustc-env:CLIPPY_CONF_DIR=tests/ui-toml/question_mark_visibility/private_only
+
+#![warn(clippy::question_mark)]
+#![allow(dead_code)]
+
+pub fn public(a: Option<u32>) -> Option<u32> {
+    if a.is_none() {
+        return None;
+    }
+    a
+}
+
+fn private(a: Option<u32>) -> Option<u32> {
+    if a.is_none() {
+        return None;
+    }
+    a
+}
+
+pub fn public_closure(a: Option<u32>) -> Option<u32> {
+    let f = || {
+        if a.is_none() {
+            return None;
+        }
+        a
+    };
+    f()
+}
+
+fn main() {}
@@ -0,0 +1,44 @@
+#![warn(clippy::manual_let_else, clippy::question_mark)]
+
+struct Configuration {
+    verbose: bool,
+}
+
+fn g() -> Option<u32> {
+    None
+}
+
+fn fetch_configuration_value() -> Option<u32> {
+    None
+}
+
+fn short_let_else() {
+    let v = if let Some(v) = g() { v } else { return };
+    println!("{v}");
+}
+
+fn long_let_else() {
+    let value = match fetch_configuration_value() {
+        Some(v) => v,
+        None => return,
+    };
+    println!("{value}");
+}
+
+fn short_unwrap(cfg: Option<Configuration>) -> Option<bool> {
+    if cfg.is_none() {
+        return None;
+    }
+    let cfg = cfg.unwrap();
+    Some(cfg.verbose)
+}
+
+fn long_unwrap(configuration_value: Option<Configuration>) -> Option<bool> {
+    if configuration_value.is_none() {
+        return None;
+    }
+    let configuration_value: Configuration = configuration_value.unwrap();
+    Some(configuration_value.verbose)
+}
+
+fn main() {}
@@ -190,3 +190,24 @@ pub fn duplicated_attr(_attr: TokenStream, input: TokenStream) -> TokenStream {
     }
     .into()
 }
+
+/// Prepends `if <first param>.is_none() { return None; }` to the body of the function, with the
+/// span of the function name, like proc macros forwarding the spans of their input.
+#[proc_macro_attribute]
+pub fn guard_first_param(_attr: TokenStream, input: TokenStream) -> TokenStream {
+    let mut item = parse_macro_input!(input as ItemFn);
+    let span = item.sig.ident.span();
+    let Some(FnArg::Typed(PatType { pat, .. })) = item.sig.inputs.first() else {
+        panic!("expected a function with a parameter");
+    };
+    let Pat::Ident(PatIdent { ident: param, .. }) = &**pat else {
+        panic!("expected a named parameter");
+    };
+    let guard = quote_spanned! {span=>
+        if #param.is_none() {
+            return None;
+        }
+    };
+    item.block.stmts.insert(0, syn::parse2(guard).unwrap());
+    quote!(#item).into()
+}
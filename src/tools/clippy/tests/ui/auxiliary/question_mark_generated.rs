@@ -0,0 +1,9 @@
+// Stands in for generated code (e.g. protobuf or bindgen output) which is `include!`d by
+// `question_mark_generated.rs`.
+
+fn from_included_file(a: Option<u32>) -> Option<u32> {
+    if a.is_none() {
+        return None;
+    }
+    a
+}
@@ -0,0 +1,10 @@
+//@edition:2015
+
+pub struct Obj;
+
+impl Obj {
+    // `try` is only a keyword from the 2018 edition on
+    pub fn try(&self) -> Option<u32> {
+        Some(1)
+    }
+}
@@ -0,0 +1,100 @@
+#![warn(clippy::manual_let_else, clippy::question_mark)]
+#![allow(clippy::needless_late_init)]
+
+fn simple(opt: Option<u32>) {
+    let x;
+    if let Some(v) = opt {
+        x = v;
+    } else {
+        return;
+    }
+    println!("{x}");
+}
+
+fn annotated(s: &str) {
+    let x: u8;
+    if let Ok(v) = s.parse() {
+        x = v;
+    } else {
+        return;
+    }
+    println!("{x}");
+}
+
+fn mutable(opt: Option<u32>) {
+    let mut x;
+    if let Some(v) = opt {
+        x = v;
+    } else {
+        panic!("no value");
+    }
+    x += 1;
+    println!("{x}");
+}
+
+fn question_mark(opt: Option<u32>) -> Option<u32> {
+    let x;
+    if let Some(v) = opt {
+        x = v;
+    } else {
+        return None;
+    }
+    Some(x + 1)
+}
+
+fn statement_in_between(opt: Option<u32>) {
+    let x;
+    println!("checking");
+    if let Some(v) = opt {
+        x = v;
+    } else {
+        return;
+    }
+    println!("{x}");
+}
+
+fn then_does_more(opt: Option<u32>) {
+    let x;
+    if let Some(v) = opt {
+        println!("found");
+        x = v;
+    } else {
+        return;
+    }
+    println!("{x}");
+}
+
+fn else_does_not_diverge(opt: Option<u32>) {
+    let x;
+    if let Some(v) = opt {
+        x = v;
+    } else {
+        x = 0;
+    }
+    println!("{x}");
+}
+
+fn msrv_on_declaration(opt: Option<u32>) {
+    #[clippy::msrv = "1.64"]
+    let x;
+    if let Some(v) = opt {
+        x = v;
+    } else {
+        return;
+    }
+    println!("{x}");
+}
+
+fn msrv_after_candidate(opt: Option<u32>) {
+    let x;
+    if let Some(v) = opt {
+        x = v;
+    } else {
+        return;
+    }
+    #[clippy::msrv = "1.64"]
+    let y = x + 1;
+    println!("{y}");
+}
+
+fn main() {}
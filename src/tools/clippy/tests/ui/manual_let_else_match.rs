@@ -178,3 +178,19 @@ fn issue11579() {
         _ => unreachable!("can't happen"),
     };
 }
+
+struct ParseRecovered(u32);
+
+fn or_pattern_success_arm(input: Result<u32, ParseRecovered>) {
+    let x = match input {
+        //~^ ERROR: this could be rewritten as `let...else`
+        Ok(v) | Err(ParseRecovered(v)) => v,
+        Err(_) => return,
+    };
+
+    // The remaining arm doesn't diverge
+    let x = match input {
+        Ok(v) | Err(ParseRecovered(v)) => v,
+        Err(_) => 0,
+    };
+}
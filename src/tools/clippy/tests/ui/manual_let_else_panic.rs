@@ -0,0 +1,47 @@
+#![warn(clippy::manual_let_else)]
+#![allow(dead_code, clippy::let_and_return)]
+
+fn implicit_capture(cfg: Option<u32>, name: &str) -> u32 {
+    let v = match cfg {
+        Some(v) => v,
+        None => panic!("missing config {name}"),
+    };
+    v
+}
+
+fn raw_string(cfg: Option<u32>, name: &str) -> u32 {
+    let v = match cfg {
+        Some(v) => v,
+        None => panic!(r#"missing config "{name}""#),
+    };
+    v
+}
+
+fn multi_line_args(cfg: Option<u32>, name: &str, path: &str) -> u32 {
+    let v = match cfg {
+        Some(v) => v,
+        None => panic!(
+            "missing config {} in {}",
+            name, path
+        ),
+    };
+    v
+}
+
+// The string literal itself spans multiple lines, so its lines must not be reindented
+fn multi_line_literal(cfg: Option<u32>, name: &str) -> u32 {
+    let v = match cfg {
+        Some(v) => v,
+        None => panic!("missing config
+            {name}"),
+    };
+    v
+}
+
+#[track_caller]
+fn track_caller(cfg: Option<u32>, name: &str) -> u32 {
+    let v = if let Some(v) = cfg { v } else { panic!("missing config {name}") };
+    v
+}
+
+fn main() {}
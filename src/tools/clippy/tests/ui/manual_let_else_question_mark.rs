@@ -88,3 +88,16 @@ fn issue11993(y: Option<i32>) -> Option<i32> {
 
     None
 }
+
+const NONE: Option<(u8, u8)> = None;
+
+// Only question_mark fires for these, rewriting them to `let...else` would just lead to another lint
+fn only_question_mark(r: Result<u8, u8>) -> Result<u8, u8> {
+    let v = if let Ok(v) = r { v } else { return r };
+    Ok(v)
+}
+
+fn only_question_mark_none_const() -> Option<(u8, u8)> {
+    let v = if let Some(v) = g() { v } else { return NONE };
+    Some(v)
+}
@@ -0,0 +1,66 @@
+#![warn(clippy::manual_let_else)]
+#![allow(clippy::single_match_else)]
+
+async fn fetch() -> Option<u32> {
+    Some(1)
+}
+
+fn parse(s: &str) -> Result<Option<u32>, ()> {
+    Ok(s.parse().ok())
+}
+
+fn log<T>(_: T) {}
+
+async fn awaited() {
+    let x = match fetch().await {
+        //~^ ERROR: this could be rewritten as `let...else`
+        Some(v) => v,
+        None => return,
+    };
+    log(x);
+}
+
+fn place(opt: Option<u32>) {
+    // Naming a place again doesn't evaluate anything
+    let x = match opt {
+        //~^ ERROR: this could be rewritten as `let...else`
+        Some(v) => v,
+        None => {
+            log(opt);
+            return;
+        },
+    };
+    log(x);
+}
+
+async fn awaited_again() {
+    let x = match fetch().await {
+        Some(v) => v,
+        None => {
+            log(fetch().await);
+            return;
+        },
+    };
+    log(x);
+}
+
+fn tried_again(s: &str) -> Result<(), ()> {
+    let x = if let Some(v) = parse(s)? {
+        v
+    } else {
+        log(parse(s)?);
+        return Err(());
+    };
+    log(x);
+    Ok(())
+}
+
+fn called_again(s: &str) {
+    let x = match s.parse::<u32>() {
+        Ok(v) => v,
+        Err(_) => panic!("{:?}", s.parse::<u32>()),
+    };
+    log(x);
+}
+
+fn main() {}
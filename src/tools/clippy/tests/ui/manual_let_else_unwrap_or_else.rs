@@ -0,0 +1,64 @@
+#![warn(clippy::manual_let_else)]
+#![allow(
+    dead_code,
+    clippy::let_and_return,
+    clippy::needless_return,
+    clippy::unnecessary_lazy_evaluations
+)]
+
+fn fatal_exit() -> ! {
+    std::process::exit(1)
+}
+
+fn fatal_exit_with(code: i32) -> ! {
+    std::process::exit(code)
+}
+
+fn option_panic(opt: Option<u32>) -> u32 {
+    let x = opt.unwrap_or_else(|| panic!("missing"));
+    x
+}
+
+fn result_ignored_param(r: Result<u32, String>) -> u32 {
+    let x = r.unwrap_or_else(|_| fatal_exit());
+    x
+}
+
+fn result_unused_param(r: Result<u32, String>) -> u32 {
+    let x = r.unwrap_or_else(|_e| {
+        eprintln!("failed");
+        fatal_exit()
+    });
+    x
+}
+
+fn map_or_else_identity(opt: Option<(u32, u32)>) -> u32 {
+    let (a, b) = opt.map_or_else(|| fatal_exit_with(2), |v| v);
+    a + b
+}
+
+// Don't lint: the error is used by the closure
+fn result_used_param(r: Result<u32, i32>) -> u32 {
+    let x = r.unwrap_or_else(|e| fatal_exit_with(e));
+    x
+}
+
+// Don't lint: the closure doesn't diverge
+fn not_diverging(opt: Option<u32>) -> u32 {
+    let x = opt.unwrap_or_else(|| 0);
+    x
+}
+
+// Don't lint: `return` only returns from the closure
+fn closure_return(opt: Option<u32>) -> u32 {
+    let x = opt.unwrap_or_else(|| return 0);
+    x
+}
+
+// Don't lint: the value is mapped
+fn mapped(opt: Option<u32>) -> u32 {
+    let x = opt.map_or_else(|| panic!(), |v| v + 1);
+    x
+}
+
+fn main() {}
@@ -0,0 +1,53 @@
+#![warn(clippy::question_mark)]
+#![allow(dead_code, clippy::unnecessary_wraps, clippy::needless_option_as_deref)]
+
+struct Person {
+    name: Option<String>,
+    nickname: Option<String>,
+}
+
+impl Person {
+    fn name_len(&self) -> Option<usize> {
+        if self.name.as_deref().is_none() {
+            return None;
+        }
+        Some(self.name.as_deref().unwrap().len())
+    }
+
+    fn nickname_len(&self) -> Option<usize> {
+        if self.nickname.as_ref().as_deref().is_none() {
+            return None;
+        }
+        let len = self.nickname.as_ref().as_deref().unwrap().len();
+        Some(len)
+    }
+
+    fn push_name(&mut self) -> Option<()> {
+        if self.name.as_mut().is_none() {
+            return None;
+        }
+        self.name.as_mut().unwrap().push('!');
+        Some(())
+    }
+
+    // `self` is mutated between the guard and the reuse
+    fn mutated(&mut self) -> Option<usize> {
+        if self.name.as_deref().is_none() {
+            return None;
+        }
+        self.nickname = None;
+        Some(self.name.as_deref().unwrap().len())
+    }
+
+    // The adapted `Option` already contains a reference, so it isn't borrowed again
+    fn if_let_ref(&self) -> Option<usize> {
+        let name = if let Some(ref name) = self.name.as_deref() {
+            name
+        } else {
+            return None;
+        };
+        Some(name.len())
+    }
+}
+
+fn main() {}
@@ -0,0 +1,111 @@
+#![warn(clippy::question_mark, clippy::question_mark_match)]
+#![allow(clippy::manual_let_else, dead_code)]
+
+#[derive(Debug)]
+pub struct MyError;
+
+type Res<T> = Result<T, MyError>;
+pub type Maybe<T> = Option<T>;
+
+fn if_let_err(r: Res<u32>) -> Res<u32> {
+    if let Err(e) = r {
+        return Err(e);
+    }
+    Ok(0)
+}
+
+fn if_let_err_alias_ctor(r: Res<u32>) -> Res<u32> {
+    if let Res::Err(e) = r {
+        return Res::Err(e);
+    }
+    Ok(0)
+}
+
+fn if_let_err_qualified_ctor(r: Res<u32>) -> Res<u32> {
+    if let Err(e) = r {
+        return Result::Err(e);
+    }
+    Ok(0)
+}
+
+fn if_let_ok(r: Res<u32>) -> Res<u32> {
+    let x = if let Ok(x) = r { x } else { return r };
+    Ok(x)
+}
+
+fn is_none(m: Maybe<u32>) -> Maybe<u32> {
+    if m.is_none() {
+        return Maybe::None;
+    }
+    m
+}
+
+fn let_else(m: Maybe<u32>) -> Maybe<u32> {
+    let Some(x) = m else { return None };
+    Some(x)
+}
+
+fn match_alias_patterns(m: Maybe<u32>) -> Maybe<u32> {
+    let x = match m {
+        Maybe::Some(x) => x,
+        Maybe::None => return None,
+    };
+    Some(x)
+}
+
+// `&Option<T>` doesn't implement `Try`, regardless of whether it's spelled through an alias
+fn borrowed_let_else(m: &Maybe<u32>) -> Maybe<u32> {
+    let Some(x) = m else { return None };
+    Some(*x)
+}
+
+fn borrowed_let_else_no_alias(m: &Option<u32>) -> Option<u32> {
+    let Some(x) = m else { return None };
+    Some(*x)
+}
+
+trait Narrow: Sized {
+    fn narrow(self) -> Self;
+}
+
+// An inherent impl isn't allowed on `Option`, but `Self::None` also resolves through a trait impl
+impl Narrow for Maybe<u32> {
+    fn narrow(self) -> Self {
+        if self.is_none() {
+            return Self::None;
+        }
+        self.filter(|x| *x > 1)
+    }
+}
+
+fn is_none_alias_path(m: Maybe<u32>) -> Option<u32> {
+    if m.is_none() {
+        return Maybe::<u32>::None;
+    }
+    m
+}
+
+pub struct Thing(u32);
+pub type MaybeThing = Option<Thing>;
+
+fn lex(s: &str) -> Option<u32> {
+    s.parse().ok()
+}
+
+trait Parse: Sized {
+    fn parse(s: &str) -> Self;
+}
+
+impl Parse for MaybeThing {
+    fn parse(s: &str) -> Self {
+        let Some(x) = lex(s) else { return Self::None };
+        Some(Thing(x))
+    }
+}
+
+fn let_else_qualified_none(m: Maybe<u32>) -> Maybe<u32> {
+    let Some(x) = m else { return Option::None };
+    Some(x)
+}
+
+fn main() {}
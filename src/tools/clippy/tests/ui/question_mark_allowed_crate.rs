@@ -0,0 +1,28 @@
+#![allow(clippy::question_mark)]
+#![allow(dead_code)]
+
+fn allowed(opt: Option<u32>) -> Option<u32> {
+    if opt.is_none() {
+        return None;
+    }
+    opt
+}
+
+mod warned {
+    #![warn(clippy::question_mark)]
+
+    fn reenabled_for_module(opt: Option<u32>) -> Option<u32> {
+        if opt.is_none() {
+            return None;
+        }
+        opt
+    }
+}
+
+fn reenabled_for_statement(opt: Option<u32>) -> Option<u32> {
+    #[warn(clippy::question_mark)]
+    let v = if let Some(v) = opt { v } else { return None };
+    Some(v)
+}
+
+fn main() {}
@@ -0,0 +1,82 @@
+#![warn(clippy::question_mark)]
+#![allow(clippy::match_bool, clippy::needless_return, dead_code)]
+
+fn option(opt: Option<u32>) -> Option<u32> {
+    match opt.is_none() {
+        true => return None,
+        false => {},
+    }
+    opt
+}
+
+fn result(r: Result<u32, u32>) -> Result<u32, u32> {
+    match r.is_err() {
+        true => return r,
+        _ => (),
+    }
+    r
+}
+
+fn false_first(opt: Option<u32>) -> Option<u32> {
+    match opt.is_none() {
+        false => {},
+        true => {
+            return None;
+        },
+    };
+    opt
+}
+
+fn borrowed(opt: Option<String>) -> Option<usize> {
+    match opt.is_none() {
+        true => return None,
+        false => (),
+    }
+    Some(opt.unwrap().len())
+}
+
+// Don't lint
+fn expression_position(opt: Option<u32>) -> Option<u32> {
+    let () = match opt.is_none() {
+        true => return None,
+        false => (),
+    };
+    opt
+}
+
+fn non_empty_false_arm(opt: Option<u32>) -> Option<u32> {
+    match opt.is_none() {
+        true => return None,
+        false => println!("some"),
+    }
+    opt
+}
+
+fn binding_pattern(opt: Option<u32>) -> Option<u32> {
+    match opt.is_none() {
+        true => return None,
+        b => {
+            let _ = b;
+        },
+    }
+    opt
+}
+
+fn guarded_arm(opt: Option<u32>, flag: bool) -> Option<u32> {
+    match opt.is_none() {
+        true if flag => return None,
+        _ => {},
+    }
+    opt
+}
+
+fn wildcard_first(opt: Option<u32>) -> Option<u32> {
+    #[allow(unreachable_patterns)]
+    match opt.is_none() {
+        _ => {},
+        true => return None,
+    }
+    opt
+}
+
+fn main() {}
@@ -0,0 +1,78 @@
+#![warn(clippy::question_mark)]
+#![allow(dead_code)]
+
+pub struct Built {
+    name: String,
+    parts: Vec<u32>,
+}
+
+#[derive(Default)]
+pub struct Builder {
+    name: Option<String>,
+    parts: Option<Vec<u32>>,
+}
+
+impl Builder {
+    // `self` is owned and not used afterwards, the field can be moved out of
+    pub fn check(self) -> Option<Built> {
+        if self.name.is_none() {
+            return None;
+        }
+        Some(Built {
+            name: String::new(),
+            parts: Vec::new(),
+        })
+    }
+
+    // `self` is used after the guard, moving the field would create a partial move
+    pub fn build(self) -> Option<Built> {
+        if self.name.is_none() {
+            return None;
+        }
+        let name = self.name.unwrap();
+        let parts = self.parts.unwrap_or_default();
+        Some(Built { name, parts })
+    }
+
+    pub fn build_mut(&mut self) -> Option<Built> {
+        if self.name.is_none() {
+            return None;
+        }
+        let name = self.name.take().unwrap();
+        let parts = self.parts.take().unwrap_or_default();
+        Some(Built { name, parts })
+    }
+}
+
+pub struct Nested {
+    builder: Builder,
+}
+
+impl Nested {
+    pub fn check(self) -> Option<()> {
+        if self.builder.parts.is_none() {
+            return None;
+        }
+        Some(())
+    }
+}
+
+pub struct WithDrop {
+    name: Option<String>,
+}
+
+impl Drop for WithDrop {
+    fn drop(&mut self) {}
+}
+
+impl WithDrop {
+    // Fields can't be moved out of types implementing `Drop`
+    pub fn check(self) -> Option<()> {
+        if self.name.is_none() {
+            return None;
+        }
+        Some(())
+    }
+}
+
+fn main() {}
@@ -0,0 +1,32 @@
+#![warn(clippy::question_mark)]
+#![allow(dead_code)]
+
+fn call_later(cb: Option<impl FnOnce()>) -> Option<()> {
+    if cb.is_none() {
+        return None;
+    }
+    (cb.unwrap())();
+    Some(())
+}
+
+fn call_with_borrow(cb: Option<impl FnOnce(&mut Vec<u32>)>) -> Option<Vec<u32>> {
+    let mut log = Vec::new();
+    if cb.is_none() {
+        return None;
+    }
+    log.push(1);
+    cb.expect("checked above")(&mut log);
+    Some(log)
+}
+
+fn called_twice(cb: Option<fn()>) -> Option<()> {
+    // The callback is still needed after the first call
+    if cb.is_none() {
+        return None;
+    }
+    (cb.unwrap())();
+    (cb.unwrap())();
+    Some(())
+}
+
+fn main() {}
@@ -0,0 +1,49 @@
+#![warn(
+    clippy::question_mark,
+    clippy::question_mark_match,
+    clippy::question_mark_candidates_summary
+)]
+#![allow(clippy::manual_let_else, dead_code)]
+
+fn if_is(a: Option<u32>) -> Option<u32> {
+    if a.is_none() {
+        return None;
+    }
+    a
+}
+
+fn if_is_err(a: Result<u32, u32>) -> Result<u32, u32> {
+    if a.is_err() {
+        return a;
+    }
+    a
+}
+
+fn if_let(a: Option<u32>) -> Option<u32> {
+    let b = if let Some(b) = a { b } else { return None };
+    Some(b)
+}
+
+fn let_else(a: Option<u32>) -> Option<u32> {
+    let Some(b) = a else { return None };
+    Some(b)
+}
+
+fn match_expr(a: Option<u32>) -> Option<u32> {
+    let b = match a {
+        Some(b) => b,
+        None => return None,
+    };
+    Some(b)
+}
+
+// Sites at which `question_mark` is allowed aren't counted
+#[allow(clippy::question_mark)]
+fn allowed(a: Option<u32>) -> Option<u32> {
+    if a.is_none() {
+        return None;
+    }
+    a
+}
+
+fn main() {}
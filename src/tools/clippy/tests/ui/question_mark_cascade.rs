@@ -0,0 +1,41 @@
+#![warn(clippy::question_mark)]
+#![allow(dead_code)]
+
+struct Tls {
+    port: Option<u16>,
+}
+
+struct Server {
+    port: Option<u16>,
+    tls: Option<Tls>,
+}
+
+struct Config {
+    server: Option<Server>,
+}
+
+fn two_levels(cfg: &Config) -> Option<u16> {
+    if cfg.server.is_none() {
+        return None;
+    }
+    if cfg.server.as_ref().unwrap().port.is_none() {
+        return None;
+    }
+    Some(cfg.server.as_ref().unwrap().port.unwrap() + 1)
+}
+
+fn three_levels(cfg: &Config) -> Option<u16> {
+    if cfg.server.is_none() {
+        return None;
+    }
+    if cfg.server.as_ref().unwrap().tls.is_none() {
+        return None;
+    }
+    if cfg.server.as_ref().unwrap().tls.as_ref().unwrap().port.is_none() {
+        return None;
+    }
+    let p = cfg.server.as_ref().unwrap().tls.as_ref().unwrap().port.unwrap();
+    Some(p)
+}
+
+fn main() {}
@@ -0,0 +1,70 @@
+//@compile-flags: --cfg feature="strict"
+#![warn(clippy::question_mark)]
+#![allow(dead_code, unexpected_cfgs)]
+
+fn log_missing() {}
+
+fn cfg_before(opt: Option<u32>) -> Option<u32> {
+    // No warning, `cfg!` is part of the condition
+    if cfg!(feature = "strict") && opt.is_none() {
+        return None;
+    }
+    opt
+}
+
+fn cfg_after(opt: Option<u32>) -> Option<u32> {
+    // No warning, `cfg!` is part of the condition
+    if opt.is_none() && cfg!(feature = "strict") {
+        return None;
+    }
+    opt
+}
+
+fn cfg_removed_stmt(opt: Option<u32>) -> Option<u32> {
+    // No warning, the suggestion would delete the configured out statement
+    if opt.is_none() {
+        #[cfg(feature = "verbose")]
+        log_missing();
+        return None;
+    }
+    opt
+}
+
+fn cfg_attr_on_return(opt: Option<u32>) -> Option<u32> {
+    // No warning, the suggestion would delete the attribute
+    if opt.is_none() {
+        #[cfg_attr(feature = "strict", allow(unreachable_code))]
+        return None;
+    }
+    opt
+}
+
+fn cfg_attr_if_let(res: Result<u32, u32>) -> Result<u32, u32> {
+    // No warning, the suggestion would delete the attribute
+    if let Err(err) = res {
+        #[cfg_attr(feature = "strict", allow(unreachable_code))]
+        return Err(err);
+    }
+    res
+}
+
+fn cfg_attr_let_else(opt: Option<u32>) -> Option<u32> {
+    // No warning, the suggestion would delete the attribute
+    let Some(v) = opt else {
+        #[cfg_attr(feature = "strict", allow(unreachable_code))]
+        return None;
+    };
+    Some(v)
+}
+
+fn cfg_enabled_guard(opt: Option<u32>) -> Option<u32> {
+    #[cfg(feature = "strict")]
+    {
+        if opt.is_none() {
+            return None;
+        }
+    }
+    opt
+}
+
+fn main() {}
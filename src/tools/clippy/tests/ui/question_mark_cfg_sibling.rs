@@ -0,0 +1,35 @@
+//@compile-flags: -C debug-assertions=off
+#![warn(clippy::question_mark)]
+#![allow(clippy::manual_let_else, dead_code)]
+
+fn debug_sibling_before(opt: Option<u32>) -> Option<u32> {
+    #[cfg(debug_assertions)]
+    if opt.is_none() {
+        panic!("missing value");
+    }
+    #[cfg(not(debug_assertions))]
+    if opt.is_none() {
+        return None;
+    }
+    opt
+}
+
+fn debug_sibling_after(opt: Option<u32>) -> Option<u32> {
+    let Some(v) = opt else {
+        return None;
+    };
+    #[cfg(debug_assertions)]
+    dbg!(opt);
+    Some(v)
+}
+
+fn unrelated_sibling(opt: Option<u32>, other: Option<u32>) -> Option<u32> {
+    if opt.is_none() {
+        return None;
+    }
+    #[cfg(debug_assertions)]
+    assert!(other.is_some());
+    opt
+}
+
+fn main() {}
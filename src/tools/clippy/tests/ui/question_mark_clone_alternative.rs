@@ -0,0 +1,31 @@
+#![warn(clippy::question_mark, clippy::question_mark_match)]
+#![allow(dead_code)]
+
+struct NotClone;
+
+fn if_let(opt: Option<String>) -> Option<usize> {
+    let s = if let Some(ref s) = opt { s } else { return None };
+    Some(s.len() + opt.map_or(0, |s| s.len()))
+}
+
+fn match_result(r: Result<Vec<u8>, String>) -> Result<usize, String> {
+    let v = match r {
+        Ok(ref v) => v,
+        Err(e) => return Err(e),
+    };
+    Ok(v.len())
+}
+
+fn not_clone(opt: Option<NotClone>) -> Option<()> {
+    // Only suggest borrowing, `NotClone` can't be cloned
+    let _nc = if let Some(ref nc) = opt { nc } else { return None };
+    Some(())
+}
+
+fn annotated(opt: Option<String>) -> Option<usize> {
+    // Only suggest borrowing, the annotation requires a reference
+    let s: &String = if let Some(ref s) = opt { s } else { return None };
+    Some(s.len())
+}
+
+fn main() {}
@@ -0,0 +1,93 @@
+#![warn(clippy::question_mark)]
+#![allow(dead_code)]
+
+use std::ops::ControlFlow;
+
+#[derive(Debug)]
+struct ParseError;
+
+#[derive(Debug)]
+struct AppError;
+
+impl From<ParseError> for AppError {
+    fn from(_: ParseError) -> Self {
+        AppError
+    }
+}
+
+fn check(x: u32) -> Result<(), ParseError> {
+    if x % 2 == 0 { Ok(()) } else { Err(ParseError) }
+}
+
+fn filter_map(items: &[Option<u32>]) -> Vec<u32> {
+    items
+        .iter()
+        .copied()
+        .filter_map(|x| {
+            if x.is_none() {
+                return None;
+            }
+            x.map(|x| x + 1)
+        })
+        .collect()
+}
+
+fn try_for_each_inferred(items: &[u32]) -> Result<(), AppError> {
+    items.iter().try_for_each(|&x| {
+        let checked = check(x);
+        // No warning, the error type of the closure couldn't be inferred once `?` converts it
+        if checked.is_err() {
+            return checked;
+        }
+        Ok(())
+    })?;
+    Ok(())
+}
+
+fn try_for_each_declared(items: &[u32]) -> Result<(), AppError> {
+    items.iter().try_for_each(|&x| -> Result<(), ParseError> {
+        let checked = check(x);
+        if checked.is_err() {
+            return checked;
+        }
+        Ok(())
+    })?;
+    Ok(())
+}
+
+fn try_for_each_named_elsewhere(items: &[u32]) -> Result<(), AppError> {
+    items.iter().try_for_each(|&x| {
+        let checked = check(x);
+        if checked.is_err() {
+            return checked;
+        }
+        if x > 10 {
+            return Err(ParseError);
+        }
+        Ok(())
+    })?;
+    Ok(())
+}
+
+fn try_for_each_option(items: &[Option<u32>]) -> Result<(), AppError> {
+    items.iter().copied().try_for_each(|x| {
+        // No warning, the error type of the closure couldn't be inferred once `?` converts it
+        if x.is_none() {
+            return Err(ParseError);
+        }
+        Ok(())
+    })?;
+    Ok(())
+}
+
+fn try_for_each_control_flow(items: &[Option<u32>]) -> ControlFlow<()> {
+    items.iter().copied().try_for_each(|x| {
+        // No warning, `?` can't produce `ControlFlow::Break(())` from a `None`
+        if x.is_none() {
+            return ControlFlow::Break(());
+        }
+        ControlFlow::Continue(())
+    })
+}
+
+fn main() {}
@@ -0,0 +1,45 @@
+#![warn(clippy::question_mark)]
+#![allow(dead_code)]
+
+fn parse(s: &str) -> Option<u32> {
+    s.parse().ok()
+}
+
+fn or(a: Option<u32>, b: Option<u32>) -> Option<u32> {
+    if a.or(b).is_none() {
+        return None;
+    }
+    Some(a.or(b).unwrap() + 1)
+}
+
+fn and_then(a: Option<&str>) -> Option<u32> {
+    if a.and_then(parse).is_none() {
+        return None;
+    }
+    Some(a.and_then(parse).unwrap() * 2)
+}
+
+fn xor_once(a: Option<u32>, b: Option<u32>) -> Option<u32> {
+    if a.xor(b).is_none() {
+        return None;
+    }
+    Some(0)
+}
+
+fn non_copy_moved(a: Option<String>, mut b: Option<String>) -> Option<String> {
+    // `a` and `b` are moved by the guard, and `b` is assigned again afterwards
+    if a.or(b).is_none() {
+        return None;
+    }
+    b = Some(String::new());
+    b
+}
+
+fn non_copy_unused(a: Option<String>, b: Option<String>) -> Option<()> {
+    if a.or(b).is_none() {
+        return None;
+    }
+    Some(())
+}
+
+fn main() {}
@@ -0,0 +1,82 @@
+#![warn(clippy::question_mark, clippy::partialeq_to_none, clippy::bool_comparison)]
+#![allow(dead_code)]
+
+fn eq_none(a: Option<u32>) -> Option<u32> {
+    if a == None {
+        return None;
+    }
+    a
+}
+
+fn none_eq(a: Option<u32>) -> Option<u32> {
+    if None == a {
+        return None;
+    }
+    a
+}
+
+fn is_none_eq_true(a: Option<u32>) -> Option<u32> {
+    if a.is_none() == true {
+        return None;
+    }
+    a
+}
+
+fn is_some_eq_false(a: Option<u32>) -> Option<u32> {
+    if a.is_some() == false {
+        return None;
+    }
+    a
+}
+
+fn is_err_eq_true(r: Result<u32, u32>) -> Result<u32, u32> {
+    if r.is_err() == true {
+        return r;
+    }
+    r
+}
+
+fn is_ok_eq_false(r: Result<u32, u32>) -> Result<u32, u32> {
+    if r.is_ok() == false {
+        return r;
+    }
+    r
+}
+
+// Not a guard, so the comparison lints still fire
+fn not_a_guard(a: Option<u32>) -> u32 {
+    if a == None {
+        return 0;
+    }
+    if a.is_none() == true {
+        return 0;
+    }
+    1
+}
+
+// `question_mark` skips functions with a non-Rust ABI, so the comparison lints fire instead
+extern "C" fn non_rust_abi(a: Option<&u32>) -> Option<&u32> {
+    if a == None {
+        return None;
+    }
+    if a.is_none() == true {
+        return None;
+    }
+    a
+}
+
+// The rewrite of the outer guard is blocked by the MSRV and `?` is banned for the inner one, so
+// the comparison is still linted
+#[clippy::msrv = "1.64"]
+#[warn(clippy::question_mark_used)]
+fn msrv_blocked_outer_guard(opt: Option<u32>, other: Option<u32>) -> Option<u32> {
+    if opt.is_none() {
+        if other == None {
+            return None;
+        }
+        return None;
+    }
+    opt
+}
+
+fn main() {}
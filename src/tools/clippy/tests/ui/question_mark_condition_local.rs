@@ -0,0 +1,48 @@
+#![warn(clippy::question_mark)]
+#![allow(dead_code)]
+
+fn hoisted(a: Option<u32>) -> Option<u32> {
+    let missing = a.is_none();
+    if missing {
+        return None;
+    }
+    a
+}
+
+fn hoisted_err(r: Result<u32, u8>, log: &mut Vec<&str>) -> Result<u32, u8> {
+    let failed = r.is_err();
+    log.push("checked");
+    if failed {
+        return r;
+    }
+    r
+}
+
+fn used_again(a: Option<u32>) -> Option<u32> {
+    let missing = a.is_none();
+    if missing {
+        return None;
+    }
+    println!("{missing}");
+    a
+}
+
+fn mutated_between(mut a: Option<u32>) -> Option<u32> {
+    let missing = a.is_none();
+    a = a.map(|x| x + 1);
+    if missing {
+        return None;
+    }
+    a
+}
+
+fn shadowed_between(a: Option<u32>) -> Option<u32> {
+    let missing = a.is_none();
+    let a = Some(1);
+    if missing {
+        return None;
+    }
+    a
+}
+
+fn main() {}
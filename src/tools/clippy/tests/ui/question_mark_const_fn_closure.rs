@@ -0,0 +1,34 @@
+#![warn(clippy::question_mark)]
+#![allow(dead_code)]
+
+// Closures created in a `const fn` run at runtime, so their guards can use `?`.
+const fn make_checker() -> impl Fn(Option<u32>) -> Option<u32> {
+    |x| {
+        if x.is_none() {
+            return None;
+        }
+        x
+    }
+}
+
+// `?` can't be used in the `const fn` itself.
+const fn checked(x: Option<u32>) -> Option<u32> {
+    if x.is_none() {
+        return None;
+    }
+    x
+}
+
+const fn make_both(x: Option<u32>) -> Option<impl Fn(Option<u32>) -> Option<u32>> {
+    if x.is_none() {
+        return None;
+    }
+    Some(|y| {
+        if y.is_none() {
+            return None;
+        }
+        y
+    })
+}
+
+fn main() {}
@@ -0,0 +1,53 @@
+#![warn(clippy::question_mark)]
+#![allow(dead_code, unused_assignments)]
+
+// Converted from a C function reporting success through a flag
+fn header_len(bytes: &[u8]) -> Option<usize> {
+    let mut ok = true;
+    let first = bytes.first();
+    if first.is_none() {
+        ok = false;
+        return None;
+    }
+    if ok { Some(bytes.len()) } else { None }
+}
+
+fn assigned_again(values: &[u32]) -> Option<u32> {
+    let mut found = false;
+    let first = values.first();
+    if first.is_none() {
+        found = false;
+        return None;
+    }
+    found = true;
+    if found { first.copied() } else { None }
+}
+
+fn captured(x: Option<u32>) -> Option<u32> {
+    let mut ok = true;
+    let report = move || ok;
+    if x.is_none() {
+        ok = false;
+        return None;
+    }
+    report().then_some(1)
+}
+
+struct Flag(bool);
+
+impl Drop for Flag {
+    fn drop(&mut self) {
+        println!("{}", self.0);
+    }
+}
+
+fn dropped(x: Option<u32>) -> Option<u32> {
+    let mut flag = Flag(true);
+    if x.is_none() {
+        flag = Flag(false);
+        return None;
+    }
+    x
+}
+
+fn main() {}
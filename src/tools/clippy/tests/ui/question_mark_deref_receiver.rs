@@ -0,0 +1,52 @@
+#![warn(clippy::question_mark)]
+#![allow(dead_code)]
+
+use std::borrow::Cow;
+use std::ops::Deref;
+
+struct Cache {
+    cached: Cow<'static, Option<String>>,
+    count: Cow<'static, Option<u32>>,
+}
+
+impl Cache {
+    fn cached(&self) -> Option<u32> {
+        if self.cached.is_none() {
+            return None;
+        }
+        Some(0)
+    }
+
+    fn count(&self) -> Option<u32> {
+        if self.count.is_none() {
+            return None;
+        }
+        Some(1)
+    }
+
+    fn explicit(&self) -> Option<u32> {
+        if (*self.cached).is_none() {
+            return None;
+        }
+        Some(2)
+    }
+}
+
+struct Wrapper(Option<String>);
+
+impl Deref for Wrapper {
+    type Target = Option<String>;
+
+    fn deref(&self) -> &Option<String> {
+        &self.0
+    }
+}
+
+fn wrapped(w: Wrapper) -> Option<usize> {
+    if w.is_none() {
+        return None;
+    }
+    Some(w.0.map_or(0, |s| s.len()))
+}
+
+fn main() {}
@@ -0,0 +1,41 @@
+#![warn(clippy::question_mark)]
+
+use std::ops::Deref;
+
+struct Wrapper(Option<u32>);
+
+impl Deref for Wrapper {
+    type Target = Option<u32>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Wrapper {
+    fn is_none(&self) -> bool {
+        self.0.is_none()
+    }
+}
+
+fn wrapper_is_none(w: Wrapper) -> Option<u32> {
+    // No warning, `Wrapper::is_none` isn't `Option::is_none`
+    if w.is_none() {
+        return None;
+    }
+    w.0
+}
+
+fn deref_is_none(w: &Wrapper) -> Option<u32> {
+    if (**w).is_none() {
+        return None;
+    }
+    **w
+}
+
+fn field_if_let(w: &Wrapper) -> Option<u32> {
+    let v = if let Some(v) = w.0 { v } else { return None };
+    Some(v)
+}
+
+fn main() {}
@@ -0,0 +1,36 @@
+#![warn(clippy::question_mark)]
+#![allow(dead_code, non_snake_case)]
+
+// Mimics the functions rustdoc wraps doctests ending in `Ok(())` in
+
+#[derive(Debug)]
+struct MyError;
+
+fn run() -> Result<(), MyError> {
+    Ok(())
+}
+
+fn main() {
+    fn _inner() -> Result<(), impl core::fmt::Debug> {
+        if let Err(e) = run() {
+            return Err(e);
+        }
+        Ok(())
+    }
+    fn _doctest_main_src_lib_rs_8_0() -> Result<(), impl core::fmt::Debug> {
+        println!("running");
+        if let Err(e) = run() {
+            return Err(e);
+        }
+        Ok(())
+    }
+    fn _doctest_main_src_lib_rs_20_0() -> Result<(), impl core::fmt::Debug> {
+        if let Err(e) = run() {
+            return Err(e);
+        }
+        Ok::<(), MyError>(())
+    }
+    _inner().unwrap();
+    _doctest_main_src_lib_rs_8_0().unwrap();
+    _doctest_main_src_lib_rs_20_0().unwrap();
+}
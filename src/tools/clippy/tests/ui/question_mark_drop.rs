@@ -0,0 +1,40 @@
+#![warn(clippy::question_mark)]
+#![allow(dead_code)]
+
+fn drop_after(opt: Option<String>) -> Option<()> {
+    if opt.is_none() {
+        return None;
+    }
+    drop(opt);
+    Some(())
+}
+
+fn drop_later(opt: Option<String>) -> Option<u32> {
+    if opt.is_none() {
+        return None;
+    }
+    let x = 1;
+    drop(opt);
+    Some(x)
+}
+
+fn reassigned(mut opt: Option<String>) -> Option<()> {
+    if opt.is_none() {
+        return None;
+    }
+    opt = None;
+    println!("{opt:?}");
+    Some(())
+}
+
+// `opt` is used before the `drop`, so the guard must only borrow it
+fn used_before_drop(opt: Option<String>) -> Option<usize> {
+    if opt.is_none() {
+        return None;
+    }
+    let len = opt.as_ref().map_or(0, String::len);
+    drop(opt);
+    Some(len)
+}
+
+fn main() {}
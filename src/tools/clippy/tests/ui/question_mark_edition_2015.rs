@@ -0,0 +1,23 @@
+//@edition:2015
+//@aux-build:question_mark_keyword_methods.rs
+#![warn(clippy::question_mark)]
+#![allow(dead_code)]
+
+extern crate question_mark_keyword_methods;
+use question_mark_keyword_methods::Obj;
+
+fn guard(obj: &Obj) -> Option<u32> {
+    if obj.try().is_none() {
+        return None;
+    }
+    Some(0)
+}
+
+fn let_else(obj: &Obj) -> Option<u32> {
+    let Some(async) = obj.try() else {
+        return None;
+    };
+    Some(async)
+}
+
+fn main() {}
@@ -0,0 +1,39 @@
+#![warn(clippy::question_mark)]
+#![allow(dead_code, unused_imports)]
+
+fn qualified(r: Result<u32, u32>) -> Result<u32, u32> {
+    if let Err(e) = r {
+        return Result::Err(e);
+    }
+    Ok(0)
+}
+
+fn fully_qualified(r: Result<u32, u32>) -> Result<u32, u32> {
+    if let Err(e) = r {
+        return std::result::Result::Err(e);
+    }
+    Ok(0)
+}
+
+fn renamed(r: Result<u32, u32>) -> Result<u32, u32> {
+    use std::result::Result::Err as E;
+
+    if let Err(e) = r {
+        return E(e);
+    }
+    Ok(0)
+}
+
+mod shadowed {
+    // Not `Result::Err`, so `?` can't be used
+    pub struct Err(pub u32);
+
+    pub fn shadowed(r: Result<u32, u32>) -> Err {
+        if let Result::Err(e) = r {
+            return Err(e);
+        }
+        Err(0)
+    }
+}
+
+fn main() {}
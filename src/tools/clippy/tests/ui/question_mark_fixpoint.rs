@@ -0,0 +1,58 @@
+//! The suggestions must not be linted by the lints their rewrites would trigger otherwise, so that
+//! `--fix` converges in a single pass.
+#![warn(clippy::question_mark)]
+#![deny(clippy::needless_question_mark, clippy::redundant_closure)]
+#![allow(dead_code, clippy::needless_return)]
+
+#[derive(Default)]
+struct Error {
+    code: u32,
+}
+
+fn make_error() -> Error {
+    Error { code: 1 }
+}
+
+fn tail(x: Option<u32>) -> Option<u32> {
+    if x.is_none() {
+        return None;
+    } else {
+        x
+    }
+}
+
+fn closure_tail(x: Option<u32>) -> Option<u32> {
+    let f = || {
+        if x.is_none() {
+            return None;
+        } else {
+            x
+        }
+    };
+    f()
+}
+
+fn not_tail(x: Option<u32>) -> Option<u32> {
+    let y = if x.is_none() {
+        return None;
+    } else {
+        x
+    };
+    y.map(|y| y + 1)
+}
+
+fn error_fn(x: Option<u32>) -> Result<u32, Error> {
+    if x.is_none() {
+        return Err(make_error());
+    }
+    Ok(1)
+}
+
+fn error_default(x: Option<u32>) -> Result<u32, Error> {
+    if x.is_none() {
+        return Err(Error::default());
+    }
+    Ok(1)
+}
+
+fn main() {}
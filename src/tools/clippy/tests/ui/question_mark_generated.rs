@@ -0,0 +1,29 @@
+//@aux-build:proc_macros.rs
+#![warn(clippy::question_mark)]
+#![allow(dead_code)]
+
+extern crate proc_macros;
+use proc_macros::external;
+
+external! {
+    fn from_external_macro(a: Option<u32>) -> Option<u32> {
+        if a.is_none() {
+            return None;
+        }
+        a
+    }
+}
+
+include!("auxiliary/question_mark_generated.rs");
+
+#[derive(Clone, PartialEq)]
+struct Derived(Option<u32>);
+
+fn handwritten(a: Option<u32>) -> Option<u32> {
+    if a.is_none() {
+        return None;
+    }
+    a
+}
+
+fn main() {}
@@ -0,0 +1,84 @@
+#![warn(clippy::question_mark)]
+
+fn debug_assert(opt: Option<u32>) -> Option<u32> {
+    if opt.is_none() {
+        debug_assert!(false, "unexpected");
+        return None;
+    }
+    opt.map(|x| x + 1)
+}
+
+fn counter(opt: Option<u32>, count: &mut u32) -> Option<u32> {
+    if opt.is_none() {
+        *count += 1;
+        return None;
+    }
+    opt
+}
+
+fn without_statements(opt: Option<u32>) -> Option<u32> {
+    if opt.is_none() {
+        return None;
+    }
+    opt
+}
+
+fn too_many_statements(opt: Option<u32>, count: &mut u32) -> Option<u32> {
+    if opt.is_none() {
+        *count += 1;
+        *count += 1;
+        *count += 1;
+        *count += 1;
+        return None;
+    }
+    opt
+}
+
+fn returns_something_else(opt: Option<u32>, count: &mut u32) -> Option<u32> {
+    if opt.is_none() {
+        *count += 1;
+        return Some(0);
+    }
+    opt
+}
+
+fn result(res: Result<u32, ()>, count: &mut u32) -> Result<u32, ()> {
+    if res.is_err() {
+        *count += 1;
+        return res;
+    }
+    res
+}
+
+#[clippy::msrv = "1.64"]
+fn msrv_without_let_else(opt: Option<u32>, count: &mut u32) -> Option<u32> {
+    // Linted without a suggestion, `let...else` is only available from 1.65 on
+    if opt.is_none() {
+        *count += 1;
+        return None;
+    }
+    opt
+}
+
+fn debug_only(opt: Option<u32>) -> Option<u32> {
+    if opt.is_none() {
+        if cfg!(debug_assertions) {
+            eprintln!("missing");
+        }
+        return None;
+    }
+    opt
+}
+
+fn conditional_return(opt: Option<u32>, count: &mut u32) -> Option<u32> {
+    // Only returns early in debug builds
+    if opt.is_none() {
+        *count += 1;
+        if cfg!(debug_assertions) {
+            return None;
+        }
+    }
+    opt
+}
+
+fn main() {}
@@ -0,0 +1,51 @@
+#![warn(clippy::question_mark)]
+#![allow(dead_code, clippy::useless_conversion)]
+
+type Found = Option<u32>;
+
+fn let_else(x: Option<u32>) -> Found {
+    let Some(v) = x else {
+        return Option::<u32>::None.into();
+    };
+    Some(v + 1)
+}
+
+fn is_none(x: Option<u32>) -> Option<u32> {
+    if x.is_none() {
+        return Into::into(Option::<u32>::None);
+    }
+    x
+}
+
+// `From<T> for Option<T>` turns `None` into `Some(None)`
+fn non_identity(x: Option<u32>) -> Option<Option<u32>> {
+    let Some(v) = x else {
+        return Option::<u32>::None.into();
+    };
+    Some(Some(v))
+}
+
+fn non_identity_if(x: Option<u32>) -> Option<Option<u32>> {
+    if x.is_none() {
+        return Option::<u32>::None.into();
+    }
+    Some(x)
+}
+
+#[must_use]
+struct Wrapper(Option<u32>);
+
+impl From<Option<u32>> for Wrapper {
+    fn from(value: Option<u32>) -> Self {
+        Self(value)
+    }
+}
+
+fn wrapper(x: Option<u32>) -> Wrapper {
+    let Some(v) = x else {
+        return Option::<u32>::None.into();
+    };
+    Wrapper(Some(v))
+}
+
+fn main() {}
@@ -0,0 +1,28 @@
+#![feature(never_type)]
+#![warn(clippy::question_mark)]
+#![allow(dead_code, clippy::unnecessary_wraps)]
+
+use std::convert::Infallible;
+
+fn infallible(r: Result<u32, Infallible>) -> Result<u32, Infallible> {
+    if r.is_err() {
+        return r;
+    }
+    Ok(1)
+}
+
+fn never(r: Result<String, !>) -> Result<u32, !> {
+    if let Err(e) = r {
+        return Err(e);
+    }
+    Ok(1)
+}
+
+fn fallible(r: Result<u32, u32>) -> Result<u32, u32> {
+    if r.is_err() {
+        return r;
+    }
+    Ok(1)
+}
+
+fn main() {}
@@ -0,0 +1,27 @@
+#![warn(clippy::question_mark)]
+
+fn run() -> Result<(), String> {
+    Ok(())
+}
+
+fn value() -> Option<u32> {
+    Some(1)
+}
+
+fn main() -> Result<(), String> {
+    #![allow(dead_code)]
+    if let Err(e) = run() {
+        return Err(e);
+    }
+    println!("done");
+    Ok(())
+}
+
+fn first() -> Option<u32> {
+    #![allow(unused_variables)]
+    // the value is checked right away
+    let Some(v) = value() else {
+        return None;
+    };
+    Some(v)
+}
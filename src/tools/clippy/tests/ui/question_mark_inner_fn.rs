@@ -0,0 +1,34 @@
+#![feature(try_blocks)]
+#![warn(clippy::question_mark)]
+#![allow(dead_code, clippy::unnecessary_wraps)]
+
+// Don't lint the guard of the `const fn` itself, but the one of the inner `fn`
+const fn outer_const(x: Option<u8>) -> Option<u8> {
+    fn helper(x: Option<u8>) -> Option<u8> {
+        if x.is_none() {
+            return None;
+        }
+        x
+    }
+
+    if x.is_none() {
+        return None;
+    }
+    x
+}
+
+fn outer_try(x: Option<u8>) -> Option<u8> {
+    let y: Option<u8> = try {
+        fn helper(x: Option<u8>) -> Option<u8> {
+            if x.is_none() {
+                return None;
+            }
+            x
+        }
+
+        helper(x)?
+    };
+    y
+}
+
+fn main() {}
@@ -0,0 +1,34 @@
+//@aux-build:macro_rules.rs
+#![warn(clippy::question_mark)]
+#![allow(unused_macros)]
+
+#[macro_use]
+extern crate macro_rules;
+
+macro_rules! local_bail_none {
+    () => {
+        return None
+    };
+}
+
+macro_rules! local_bail_none_stmt {
+    () => {
+        return None;
+    };
+}
+
+fn local_macro(opt: Option<u32>) -> Option<u32> {
+    let Some(x) = opt else { local_bail_none!() };
+    let Some(y) = opt else {
+        local_bail_none_stmt!();
+    };
+    Some(x + y)
+}
+
+fn external_macro(opt: Option<u32>) -> Option<u32> {
+    // Don't lint, the macro is defined in another crate
+    let Some(x) = opt else { bail_none!() };
+    Some(x)
+}
+
+fn main() {}
@@ -0,0 +1,30 @@
+#![warn(clippy::question_mark)]
+#![allow(clippy::redundant_pattern)]
+
+fn at_binding(opt: Option<u32>) -> Option<u32> {
+    // Don't lint, the range check would be lost
+    let Some(x @ 1..=9) = opt else { return None };
+    // Don't lint, `@` bindings are left alone
+    let Some(y @ _) = opt else { return None };
+    Some(x + y)
+}
+
+fn literal(opt: Option<u32>) -> Option<u32> {
+    // Don't lint, `let 0 = opt?;` is refutable
+    let Some(0) = opt else { return None };
+    Some(0)
+}
+
+fn tuple(opt: Option<(u32, u32)>) -> Option<u32> {
+    let Some((a, b)) = opt else { return None };
+    Some(a + b)
+}
+
+fn binding_modes(opt: Option<u32>) -> Option<u32> {
+    let Some(mut   x) = opt else { return None };
+    x += 1;
+    let Some(/* the value */ y) = opt else { return None };
+    Some(x + y)
+}
+
+fn main() {}
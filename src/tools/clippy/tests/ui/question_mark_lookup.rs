@@ -0,0 +1,75 @@
+#![warn(clippy::question_mark)]
+#![allow(
+    dead_code,
+    clippy::unnecessary_wraps,
+    clippy::unnecessary_get_then_check,
+    clippy::unnecessary_first_then_check
+)]
+
+use std::collections::HashMap;
+
+fn map_unwrap(map: &HashMap<u32, String>, k: u32) -> Option<usize> {
+    if map.get(&k).is_none() {
+        return None;
+    }
+    let v = map.get(&k).unwrap();
+    Some(v.len() + map.len())
+}
+
+fn map_index(map: &HashMap<u32, String>, k: u32) -> Option<usize> {
+    if map.get(&k).is_none() {
+        return None;
+    }
+    Some(map[&k].len())
+}
+
+fn slice_index(v: &[u32], i: usize) -> Option<u32> {
+    if v.get(i).is_none() {
+        return None;
+    }
+    let x = v[i];
+    Some(x + 1)
+}
+
+fn first(v: &[u32]) -> Option<u32> {
+    if v.first().is_none() {
+        return None;
+    }
+    Some(*v.first().unwrap() + 1)
+}
+
+fn get_mut(v: &mut Vec<u32>, i: usize) -> Option<()> {
+    if v.get_mut(i).is_none() {
+        return None;
+    }
+    *v.get_mut(i).unwrap() += 1;
+    Some(())
+}
+
+// The container is mutated between the guard and the reuse
+fn mutated(map: &mut HashMap<u32, String>, k: u32) -> Option<usize> {
+    if map.get(&k).is_none() {
+        return None;
+    }
+    map.insert(k + 1, String::new());
+    Some(map.get(&k).unwrap().len())
+}
+
+// The key is mutated between the guard and the reuse
+fn key_mutated(v: &[u32], mut i: usize) -> Option<u32> {
+    if v.get(i).is_none() {
+        return None;
+    }
+    i = 0;
+    Some(v[i])
+}
+
+// No later lookup, only the guard is rewritten
+fn no_reuse(map: &HashMap<u32, String>, k: u32) -> Option<()> {
+    if map.get(&k).is_none() {
+        return None;
+    }
+    Some(())
+}
+
+fn main() {}
@@ -0,0 +1,39 @@
+#![warn(clippy::question_mark)]
+#![allow(clippy::manual_let_else, clippy::never_loop, dead_code)]
+
+fn collapse(opt: Option<u32>) -> Option<u32> {
+    let v = loop {
+        if opt.is_none() {
+            return None;
+        }
+        break opt.unwrap();
+    };
+    Some(v + 1)
+}
+
+fn collapse_result(res: Result<String, ()>) -> Result<String, ()> {
+    let s = loop {
+        if res.is_err() {
+            return res;
+        }
+        break res.unwrap();
+    };
+    Ok(s + "!")
+}
+
+// The `unwrap` isn't paired with the guard across the `break`
+fn no_pairing_across_break(opt: Option<String>, early: bool) -> Option<String> {
+    let s = loop {
+        if opt.is_none() {
+            return None;
+        }
+        if early {
+            break String::new();
+        }
+        let s = opt.unwrap();
+        break s;
+    };
+    Some(s)
+}
+
+fn main() {}
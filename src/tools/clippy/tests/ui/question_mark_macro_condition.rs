@@ -0,0 +1,45 @@
+#![warn(clippy::question_mark)]
+#![allow(dead_code)]
+
+fn load() -> Option<u32> {
+    None
+}
+
+macro_rules! missing {
+    () => {
+        load().is_none()
+    };
+    ($e:expr) => {
+        $e.is_none()
+    };
+}
+
+macro_rules! failed {
+    ($e:expr) => {
+        $e.is_err()
+    };
+}
+
+// The whole condition is expanded from a single macro call, so there's no receiver to suggest
+fn whole_condition() -> Option<u32> {
+    if missing!() {
+        return None;
+    }
+    Some(1)
+}
+
+fn whole_condition_with_argument(x: Option<u32>) -> Option<u32> {
+    if missing!(x) {
+        return None;
+    }
+    x
+}
+
+fn whole_condition_result(r: Result<u32, String>) -> Result<u32, String> {
+    if failed!(r) {
+        return r;
+    }
+    Ok(2)
+}
+
+fn main() {}
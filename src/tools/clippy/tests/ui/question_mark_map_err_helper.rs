@@ -0,0 +1,98 @@
+#![warn(clippy::question_mark)]
+#![allow(dead_code, clippy::redundant_closure, clippy::unnecessary_wraps)]
+
+#[derive(Debug)]
+struct StepError;
+
+#[derive(Debug)]
+struct WrappedError;
+
+fn step() -> Result<(), StepError> {
+    Ok(())
+}
+
+fn wrap(e: StepError) -> WrappedError {
+    let _ = e;
+    WrappedError
+}
+
+fn wrap_with(context: &str, e: StepError) -> WrappedError {
+    let _ = (context, e);
+    WrappedError
+}
+
+struct Runner {
+    name: String,
+    failures: u32,
+}
+
+impl Runner {
+    fn wrap(&self, e: StepError) -> WrappedError {
+        let _ = (&self.name, e);
+        WrappedError
+    }
+
+    fn wrap_counted(&mut self, e: StepError) -> WrappedError {
+        self.failures += 1;
+        let _ = e;
+        WrappedError
+    }
+
+    fn method(&self) -> Result<(), WrappedError> {
+        if let Err(e) = step() {
+            return Err(self.wrap(e));
+        }
+        Ok(())
+    }
+
+    fn counted(&mut self) -> Result<(), WrappedError> {
+        if let Err(err) = step() {
+            return Err(self.wrap_counted(err));
+        }
+        Ok(())
+    }
+
+    fn counted_used_later(&mut self) -> Result<u32, WrappedError> {
+        if let Err(e) = step() {
+            return Err(self.wrap_counted(e));
+        }
+        Ok(self.failures)
+    }
+}
+
+fn function() -> Result<(), WrappedError> {
+    if let Err(e) = step() {
+        return Err(wrap(e));
+    }
+    Ok(())
+}
+
+fn function_with_context(name: &str) -> Result<(), WrappedError> {
+    if let Err(e) = step() {
+        return Err(wrap_with(name, e));
+    }
+    Ok(())
+}
+
+fn other_argument_calls(name: &str) -> Result<(), WrappedError> {
+    if let Err(e) = step() {
+        return Err(wrap_with(&name.to_uppercase(), e));
+    }
+    Ok(())
+}
+
+fn binding_not_passed() -> Result<(), WrappedError> {
+    if let Err(_e) = step() {
+        return Err(WrappedError);
+    }
+    Ok(())
+}
+
+fn with_question_mark(name: Option<&str>) -> Result<(), WrappedError> {
+    if let Err(e) = step() {
+        return Err(wrap_with(name.ok_or(WrappedError)?, e));
+    }
+    Ok(())
+}
+
+fn main() {}
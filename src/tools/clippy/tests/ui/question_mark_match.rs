@@ -0,0 +1,81 @@
+#![warn(clippy::question_mark_match)]
+#![allow(clippy::manual_let_else, dead_code)]
+
+fn some_none(a: Option<u32>) -> Option<u32> {
+    let b = match a {
+        Some(b) => b,
+        None => return None,
+    };
+    Some(b + 1)
+}
+
+fn none_some(a: Option<u32>) -> Option<u32> {
+    let b = match a {
+        None => return None,
+        Some(b) => b,
+    };
+    Some(b + 1)
+}
+
+fn ok_err(a: Result<u32, String>) -> Result<u32, String> {
+    let b = match a {
+        Ok(b) => b,
+        Err(e) => return Err(e),
+    };
+    Ok(b + 1)
+}
+
+struct Wrapper {
+    inner: Option<String>,
+}
+
+fn by_ref(a: &Wrapper) -> Option<usize> {
+    let b = match a.inner {
+        Some(ref b) => b,
+        None => return None,
+    };
+    Some(b.len())
+}
+
+fn stmt(a: Option<u32>) -> Option<u32> {
+    match a {
+        Some(b) => b,
+        None => return None,
+    };
+    Some(0)
+}
+
+// A wildcard `None` arm is only accepted with `matches-for-question-mark = "AllTypes"`
+fn wildcard(a: Option<u32>) -> Option<u32> {
+    let b = match a {
+        Some(b) => b,
+        _ => return None,
+    };
+    Some(b + 1)
+}
+
+fn guarded(a: Option<u32>) -> Option<u32> {
+    let b = match a {
+        Some(b) if b > 1 => b,
+        _ => return None,
+    };
+    Some(b + 1)
+}
+
+fn different_err(a: Result<u32, String>) -> Result<u32, String> {
+    let b = match a {
+        Ok(b) => b,
+        Err(_) => return Err(String::new()),
+    };
+    Ok(b + 1)
+}
+
+fn not_returning(a: Option<u32>) -> Option<u32> {
+    let b = match a {
+        Some(b) => b,
+        None => 0,
+    };
+    Some(b + 1)
+}
+
+fn main() {}
@@ -0,0 +1,34 @@
+#![warn(clippy::question_mark, clippy::question_mark_match)]
+#![allow(clippy::manual_let_else, dead_code)]
+
+// `if` guards are still linted when only `match` expressions are allowed
+#[allow(clippy::question_mark_match)]
+fn match_allowed(a: Option<u32>) -> Option<u32> {
+    if a.is_none() {
+        return None;
+    }
+    let b = match a {
+        Some(b) => b,
+        None => return None,
+    };
+    Some(b)
+}
+
+// `match` expressions are still linted when only the other shapes are allowed
+mod question_mark_allowed {
+    #![warn(clippy::style)]
+    #![allow(clippy::question_mark)]
+
+    fn f(a: Option<u32>) -> Option<u32> {
+        if a.is_none() {
+            return None;
+        }
+        let b = match a {
+            Some(b) => b,
+            None => return None,
+        };
+        Some(b)
+    }
+}
+
+fn main() {}
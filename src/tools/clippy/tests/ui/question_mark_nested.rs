@@ -0,0 +1,45 @@
+#![warn(clippy::question_mark)]
+#![allow(dead_code, clippy::unnecessary_wraps)]
+
+fn step() -> Result<u32, String> {
+    Ok(1)
+}
+
+// `?` can't produce `Ok(None)`, so this isn't linted
+fn ok_none(inner: Option<u32>) -> Result<Option<u32>, String> {
+    if inner.is_none() {
+        return Ok(None);
+    }
+    Ok(inner)
+}
+
+fn err_in_nested(x: u32) -> Result<Option<u32>, String> {
+    if let Err(e) = step() {
+        return Err(e);
+    }
+    Ok(Some(x))
+}
+
+fn outer_same(outer: Result<Option<u32>, u32>) -> Result<Option<u32>, u32> {
+    if outer.is_err() {
+        return outer;
+    }
+    outer
+}
+
+// The payloads don't line up, so the suggestion isn't machine applicable
+fn outer_different(outer: Result<Option<u32>, u32>) -> Result<u32, u32> {
+    if let Err(e) = outer {
+        return Err(e);
+    }
+    Ok(0)
+}
+
+fn option_of_result(opt: Option<Result<u32, u32>>) -> Option<u32> {
+    if opt.is_none() {
+        return None;
+    }
+    Some(0)
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+#![warn(clippy::question_mark)]
+
+// Only the outer guard is linted, its `else` block keeps the inner one as written
+fn nested_in_else(opt: Option<u32>, fallback: Option<u32>) -> Option<u32> {
+    if opt.is_none() {
+        if fallback.is_none() {
+            return None;
+        }
+        eprintln!("no value");
+        return None;
+    }
+    opt
+}
+
+fn main() {}
@@ -0,0 +1,29 @@
+#![warn(clippy::question_mark, clippy::question_mark_used)]
+#![allow(dead_code)]
+
+#[no_implicit_prelude]
+mod no_prelude {
+    use ::std::option::Option::{self, None};
+    use ::std::task::Poll;
+
+    pub struct Countdown {
+        remaining: Option<u32>,
+    }
+
+    impl Countdown {
+        pub fn poll_next(&mut self) -> Poll<Option<u32>> {
+            if self.remaining.is_none() {
+                return Poll::Ready(None);
+            }
+            self.remaining = self.remaining.and_then(|n| n.checked_sub(1));
+            Poll::Ready(self.remaining)
+        }
+    }
+
+    pub fn first(v: &[u32]) -> Option<u32> {
+        let x = *v.first()?;
+        Option::Some(x)
+    }
+}
+
+fn main() {}
@@ -0,0 +1,56 @@
+//@compile-flags: -C debug-assertions=off
+#![warn(clippy::question_mark)]
+#![allow(clippy::manual_let_else, dead_code, unused_unsafe)]
+
+fn guard(opciones: Option<u32>) -> Option<u32> {
+    // año
+    if opciones.is_none() { return None; } // año
+    opciones
+}
+
+fn guard_with_semicolon(größe: Option<u32>) -> Option<u32> {
+    if größe.is_none() { return None; }; // größe
+    größe
+}
+
+fn let_else(número: Option<u32>) -> Option<u32> {
+    let Some(n) = número else { return None }; // número
+    Some(n)
+}
+
+fn if_let(café: Option<u32>) -> Option<u32> {
+    let v = if let Some(v) = café { v } else { return None }; // café
+    Some(v)
+}
+
+fn inner_attr(ñandú: Option<u32>) -> Option<u32> {
+    #![allow(unused_variables)]
+    // ñandú
+    if ñandú.is_none() {
+        return None;
+    }
+    ñandú
+}
+
+fn cfg_sibling(über: Option<u32>) -> Option<u32> {
+    if über.is_none() {
+        return None;
+    }
+    #[cfg(debug_assertions)] // über
+    dbg!(über); // über
+    über
+}
+
+fn cfg_sibling_in_unsafe(ö: Option<u32>) -> Option<u32> {
+    unsafe {
+        #[cfg(debug_assertions)]
+        dbg!(ö); // ö
+        if ö.is_none() {
+            return None;
+        }
+        let _ = ö;
+    }
+    ö
+}
+
+fn main() {}
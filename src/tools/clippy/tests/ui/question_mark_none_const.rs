@@ -0,0 +1,42 @@
+#![warn(clippy::question_mark)]
+#![allow(dead_code)]
+
+struct Foo(u32);
+
+const NONE: Option<Foo> = None;
+const SOME: Option<Foo> = Some(Foo(0));
+
+fn local_const(opt: Option<u32>) -> Option<Foo> {
+    if opt.is_none() {
+        return NONE;
+    }
+    Some(Foo(opt.unwrap()))
+}
+
+struct Bar;
+
+impl Bar {
+    const NONE: Option<u32> = None;
+
+    fn assoc_const(&self, opt: Option<u32>) -> Option<u32> {
+        if opt.is_none() {
+            return Self::NONE;
+        }
+        opt
+    }
+}
+
+fn if_let_assoc_const(opt: Option<u32>) -> Option<u32> {
+    let x = if let Some(x) = opt { x } else { return Bar::NONE };
+    Some(x + 1)
+}
+
+// Not `None`, so this can't be rewritten
+fn some_const(opt: Option<u32>) -> Option<Foo> {
+    if opt.is_none() {
+        return SOME;
+    }
+    Some(Foo(opt.unwrap()))
+}
+
+fn main() {}
@@ -0,0 +1,89 @@
+#![warn(clippy::question_mark)]
+#![allow(dead_code, clippy::unnecessary_wraps)]
+
+#[derive(Debug)]
+struct Error {
+    code: u32,
+    msg: String,
+}
+
+impl Error {
+    fn new(code: u32, msg: String) -> Self {
+        Self { code, msg }
+    }
+}
+
+struct Context {
+    name: String,
+}
+
+fn msg(ctx: &Context) -> String {
+    ctx.name.clone()
+}
+
+struct Missing;
+
+fn describe(_: Option<u32>) -> String {
+    String::new()
+}
+
+fn literal(a: Option<u32>) -> Result<u32, &'static str> {
+    if a.is_none() {
+        return Err("missing");
+    }
+    Ok(0)
+}
+
+fn unit_struct(a: Option<u32>) -> Result<u32, Missing> {
+    if a.is_none() {
+        return Err(Missing);
+    }
+    Ok(0)
+}
+
+fn constructed(a: Option<u32>, code: u32, ctx: &Context) -> Result<u32, Error> {
+    if a.is_none() {
+        return Err(Error::new(code, msg(ctx)));
+    }
+    Ok(a.unwrap())
+}
+
+fn borrowed_later(a: Option<u32>, ctx: Context) -> Result<String, Error> {
+    if a.is_none() {
+        return Err(Error::new(0, msg(&ctx)));
+    }
+    Ok(ctx.name)
+}
+
+fn not_copy(a: Option<String>) -> Result<usize, &'static str> {
+    if a.is_none() {
+        return Err("missing");
+    }
+    Ok(a.unwrap().len())
+}
+
+// `name` is moved by the error, but still used after the guard
+fn moved_and_used_later(a: Option<u32>, name: String) -> Result<String, Error> {
+    if a.is_none() {
+        return Err(Error::new(0, name));
+    }
+    Ok(name)
+}
+
+// The error refers to the option itself
+fn refers_to_option(a: Option<u32>) -> Result<u32, String> {
+    if a.is_none() {
+        return Err(describe(a));
+    }
+    Ok(0)
+}
+
+// The error contains a `?`
+fn contains_try(a: Option<u32>, b: Result<u32, Error>) -> Result<u32, Error> {
+    if a.is_none() {
+        return Err(Error::new(b?, String::new()));
+    }
+    Ok(0)
+}
+
+fn main() {}
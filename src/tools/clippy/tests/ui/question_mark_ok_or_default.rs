@@ -0,0 +1,55 @@
+#![warn(clippy::question_mark)]
+#![allow(clippy::default_trait_access, dead_code)]
+
+struct UnitError;
+
+impl Default for UnitError {
+    fn default() -> Self {
+        UnitError
+    }
+}
+
+#[derive(Default)]
+struct DerivedError {
+    code: u32,
+}
+
+fn unit_struct(a: Option<u32>) -> Result<u32, UnitError> {
+    if a.is_none() {
+        return Err(Default::default());
+    }
+    Ok(a.unwrap())
+}
+
+fn derived(a: Option<u32>) -> Result<u32, DerivedError> {
+    if a.is_none() {
+        return Err(DerivedError::default());
+    }
+    Ok(a.unwrap())
+}
+
+fn derived_untyped(a: Option<u32>) -> Result<u32, DerivedError> {
+    if a.is_none() {
+        return Err(Default::default());
+    }
+    Ok(a.unwrap())
+}
+
+fn generic_error(a: Option<u32>) -> Result<u32, Vec<u8>> {
+    if a.is_none() {
+        return Err(Default::default());
+    }
+    Ok(a.unwrap())
+}
+
+// Don't lint: the error type can't be named
+fn in_closure(a: Option<u32>) {
+    let _ = || {
+        if a.is_none() {
+            return Err::<(), UnitError>(Default::default());
+        }
+        Ok(())
+    };
+}
+
+fn main() {}
@@ -0,0 +1,46 @@
+#![warn(clippy::question_mark)]
+#![allow(dead_code)]
+
+// Stands in for `anyhow::anyhow!`
+macro_rules! anyhow {
+    ($($arg:tt)*) => {
+        format!($($arg)*)
+    };
+}
+
+macro_rules! bail {
+    ($($arg:tt)*) => {
+        return Err(anyhow!($($arg)*))
+    };
+}
+
+macro_rules! try_format {
+    ($e:expr) => {
+        format!("{}", $e?)
+    };
+}
+
+fn missing(opt: Option<u32>, name: &str) -> Result<u32, String> {
+    if opt.is_none() {
+        return Err(anyhow!("missing {}",   name));
+    }
+    Ok(0)
+}
+
+fn bailing(opt: Option<u32>, name: &str) -> Result<u32, String> {
+    // Don't lint, the whole `return` comes from the macro
+    if opt.is_none() {
+        bail!("missing {}", name);
+    }
+    Ok(0)
+}
+
+fn with_try(opt: Option<u32>, name: Option<&str>) -> Result<u32, String> {
+    // Don't lint, the `?` in the macro would then apply to the closure
+    if opt.is_none() {
+        return Err(try_format!(name.ok_or(String::new())));
+    }
+    Ok(0)
+}
+
+fn main() {}
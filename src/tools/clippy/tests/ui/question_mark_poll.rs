@@ -0,0 +1,78 @@
+#![warn(clippy::question_mark)]
+#![allow(dead_code)]
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+trait Stream {
+    type Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>>;
+}
+
+struct Lines {
+    inner: Option<Vec<String>>,
+}
+
+impl Stream for Lines {
+    type Item = String;
+
+    fn poll_next(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Option<String>> {
+        if self.inner.is_none() {
+            return Poll::Ready(None);
+        }
+        let inner = self.inner.as_mut().unwrap();
+        Poll::Ready(inner.pop())
+    }
+}
+
+struct Countdown {
+    remaining: Option<u32>,
+}
+
+impl Stream for Countdown {
+    type Item = u32;
+
+    fn poll_next(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Option<u32>> {
+        if self.remaining.is_none() {
+            return Poll::Ready(None);
+        }
+        self.remaining = self.remaining.and_then(|n| n.checked_sub(1));
+        Poll::Ready(self.remaining)
+    }
+}
+
+struct Connect {
+    result: Result<u32, String>,
+}
+
+impl Future for Connect {
+    type Output = Result<u32, String>;
+
+    fn poll(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.result.is_err() {
+            return Poll::Ready(Err(String::from("failed")));
+        }
+        Poll::Ready(Ok(1))
+    }
+}
+
+struct Old {
+    inner: Option<u32>,
+}
+
+impl Stream for Old {
+    type Item = u32;
+
+    // Linted without a suggestion, `let...else` is only available from 1.65 on
+    #[clippy::msrv = "1.64"]
+    fn poll_next(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Option<u32>> {
+        if self.inner.is_none() {
+            return Poll::Ready(None);
+        }
+        Poll::Ready(self.inner)
+    }
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+//@aux-build:proc_macro_attr.rs
+#![warn(clippy::question_mark)]
+#![allow(dead_code)]
+
+extern crate proc_macro_attr;
+
+// No warning, the guard is generated and only has the span of the function name
+#[proc_macro_attr::guard_first_param]
+fn checked(opt: Option<u32>) -> Option<u32> {
+    opt.map(|v| v + 1)
+}
+
+fn main() {}
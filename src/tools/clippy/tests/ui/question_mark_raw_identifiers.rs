@@ -0,0 +1,33 @@
+//@aux-build:question_mark_keyword_methods.rs
+#![warn(clippy::question_mark)]
+#![allow(dead_code)]
+
+extern crate question_mark_keyword_methods;
+use question_mark_keyword_methods::Obj;
+
+fn describe(code: u32) -> String {
+    code.to_string()
+}
+
+fn guard(obj: &Obj) -> Option<u32> {
+    if obj.r#try().is_none() {
+        return None;
+    }
+    Some(0)
+}
+
+fn let_else(obj: &Obj) -> Option<u32> {
+    let Some(r#async) = obj.r#try() else {
+        return None;
+    };
+    Some(r#async)
+}
+
+fn wrapped(res: Result<u32, u32>) -> Result<u32, String> {
+    if let Err(r#try) = res {
+        return Err(describe(r#try));
+    }
+    Ok(0)
+}
+
+fn main() {}
@@ -0,0 +1,90 @@
+#![warn(clippy::question_mark)]
+#![allow(dead_code)]
+
+struct Node {
+    idx: Option<u32>,
+    name: Option<String>,
+}
+
+impl Node {
+    #[inline(always)]
+    fn idx(&self) -> Option<u32> {
+        self.idx
+    }
+
+    #[inline(always)]
+    fn name(&self) -> Option<String> {
+        self.name.clone()
+    }
+
+    #[inline(always)]
+    fn name_ref(&self) -> &Option<String> {
+        &self.name
+    }
+
+    #[inline(always)]
+    fn name_mut(&mut self) -> &mut Option<String> {
+        &mut self.name
+    }
+
+    fn copy_getter(&self) -> Option<u32> {
+        if self.idx().is_none() {
+            return None;
+        }
+        let _ = if let Some(idx) = self.idx() {
+            idx
+        } else {
+            return None;
+        };
+        self.idx()
+    }
+
+    fn owned_getter(&self) -> Option<String> {
+        if self.name().is_none() {
+            return None;
+        }
+        let _ = if let Some(name) = self.name() {
+            name
+        } else {
+            return None;
+        };
+        self.name()
+    }
+
+    fn ref_getter(&self) -> Option<usize> {
+        if self.name_ref().is_none() {
+            return None;
+        }
+        let _ = if let Some(name) = self.name_ref() {
+            name
+        } else {
+            return None;
+        };
+        self.name.as_ref().map(String::len)
+    }
+
+    fn mut_getter(&mut self) -> Option<()> {
+        let _ = if let Some(name) = self.name_mut() {
+            name
+        } else {
+            return None;
+        };
+        Some(())
+    }
+
+    fn copy_place(&self) -> Option<u32> {
+        if self.idx.is_none() {
+            return None;
+        }
+        self.idx
+    }
+
+    fn non_copy_place(&self) -> Option<usize> {
+        if self.name.is_none() {
+            return None;
+        }
+        self.name.as_ref().map(String::len)
+    }
+}
+
+fn main() {}
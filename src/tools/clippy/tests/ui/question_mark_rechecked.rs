@@ -0,0 +1,44 @@
+#![warn(clippy::question_mark_rechecked)]
+#![allow(dead_code, clippy::question_mark, clippy::partialeq_to_none)]
+
+fn nested_option(a: Option<Option<u32>>) -> Option<u32> {
+    let x = a?;
+    if x.is_none() {
+        return None;
+    }
+    x
+}
+
+fn nested_result(r: Result<Result<u32, String>, String>) -> Result<u32, String> {
+    let x = r?;
+    if x.is_err() {
+        return Err(String::new());
+    }
+    x
+}
+
+fn tail(a: Option<Option<u32>>) -> Option<u32> {
+    let x = a?;
+    if x == None { None } else { x }
+}
+
+// Another statement in between, the check is probably intended
+fn not_directly_after(a: Option<Option<u32>>) -> Option<u32> {
+    let x = a?;
+    println!("{x:?}");
+    if x.is_none() {
+        return None;
+    }
+    x
+}
+
+// Not the unwrapped binding
+fn other_binding(a: Option<u32>, b: Option<u32>) -> Option<u32> {
+    let x = a?;
+    if b.is_none() {
+        return None;
+    }
+    Some(x)
+}
+
+fn main() {}
@@ -0,0 +1,78 @@
+#![warn(clippy::question_mark)]
+#![allow(dead_code)]
+
+struct MyError;
+
+mod allowed {
+    #![allow(clippy::redundant_pattern_matching)]
+
+    use super::MyError;
+
+    fn option(opt: Option<u32>) -> Option<u32> {
+        if let None = opt {
+            return None;
+        }
+        opt
+    }
+
+    fn result(r: Result<u32, u32>) -> Result<u32, u32> {
+        if let Err(_) = r {
+            return r;
+        }
+        Ok(0)
+    }
+
+    fn option_some(opt: Option<u32>) -> Option<u32> {
+        if let Some(_) = opt {
+        } else {
+            return None;
+        }
+        opt
+    }
+
+    fn result_fresh_err(r: Result<u32, u32>) -> Result<u32, MyError> {
+        if let Err(_) = r {
+            return Err(MyError);
+        }
+        Ok(0)
+    }
+}
+
+mod warned {
+    #![warn(clippy::redundant_pattern_matching)]
+
+    use super::MyError;
+
+    // Only `redundant_pattern_matching` lints these, the guards it suggests are linted next
+    fn option(opt: Option<u32>) -> Option<u32> {
+        if let None = opt {
+            return None;
+        }
+        opt
+    }
+
+    fn result(r: Result<u32, u32>) -> Result<u32, u32> {
+        if let Err(_) = r {
+            return r;
+        }
+        Ok(0)
+    }
+
+    // Only `question_mark` lints these, the guards `redundant_pattern_matching` suggests aren't
+    fn option_some(opt: Option<u32>) -> Option<u32> {
+        if let Some(_) = opt {
+        } else {
+            return None;
+        }
+        opt
+    }
+
+    fn result_fresh_err(r: Result<u32, u32>) -> Result<u32, MyError> {
+        if let Err(_) = r {
+            return Err(MyError);
+        }
+        Ok(0)
+    }
+}
+
+fn main() {}
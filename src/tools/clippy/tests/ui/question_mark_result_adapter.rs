@@ -0,0 +1,40 @@
+#![warn(clippy::question_mark)]
+#![allow(dead_code, clippy::bool_comparison)]
+
+fn err_is_some(r: Result<u32, u8>) -> Result<u32, u8> {
+    if r.err().is_some() {
+        return r;
+    }
+    Ok(1)
+}
+
+fn ok_is_none(r: Result<u32, u8>) -> Result<u32, u8> {
+    if r.ok().is_none() {
+        return r;
+    }
+    r
+}
+
+fn err_is_some_eq_true(r: Result<u32, u8>) -> Result<u32, u8> {
+    if r.err().is_some() == true {
+        return r;
+    }
+    Ok(2)
+}
+
+// These check for the success case
+fn err_is_none(r: Result<u32, u8>) -> Result<u32, u8> {
+    if r.err().is_none() {
+        return r;
+    }
+    Ok(3)
+}
+
+fn ok_is_some(r: Result<u32, u8>) -> Result<u32, u8> {
+    if r.ok().is_some() {
+        return r;
+    }
+    Ok(4)
+}
+
+fn main() {}
@@ -0,0 +1,27 @@
+#![warn(clippy::question_mark)]
+#![allow(dead_code)]
+
+struct Query {
+    filter: Option<String>,
+    limit: Option<u32>,
+}
+
+impl Query {
+    // `self` is returned whole, so `self.filter` must not be moved out of
+    fn refine(self) -> Option<Self> {
+        if self.filter.is_none() {
+            return None;
+        }
+        Some(self)
+    }
+
+    // Copying the field out doesn't move out of `self`
+    fn limited(self) -> Option<Self> {
+        if self.limit.is_none() {
+            return None;
+        }
+        Some(self)
+    }
+}
+
+fn main() {}
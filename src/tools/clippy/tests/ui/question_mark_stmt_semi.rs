@@ -0,0 +1,29 @@
+#![warn(clippy::question_mark)]
+#![allow(dead_code)]
+
+// Guards followed by a semicolon of their own
+
+fn guard(a: Option<u32>) -> Option<u32> {
+    if a.is_none() {
+        return None;
+    };
+    a
+}
+
+fn if_let(a: Option<u32>) -> Option<u32> {
+    if let Some(x) = a {
+        x
+    } else {
+        return None;
+    };
+    Some(0)
+}
+
+fn guard_without_semi(a: Option<u32>) -> Option<u32> {
+    if a.is_none() {
+        return None;
+    }
+    a
+}
+
+fn main() {}
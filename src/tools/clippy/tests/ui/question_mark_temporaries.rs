@@ -0,0 +1,39 @@
+#![warn(clippy::question_mark)]
+#![allow(dead_code)]
+
+use std::sync::Mutex;
+
+struct State {
+    value: Option<u32>,
+}
+
+static STATE: Mutex<State> = Mutex::new(State { value: None });
+
+fn boxed() -> Box<State> {
+    Box::new(State { value: None })
+}
+
+// The guard is now held until the end of the statement
+fn locked() -> Option<u32> {
+    if STATE.lock().unwrap().value.is_none() {
+        return None;
+    }
+    Some(1)
+}
+
+fn place(state: &State) -> Option<u32> {
+    if state.value.is_none() {
+        return None;
+    }
+    Some(1)
+}
+
+// `Box` only deallocates, so the drop order doesn't matter
+fn insignificant() -> Option<u32> {
+    if boxed().value.is_none() {
+        return None;
+    }
+    Some(1)
+}
+
+fn main() {}
@@ -0,0 +1,49 @@
+#![warn(clippy::question_mark)]
+#![allow(dead_code)]
+
+fn parse(s: Option<&str>) -> Option<Result<u32, u8>> {
+    s.map(|s| s.parse().map_err(|_| 0))
+}
+
+fn local(s: Option<&str>) -> Result<u32, u8> {
+    let maybe_result = parse(s);
+    if let Some(Err(e)) = maybe_result {
+        return Err(e);
+    }
+    Ok(0)
+}
+
+fn call(s: Option<&str>) -> Result<u32, u8> {
+    if let Some(Err(e)) = parse(s) {
+        return Err(e);
+    }
+    Ok(1)
+}
+
+fn reused(s: Option<&str>) -> Result<u32, u8> {
+    // No warning, `maybe_result` is used afterwards with its original nesting
+    let maybe_result = parse(s);
+    if let Some(Err(e)) = maybe_result {
+        return Err(e);
+    }
+    Ok(maybe_result.map_or(0, |r| r.unwrap_or_default()))
+}
+
+fn other_error(s: Option<&str>) -> Result<u32, u8> {
+    // No warning, the error is replaced
+    if let Some(Err(_)) = parse(s) {
+        return Err(1);
+    }
+    Ok(2)
+}
+
+#[clippy::msrv = "1.32"]
+fn msrv_1_32(s: Option<&str>) -> Result<u32, u8> {
+    // Linted without a suggestion, `Option::transpose` is only available from 1.33 on
+    if let Some(Err(e)) = parse(s) {
+        return Err(e);
+    }
+    Ok(3)
+}
+
+fn main() {}
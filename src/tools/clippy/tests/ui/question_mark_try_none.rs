@@ -0,0 +1,38 @@
+#![warn(clippy::question_mark)]
+#![allow(dead_code)]
+
+fn then_none(x: Option<u32>) -> Option<u32> {
+    if x.is_none() {
+        None?
+    }
+    Some(1)
+}
+
+fn else_none(x: Option<u32>) -> Option<u32> {
+    let v = if let Some(v) = x { v } else { None? };
+    Some(v + 1)
+}
+
+fn then_err(r: Result<u32, String>) -> Result<u32, String> {
+    if let Err(e) = r {
+        Err(e)?
+    }
+    Ok(1)
+}
+
+// Not the `None` we are checking for
+fn other_option(x: Option<u32>, y: Option<()>) -> Option<u32> {
+    if x.is_none() {
+        y?
+    }
+    x
+}
+
+fn some(x: Option<u32>) -> Option<u32> {
+    if x.is_none() {
+        Some(())?
+    }
+    x
+}
+
+fn main() {}
@@ -0,0 +1,52 @@
+#![warn(clippy::question_mark)]
+#![allow(dead_code)]
+
+struct Config;
+struct Invalid;
+
+fn check(_: &Config) -> Result<(), Invalid> {
+    Ok(())
+}
+
+fn wrap(e: Invalid) -> Invalid {
+    e
+}
+
+fn validate_twice(c: &Config) -> Result<(), Invalid> {
+    if check(c).is_err() {
+        return check(c);
+    }
+    Ok(())
+}
+
+fn validate_once(c: &Config) -> Result<(), Invalid> {
+    if let Err(e) = check(c) {
+        return Err(e);
+    }
+    Ok(())
+}
+
+fn present(opt: Option<()>) -> Option<()> {
+    if opt.is_none() {
+        return None;
+    }
+    Some(())
+}
+
+fn validate_both(a: &Config, b: &Config) -> Result<(), Invalid> {
+    // The guard isn't the whole body
+    if let Err(e) = check(a) {
+        return Err(e);
+    }
+    check(b)
+}
+
+fn validate_mapped(c: &Config) -> Result<(), Invalid> {
+    // The error is wrapped, so returning the result of `check` would change it
+    if let Err(e) = check(c) {
+        return Err(wrap(e));
+    }
+    Ok(())
+}
+
+fn main() {}
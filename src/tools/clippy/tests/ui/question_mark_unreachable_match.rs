@@ -0,0 +1,77 @@
+#![warn(clippy::question_mark)]
+#![allow(dead_code, clippy::match_wild_err_arm)]
+
+fn consume(_: String) {}
+
+fn statement(name: Option<String>) -> Option<()> {
+    if name.is_none() {
+        return None;
+    }
+    match name {
+        Some(name) => consume(name),
+        None => unreachable!(),
+    }
+    Some(())
+}
+
+fn statement_multiple_uses(opt: Option<u32>) -> Option<u32> {
+    let mut total = 0;
+    if opt.is_none() {
+        return None;
+    }
+    match opt {
+        Some(v) => total += v * v,
+        None => unreachable!("checked above"),
+    }
+    Some(total)
+}
+
+fn value(opt: Option<u32>) -> Option<u32> {
+    if opt.is_none() {
+        return None;
+    }
+    let doubled = match opt {
+        Some(v) => v * 2,
+        None => unreachable!(),
+    };
+    Some(doubled)
+}
+
+fn tail(res: Result<u32, String>) -> Result<u32, String> {
+    if res.is_err() {
+        return res;
+    }
+    match res {
+        Ok(v) => Ok(v + 1),
+        Err(_) => panic!("checked above"),
+    }
+}
+
+// `g()` would run before `?` returns
+fn g() -> u32 {
+    1
+}
+
+fn side_effect_first(opt: Option<u32>) -> Option<u32> {
+    if opt.is_none() {
+        return None;
+    }
+    match opt {
+        Some(v) => Some(g() + v),
+        None => unreachable!(),
+    }
+}
+
+// The `None` arm isn't dead code
+fn reachable_arm(opt: Option<u32>) -> Option<()> {
+    if opt.is_none() {
+        return None;
+    }
+    match opt {
+        Some(v) => println!("{v}"),
+        None => println!("none"),
+    }
+    Some(())
+}
+
+fn main() {}
@@ -0,0 +1,51 @@
+#![warn(clippy::question_mark)]
+#![deny(unused_unsafe)]
+#![allow(dead_code, unused_labels)]
+
+unsafe fn unsafe_guard(opt: Option<u32>) -> Option<u32> {
+    if opt.is_none() {
+        unsafe {
+            return None;
+        }
+    }
+    opt
+}
+
+unsafe fn unsafe_if_let(r: Result<u32, u32>) -> Result<u32, u32> {
+    let v = if let Ok(v) = r {
+        v
+    } else {
+        unsafe { return r }
+    };
+    Ok(v)
+}
+
+fn nested_block(opt: Option<u32>) -> Option<u32> {
+    if opt.is_none() {
+        {
+            return None;
+        }
+    }
+    opt
+}
+
+fn inside_labeled_block(opt: Option<u32>) -> Option<u32> {
+    'a: {
+        if opt.is_none() {
+            return None;
+        }
+    }
+    opt
+}
+
+fn labeled_block(opt: Option<u32>) -> Option<u32> {
+    // Don't lint, the labeled block is kept around
+    if opt.is_none() {
+        'a: {
+            return None;
+        }
+    }
+    opt
+}
+
+fn main() {}
@@ -0,0 +1,80 @@
+#![warn(clippy::question_mark)]
+#![allow(dead_code)]
+
+mod list_import {
+    use std::option::Option::{None, Some};
+
+    fn f(a: Option<u32>) -> Option<u32> {
+        let v = if let Some(v) = a { v } else { return None };
+        Some(v)
+    }
+}
+
+mod single_import {
+    use std::option::Option::None;
+
+    fn f(a: Option<u32>) -> Option<u32> {
+        if a.is_none() {
+            return None;
+        }
+        a
+    }
+}
+
+mod err_import {
+    use std::result::Result::Err;
+
+    fn f(r: Result<u32, u32>) -> Result<u32, u32> {
+        if let Err(e) = r {
+            return Err(e);
+        }
+        r
+    }
+}
+
+mod still_used {
+    use std::option::Option::None;
+
+    fn f(a: Option<u32>) -> Option<u32> {
+        if a.is_none() {
+            return None;
+        }
+        if a == Some(0) { None } else { a }
+    }
+}
+
+mod partially_used {
+    use std::option::Option::{None, Some};
+
+    fn f(a: Option<u32>) -> Option<u32> {
+        if a.is_none() {
+            return None;
+        }
+        Some(1)
+    }
+}
+
+mod reexported {
+    pub(crate) use std::option::Option::None;
+
+    fn f(a: Option<u32>) -> Option<u32> {
+        if a.is_none() {
+            return None;
+        }
+        a
+    }
+}
+
+mod with_other_edits {
+    use std::option::Option::None;
+
+    fn f(a: Option<String>) -> Option<()> {
+        if a.is_none() {
+            return None;
+        }
+        drop(a);
+        Some(())
+    }
+}
+
+fn main() {}
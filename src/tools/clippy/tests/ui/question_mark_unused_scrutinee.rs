@@ -0,0 +1,54 @@
+#![warn(clippy::question_mark)]
+#![allow(dead_code)]
+
+use std::io::Write;
+
+fn boxed_unused(out: Option<Box<dyn Write>>) -> Option<()> {
+    if out.is_none() {
+        return None;
+    }
+    Some(())
+}
+
+fn boxed_used_later(mut out: Option<Box<dyn Write>>) -> Option<()> {
+    if out.is_none() {
+        return None;
+    }
+    let _ = out.take();
+    Some(())
+}
+
+fn string_unused(name: Option<String>) -> Option<usize> {
+    if name.is_none() {
+        return None;
+    }
+    Some(0)
+}
+
+fn string_used_later(name: Option<String>) -> Option<usize> {
+    if name.is_none() {
+        return None;
+    }
+    Some(name.map_or(0, |name| name.len()))
+}
+
+// Moving `name` would conflict with the borrow
+fn borrowed_before(name: Option<String>) -> Option<usize> {
+    let r = &name;
+    if name.is_none() {
+        return None;
+    }
+    Some(r.as_ref().map_or(0, String::len))
+}
+
+// `name` can't be moved in every iteration
+fn in_loop(name: Option<String>) -> Option<()> {
+    for _ in 0..2 {
+        if name.is_none() {
+            return None;
+        }
+    }
+    Some(())
+}
+
+fn main() {}
@@ -0,0 +1,56 @@
+#![warn(clippy::question_mark)]
+#![allow(dead_code)]
+
+fn dependent(opt: Option<u32>) -> Option<u32> {
+    if opt.is_none() {
+        return None;
+    }
+    if opt.unwrap() > 10 {
+        return None;
+    }
+    Some(1)
+}
+
+fn dependent_method(name: Option<String>) -> Option<usize> {
+    if name.is_none() {
+        return None;
+    }
+    if name.expect("checked above").is_empty() {
+        return None;
+    }
+    Some(2)
+}
+
+// Not the only use, so the suggestion isn't machine applicable
+fn used_later(opt: Option<u32>) -> Option<u32> {
+    if opt.is_none() {
+        return None;
+    }
+    if opt.unwrap() % 2 == 0 {
+        return None;
+    }
+    opt
+}
+
+fn independent(opt: Option<u32>, limit: u32) -> Option<u32> {
+    if opt.is_none() {
+        return None;
+    }
+    if limit > 10 {
+        return None;
+    }
+    opt
+}
+
+fn lazy_operand(opt: Option<u32>, check: bool) -> Option<u32> {
+    if opt.is_none() {
+        return None;
+    }
+    // Only unwrapped if `check` holds, so `?` can't go here
+    if check && opt.unwrap() > 10 {
+        return None;
+    }
+    Some(3)
+}
+
+fn main() {}
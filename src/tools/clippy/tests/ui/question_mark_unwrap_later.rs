@@ -0,0 +1,48 @@
+#![warn(clippy::question_mark)]
+#![allow(dead_code)]
+
+struct Config {
+    verbose: bool,
+}
+
+fn inspect(_: &Option<Config>) {}
+
+fn adjacent(cfg: Option<Config>) -> Option<bool> {
+    if cfg.is_none() {
+        return None;
+    }
+    let cfg = cfg.unwrap();
+    Some(cfg.verbose)
+}
+
+fn after_unrelated_statements(cfg: Option<Config>, name: &str) -> Option<bool> {
+    if cfg.is_none() {
+        return None;
+    }
+    let len = name.len();
+    println!("{name}");
+    let doubled = len * 2;
+    let config: Config = cfg.expect("checked above");
+    Some(config.verbose && doubled > 0)
+}
+
+fn used_in_between(cfg: Option<Config>) -> Option<bool> {
+    if cfg.is_none() {
+        return None;
+    }
+    inspect(&cfg);
+    let cfg = cfg.unwrap();
+    Some(cfg.verbose)
+}
+
+fn closure_in_between(cfg: Option<Config>) -> Option<bool> {
+    let check = || inspect(&cfg);
+    if cfg.is_none() {
+        return None;
+    }
+    check();
+    let cfg = cfg.unwrap();
+    Some(cfg.verbose)
+}
+
+fn main() {}
@@ -1,4 +1,3 @@
-// non rustfixable
 #![allow(unreachable_code)]
 #![allow(dead_code)]
 #![warn(clippy::question_mark_used)]
@@ -9,7 +8,7 @@ fn other_function() -> Option<i32> {
 
 fn my_function() -> Option<i32> {
     other_function()?;
-    //~^ ERROR: question mark operator was used
+    //~^ ERROR: the `?` operator here propagates `None` out of `fn my_function`
     None
 }
 
@@ -0,0 +1,59 @@
+#![warn(clippy::question_mark)]
+#![allow(dead_code)]
+
+pub struct Config {
+    name: Option<String>,
+    count: u32,
+}
+
+impl Config {
+    // `self` is only used by the guard itself, the field can be moved out of
+    pub fn unused(self) -> Option<()> {
+        if self.name.is_none() {
+            return None;
+        }
+        Some(())
+    }
+
+    // Uses preceding the guard don't matter
+    pub fn used_before(self) -> Option<u32> {
+        let count = self.count;
+        if self.name.is_none() {
+            return None;
+        }
+        Some(count)
+    }
+
+    pub fn read(self) -> Option<u32> {
+        if self.name.is_none() {
+            return None;
+        }
+        let count = self.count;
+        Some(count)
+    }
+
+    pub fn written(mut self) -> Option<()> {
+        if self.name.is_none() {
+            return None;
+        }
+        self.count += 1;
+        Some(())
+    }
+
+    pub fn moved(self) -> Option<Self> {
+        if self.name.is_none() {
+            return None;
+        }
+        Some(self)
+    }
+
+    pub fn captured(self) -> Option<u32> {
+        if self.name.is_none() {
+            return None;
+        }
+        let count = || self.count;
+        Some(count())
+    }
+}
+
+fn main() {}
@@ -0,0 +1,47 @@
+#![warn(clippy::question_mark)]
+#![deny(clippy::question_mark_used)]
+#![allow(dead_code)]
+
+fn if_let(x: Option<u32>) -> Option<u32> {
+    let v = if let Some(v) = x { v } else { return None };
+    Some(v + 1)
+}
+
+fn match_expr(x: Option<u32>) -> Option<u32> {
+    let v = match x {
+        Some(v) => v,
+        None => return None,
+    };
+    Some(v + 1)
+}
+
+// Only the `?` operator would improve these
+fn guard(x: Option<u32>) -> Option<u32> {
+    if x.is_none() {
+        return None;
+    }
+    x
+}
+
+fn let_else(x: Option<u32>) -> Option<u32> {
+    let Some(v) = x else {
+        return None;
+    };
+    Some(v)
+}
+
+fn match_err(r: Result<u32, String>) -> Result<u32, String> {
+    let v = match r {
+        Ok(v) => v,
+        Err(e) => return Err(e),
+    };
+    Ok(v + 1)
+}
+
+#[allow(clippy::question_mark_used)]
+fn allowed(x: Option<u32>) -> Option<u32> {
+    let v = if let Some(v) = x { v } else { return None };
+    Some(v + 1)
+}
+
+fn main() {}
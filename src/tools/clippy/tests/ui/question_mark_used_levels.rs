@@ -0,0 +1,85 @@
+#![feature(stmt_expr_attributes)]
+#![warn(clippy::question_mark)]
+#![allow(dead_code)]
+
+#[deny(clippy::question_mark_used)]
+fn fn_deny_stmt_allow(x: Option<u32>) -> Option<u32> {
+    #[allow(clippy::question_mark_used)]
+    let Some(v) = x else {
+        return None;
+    };
+    Some(v)
+}
+
+#[allow(clippy::question_mark_used)]
+fn fn_allow_stmt_deny(x: Option<u32>) -> Option<u32> {
+    #[deny(clippy::question_mark_used)]
+    let Some(v) = x else {
+        return None;
+    };
+    Some(v)
+}
+
+#[deny(clippy::question_mark_used)]
+fn fn_deny_guard_allow(x: Option<u32>) -> Option<u32> {
+    #[allow(clippy::question_mark_used)]
+    if x.is_none() {
+        return None;
+    }
+    x
+}
+
+#[allow(clippy::question_mark_used)]
+fn fn_allow_guard_deny(x: Option<u32>) -> Option<u32> {
+    #[deny(clippy::question_mark_used)]
+    if x.is_none() {
+        return None;
+    }
+    x
+}
+
+#[deny(clippy::question_mark_used)]
+fn fn_deny_expr_allow(x: Option<u32>) -> Option<u32> {
+    let v = #[allow(clippy::question_mark_used)]
+    if let Some(v) = x {
+        v
+    } else {
+        return None;
+    };
+    Some(v)
+}
+
+#[allow(clippy::question_mark_used)]
+fn fn_allow_expr_deny(x: Option<u32>) -> Option<u32> {
+    let v = #[deny(clippy::question_mark_used)]
+    if let Some(v) = x {
+        v
+    } else {
+        return None;
+    };
+    Some(v)
+}
+
+fn stmt_deny_expr_allow(x: Option<u32>) -> Option<u32> {
+    #[deny(clippy::question_mark_used)]
+    let v = #[allow(clippy::question_mark_used)]
+    if let Some(v) = x {
+        v
+    } else {
+        return None;
+    };
+    Some(v)
+}
+
+fn stmt_allow_expr_deny(x: Option<u32>) -> Option<u32> {
+    #[allow(clippy::question_mark_used)]
+    let v = #[deny(clippy::question_mark_used)]
+    if let Some(v) = x {
+        v
+    } else {
+        return None;
+    };
+    Some(v)
+}
+
+fn main() {}
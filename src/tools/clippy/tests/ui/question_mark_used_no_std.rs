@@ -0,0 +1,38 @@
+#![warn(clippy::question_mark_used)]
+#![allow(dead_code)]
+#![no_std]
+#![feature(lang_items)]
+
+use core::panic::PanicInfo;
+
+#[lang = "eh_personality"]
+extern "C" fn eh_personality() {}
+
+#[panic_handler]
+fn panic(_info: &PanicInfo) -> ! {
+    loop {}
+}
+
+struct ParseError;
+
+impl From<u8> for ParseError {
+    fn from(_: u8) -> Self {
+        ParseError
+    }
+}
+
+fn checked(x: u32) -> Result<u32, u8> {
+    x.checked_add(1).ok_or(0)
+}
+
+fn parse(x: u32) -> Result<u32, ParseError> {
+    let x = checked(x)?;
+    Ok(x)
+}
+
+fn first(v: &[u32]) -> Option<u32> {
+    let x = *v.first()?;
+    Some(x)
+}
+
+fn main() {}
@@ -0,0 +1,38 @@
+#![warn(clippy::question_mark)]
+#![allow(dead_code)]
+
+use std::rc::Weak;
+
+struct Node {
+    parent: Weak<Node>,
+    depth: u32,
+}
+
+impl Node {
+    fn parent_depth(&self) -> Option<u32> {
+        if self.parent.upgrade().is_none() {
+            return None;
+        }
+        let p = self.parent.upgrade().unwrap();
+        Some(p.depth)
+    }
+
+    fn parent_depth_inline(&self) -> Option<u32> {
+        if self.parent.upgrade().is_none() {
+            return None;
+        }
+        Some(self.parent.upgrade().unwrap().depth + 1)
+    }
+
+    fn detach(&mut self) -> Option<u32> {
+        if self.parent.upgrade().is_none() {
+            return None;
+        }
+        // The parent is gone by the time it's upgraded again, so the upgrades can't be merged
+        self.parent = Weak::new();
+        let p = self.parent.upgrade().unwrap();
+        Some(p.depth)
+    }
+}
+
+fn main() {}
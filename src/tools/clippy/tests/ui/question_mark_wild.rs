@@ -0,0 +1,38 @@
+#![warn(clippy::question_mark)]
+#![allow(dead_code)]
+
+struct MyError;
+
+fn option_copy(opt: Option<u32>) -> Option<u32> {
+    if let Some(_) = opt {
+    } else {
+        return None;
+    }
+    Some(0)
+}
+
+fn option_rest(opt: Option<String>) -> Option<usize> {
+    if let Some(..) = opt {
+    } else {
+        return None;
+    }
+    Some(opt.unwrap().len())
+}
+
+fn result_fresh_err(r: Result<u32, String>) -> Result<u32, MyError> {
+    if let Err(_) = r {
+        return Err(MyError);
+    }
+    Ok(r.unwrap())
+}
+
+fn then_not_empty(opt: Option<u32>) -> Option<u32> {
+    if let Some(_) = opt {
+        println!("some");
+    } else {
+        return None;
+    }
+    Some(0)
+}
+
+fn main() {}
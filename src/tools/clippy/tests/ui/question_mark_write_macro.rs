@@ -0,0 +1,38 @@
+#![warn(clippy::question_mark)]
+#![allow(dead_code)]
+
+use std::fmt::{self, Write};
+
+struct Point(u32, u32);
+
+macro_rules! double {
+    ($e:expr) => {
+        $e.checked_mul(2).ok_or(fmt::Error)
+    };
+}
+
+impl fmt::Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Err(err) = write!(f, "({}, ", self.0) {
+            return Err(err);
+        }
+        if let Err(err) = double!(self.1) {
+            return Err(err);
+        }
+        if let Err(err) = writeln!(f, "{})", self.1) {
+            return Err(err);
+        }
+        Ok(())
+    }
+}
+
+fn write_all(out: &mut String, items: &[u32]) -> fmt::Result {
+    for item in items {
+        if let Err(err) = write!(out, "{item},") {
+            return Err(err);
+        }
+    }
+    Ok(())
+}
+
+fn main() {}
@@ -0,0 +1,120 @@
+//! Checks `clippy_utils::usage::used_after_stmt` on the functions of
+//! `tests/used_after_stmt/cases.rs`. Each function calls `guard` on its parameter, and its name
+//! starts with the `UsageKind` expected for the parameter after that call.
+
+#![feature(rustc_private)]
+#![warn(rust_2018_idioms, unused_lifetimes)]
+
+extern crate rustc_driver;
+extern crate rustc_hir;
+extern crate rustc_interface;
+extern crate rustc_lint;
+extern crate rustc_session;
+extern crate rustc_span;
+
+use clippy_utils::usage::{UsageKind, used_after_stmt};
+use rustc_hir::def_id::LocalDefId;
+use rustc_hir::intravisit::FnKind;
+use rustc_hir::{Body, ExprKind, FnDecl, QPath, StmtKind};
+use rustc_interface::interface;
+use rustc_lint::{LateContext, LateLintPass};
+use rustc_session::impl_lint_pass;
+use rustc_span::Span;
+use std::env;
+use std::path::Path;
+use std::sync::Mutex;
+use test_utils::IS_RUSTC_TEST_SUITE;
+
+mod test_utils;
+
+/// The name of each function of the fixture, and the use found after its call to `guard`.
+static RESULTS: Mutex<Vec<(String, UsageKind)>> = Mutex::new(Vec::new());
+
+fn main() {
+    if IS_RUSTC_TEST_SUITE {
+        return;
+    }
+
+    let fixture = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/used_after_stmt/cases.rs");
+    let mut args = vec![
+        "rustc".to_owned(),
+        "--crate-type=lib".to_owned(),
+        "--edition=2021".to_owned(),
+        "--emit=metadata".to_owned(),
+        "--out-dir".to_owned(),
+        env!("CARGO_TARGET_TMPDIR").to_owned(),
+        fixture.display().to_string(),
+    ];
+    if let Ok(sysroot) = env::var("SYSROOT") {
+        args.extend(["--sysroot".to_owned(), sysroot]);
+    }
+    let result = rustc_driver::RunCompiler::new(&args, &mut UsedAfterStmtCallbacks).run();
+    assert!(result.is_ok(), "`{}` doesn't compile", fixture.display());
+
+    let results = RESULTS.lock().unwrap();
+    assert!(!results.is_empty(), "no functions found in `{}`", fixture.display());
+    for (name, usage) in &*results {
+        let expected = match name.split('_').next() {
+            Some("unused") => UsageKind::Unused,
+            Some("read") => UsageKind::Read,
+            Some("written") => UsageKind::Written,
+            Some("moved") => UsageKind::Moved,
+            _ => panic!("`{name}` doesn't start with the expected use"),
+        };
+        assert_eq!(*usage, expected, "wrong use found in `{name}`");
+    }
+}
+
+struct UsedAfterStmtCallbacks;
+
+impl rustc_driver::Callbacks for UsedAfterStmtCallbacks {
+    fn config(&mut self, config: &mut interface::Config) {
+        config.register_lints = Some(Box::new(|_, lint_store| {
+            lint_store.register_late_pass(|_| Box::new(UsedAfterStmtPass));
+        }));
+    }
+}
+
+struct UsedAfterStmtPass;
+
+impl_lint_pass!(UsedAfterStmtPass => []);
+
+impl<'tcx> LateLintPass<'tcx> for UsedAfterStmtPass {
+    fn check_fn(
+        &mut self,
+        cx: &LateContext<'tcx>,
+        kind: FnKind<'tcx>,
+        _: &'tcx FnDecl<'tcx>,
+        body: &'tcx Body<'tcx>,
+        _: Span,
+        _: LocalDefId,
+    ) {
+        let FnKind::ItemFn(ident, ..) = kind else {
+            return;
+        };
+        if ident.name.as_str() == "guard" {
+            return;
+        }
+        let (ExprKind::Block(block, _), [param]) = (body.value.kind, body.params) else {
+            panic!("`{ident}` doesn't have a block body and a single parameter");
+        };
+        let guard_idx = block
+            .stmts
+            .iter()
+            .position(|stmt| {
+                if let StmtKind::Semi(call) = stmt.kind
+                    && let ExprKind::Call(callee, _) = call.kind
+                    && let ExprKind::Path(QPath::Resolved(None, path)) = callee.kind
+                {
+                    path.segments
+                        .iter()
+                        .any(|segment| segment.ident.name.as_str() == "guard")
+                } else {
+                    false
+                }
+            })
+            .unwrap_or_else(|| panic!("`{ident}` doesn't call `guard`"));
+        let usage = used_after_stmt(cx, param.pat.hir_id, block, guard_idx);
+        RESULTS.lock().unwrap().push((ident.to_string(), usage));
+    }
+}
@@ -0,0 +1,107 @@
+// The cases checked by `tests/used-after-stmt.rs`. Every function calls `guard` on its parameter,
+// and the name of the function starts with the use of the parameter expected after that call.
+
+#![allow(unused)]
+
+fn guard<T>(_: &T) {}
+
+pub struct S {
+    a: String,
+    b: u32,
+}
+
+pub fn unused(x: S) {
+    guard(&x);
+}
+
+pub fn unused_only_before(x: S) {
+    let _ = x.a.len();
+    guard(&x);
+}
+
+pub fn read_copy_field(x: S) -> u32 {
+    guard(&x);
+    let b = x.b;
+    b
+}
+
+pub fn read_borrow(x: S) {
+    guard(&x);
+    let _ = &x.a;
+}
+
+pub fn read_method_call(x: S) {
+    guard(&x);
+    x.a.len();
+}
+
+pub fn read_tail(x: S) -> u32 {
+    guard(&x);
+    x.b
+}
+
+pub fn written_assign(mut x: S) {
+    guard(&x);
+    x.b = 1;
+}
+
+pub fn written_assign_op(mut x: S) {
+    guard(&x);
+    x.b += 1;
+}
+
+pub fn written_borrow(mut x: S) {
+    guard(&x);
+    let _ = &mut x.a;
+}
+
+pub fn written_method_call(mut x: S) {
+    guard(&x);
+    x.a.push('a');
+}
+
+pub fn moved(x: S) -> S {
+    guard(&x);
+    x
+}
+
+pub fn moved_field(x: S) -> String {
+    guard(&x);
+    x.a
+}
+
+pub fn moved_after_read(x: S) -> S {
+    guard(&x);
+    let _b = x.b;
+    x
+}
+
+pub fn read_by_closure(x: S) {
+    guard(&x);
+    let f = || x.a.len();
+    f();
+}
+
+pub fn read_by_move_closure_of_copy_field(x: S) {
+    guard(&x);
+    let f = move || x.b;
+    f();
+}
+
+pub fn written_by_closure(mut x: S) {
+    guard(&x);
+    let mut f = || x.b += 1;
+    f();
+}
+
+pub fn moved_by_move_closure(x: S) {
+    guard(&x);
+    let f = move || x.a.len();
+    f();
+}
+
+pub fn moved_by_closure(x: S) {
+    guard(&x);
+    let f = || drop(x);
+    f();
+}
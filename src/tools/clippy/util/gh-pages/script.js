@@ -517,7 +517,11 @@ function scrollToLint(lintId) {
         return;
     }
     target.scrollIntoView();
-    expandLint(lintId);
+    // The anchor may also be a section of the documentation of a lint
+    const lint = target.closest("article");
+    if (lint) {
+        expandLint(lint.id);
+    }
 }
 
 // If the page we arrive on has link to a given lint, we scroll to it.